@@ -0,0 +1,35 @@
+-- Migration: Moderator messages (hint releases and announcements) with a per-participant inbox
+
+DO $$ BEGIN
+    CREATE TYPE challenge_message_type AS ENUM ('HINT', 'ANNOUNCEMENT');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+-- Messages sent by a moderator within a challenge
+CREATE TABLE IF NOT EXISTS challenge_messages (
+    message_id SERIAL PRIMARY KEY,
+    challenge_id INTEGER NOT NULL,
+    sender_user_id INTEGER REFERENCES users(user_id),
+    message_type challenge_message_type NOT NULL,
+    waypoint_sequence INTEGER,
+    message_body TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+-- Delivery of a message to a single participant; polled by the participant app
+CREATE TABLE IF NOT EXISTS participant_inbox (
+    message_id INTEGER REFERENCES challenge_messages(message_id) ON DELETE CASCADE,
+    participant_id UUID REFERENCES challenge_participants(participant_id) ON DELETE CASCADE,
+    delivered_at TIMESTAMP WITH TIME ZONE,
+    read_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    PRIMARY KEY (message_id, participant_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_challenge_messages_challenge_id ON challenge_messages(challenge_id);
+CREATE INDEX IF NOT EXISTS idx_participant_inbox_participant ON participant_inbox(participant_id);
+
+-- current_waypoint_id is reused to hold the participant's current waypoint_sequence now that
+-- waypoints are embedded in the challenge JSON
+COMMENT ON COLUMN challenge_participants.current_waypoint_id IS 'waypoint_sequence of the waypoint currently presented to the participant';
@@ -0,0 +1,44 @@
+-- Migration: Device token registration and a persistent push notification outbox
+
+DO $$ BEGIN
+    CREATE TYPE device_platform AS ENUM ('FCM', 'APNS', 'LOCAL');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+DO $$ BEGIN
+    CREATE TYPE notification_status AS ENUM ('PENDING', 'SENT', 'FAILED');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+-- Push tokens registered by a user's devices
+CREATE TABLE IF NOT EXISTS device_tokens (
+    device_id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(user_id) ON DELETE CASCADE,
+    platform device_platform NOT NULL,
+    device_token TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    last_seen_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    UNIQUE (platform, device_token)
+);
+
+-- One row per notification per device; drained by the notification dispatcher
+CREATE TABLE IF NOT EXISTS notification_outbox (
+    notification_id SERIAL PRIMARY KEY,
+    device_id INTEGER NOT NULL REFERENCES device_tokens(device_id) ON DELETE CASCADE,
+    challenge_id INTEGER,
+    title TEXT NOT NULL,
+    body TEXT NOT NULL,
+    data JSONB NOT NULL DEFAULT '{}',
+    status notification_status NOT NULL DEFAULT 'PENDING',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    last_error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    sent_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_device_tokens_user_id ON device_tokens(user_id);
+CREATE INDEX IF NOT EXISTS idx_notification_outbox_pending ON notification_outbox(next_attempt_at)
+    WHERE status = 'PENDING';
@@ -0,0 +1,23 @@
+-- Migration: Email invitations to challenges, for registered and not yet registered users
+
+DO $$ BEGIN
+    CREATE TYPE invitation_status AS ENUM ('PENDING', 'ACCEPTED', 'DECLINED');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+CREATE TABLE IF NOT EXISTS challenge_invitations (
+    invitation_id SERIAL PRIMARY KEY,
+    challenge_id INTEGER NOT NULL,
+    email VARCHAR(255) NOT NULL,
+    invited_user_id INTEGER REFERENCES users(user_id) ON DELETE SET NULL,
+    invited_by INTEGER REFERENCES users(user_id),
+    status invitation_status NOT NULL DEFAULT 'PENDING',
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    responded_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    UNIQUE (challenge_id, email)
+);
+
+CREATE INDEX IF NOT EXISTS idx_challenge_invitations_email ON challenge_invitations(email);
+CREATE INDEX IF NOT EXISTS idx_challenge_invitations_user ON challenge_invitations(invited_user_id);
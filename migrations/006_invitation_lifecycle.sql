@@ -0,0 +1,20 @@
+-- Migration: Invitation lifecycle - revoked and expired invitations, participants only join by accepting
+
+ALTER TYPE invitation_status ADD VALUE IF NOT EXISTS 'REVOKED';
+ALTER TYPE invitation_status ADD VALUE IF NOT EXISTS 'EXPIRED';
+
+-- Nickname suggested by the moderator, used when the invitee does not choose one
+ALTER TABLE challenge_invitations ADD COLUMN IF NOT EXISTS participant_nickname VARCHAR(100);
+
+-- Participants added before invitations existed count as accepted invitees
+INSERT INTO challenge_invitations (challenge_id, email, invited_user_id, status, expires_at,
+                                   responded_at, participant_nickname)
+SELECT p.challenge_id, lower(u.username), p.user_id, 'ACCEPTED', COALESCE(p.joined_at, NOW()),
+       COALESCE(p.joined_at, NOW()), p.participant_nickname
+FROM challenge_participants p
+JOIN users u ON u.user_id = p.user_id
+WHERE p.challenge_id IS NOT NULL
+ON CONFLICT (challenge_id, email) DO NOTHING;
+
+CREATE INDEX IF NOT EXISTS idx_challenge_invitations_challenge_status
+    ON challenge_invitations(challenge_id, status);
@@ -0,0 +1,24 @@
+-- Migration: Participant groups - several users playing a challenge as one participant
+
+CREATE TABLE IF NOT EXISTS participant_groups (
+    group_id SERIAL PRIMARY KEY,
+    challenge_id INTEGER NOT NULL,
+    participant_id UUID NOT NULL UNIQUE REFERENCES challenge_participants(participant_id) ON DELETE CASCADE,
+    group_name VARCHAR(100) NOT NULL,
+    join_code VARCHAR(16) NOT NULL UNIQUE,
+    captain_user_id INTEGER NOT NULL REFERENCES users(user_id),
+    max_size INTEGER NOT NULL CHECK (max_size > 0),
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+-- A user belongs to at most one group per challenge; the captain is a member as well
+CREATE TABLE IF NOT EXISTS participant_group_members (
+    group_id INTEGER NOT NULL REFERENCES participant_groups(group_id) ON DELETE CASCADE,
+    challenge_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL REFERENCES users(user_id) ON DELETE CASCADE,
+    joined_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    PRIMARY KEY (group_id, user_id),
+    UNIQUE (challenge_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_participant_groups_challenge ON participant_groups(challenge_id);
@@ -0,0 +1,4 @@
+-- Migration: Self-paced challenges - each participant starts their own clock
+
+ALTER TABLE challenge_participants ADD COLUMN IF NOT EXISTS started_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE challenge_participants ADD COLUMN IF NOT EXISTS deadline_at TIMESTAMP WITH TIME ZONE;
@@ -0,0 +1,22 @@
+-- Migration: Participant status - participants can forfeit, moderators disqualify and reinstate
+
+DO $$ BEGIN
+    CREATE TYPE participant_status AS ENUM ('ACTIVE', 'FORFEITED', 'DISQUALIFIED');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+ALTER TABLE challenge_participants
+    ADD COLUMN IF NOT EXISTS participant_status participant_status NOT NULL DEFAULT 'ACTIVE';
+ALTER TABLE challenge_participants ADD COLUMN IF NOT EXISTS status_reason TEXT;
+ALTER TABLE challenge_participants
+    ADD COLUMN IF NOT EXISTS status_changed_at TIMESTAMP WITH TIME ZONE;
+
+ALTER TYPE audit_event_type ADD VALUE IF NOT EXISTS 'PARTICIPANT_FORFEITED';
+ALTER TYPE audit_event_type ADD VALUE IF NOT EXISTS 'PARTICIPANT_DISQUALIFIED';
+ALTER TYPE audit_event_type ADD VALUE IF NOT EXISTS 'PARTICIPANT_REINSTATED';
+
+-- Audit history outlives participants removed by a revoked invitation
+ALTER TABLE audit_log DROP CONSTRAINT IF EXISTS audit_log_participant_id_fkey;
+ALTER TABLE audit_log ADD CONSTRAINT audit_log_participant_id_fkey
+    FOREIGN KEY (participant_id) REFERENCES challenge_participants(participant_id) ON DELETE SET NULL;
@@ -0,0 +1,3 @@
+-- Migration: Moderator overrides of waypoint verification are audited
+
+ALTER TYPE audit_event_type ADD VALUE IF NOT EXISTS 'WAYPOINT_OVERRIDDEN';
@@ -0,0 +1,15 @@
+-- Migration: Participants track the set of waypoints they have verified, needed by unordered
+-- and branching routes where the current waypoint alone no longer tells progress
+
+ALTER TABLE challenge_participants
+    ADD COLUMN completed_waypoints INTEGER[] NOT NULL DEFAULT '{}';
+
+-- Routes so far were all linear: everything before the current waypoint is done
+UPDATE challenge_participants
+SET completed_waypoints = ARRAY(
+    SELECT generate_series(
+        1,
+        current_waypoint_id - CASE WHEN current_state = 'VERIFIED' THEN 0 ELSE 1 END
+    )
+)
+WHERE current_waypoint_id IS NOT NULL;
@@ -0,0 +1,6 @@
+-- Migration: Bonus waypoints are tracked apart from the main route, participants may be checked
+-- in at several of them while working on the route
+
+ALTER TABLE challenge_participants
+    ADD COLUMN checked_in_bonus_waypoints INTEGER[] NOT NULL DEFAULT '{}',
+    ADD COLUMN verified_bonus_waypoints INTEGER[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,29 @@
+-- Migration: Image proofs are verified in the background by proof job workers
+
+DO $$ BEGIN
+    CREATE TYPE proof_job_status AS ENUM ('QUEUED', 'SUBMITTED', 'ACCEPTED', 'REJECTED', 'FAILED');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+-- One row per submitted photo; QUEUED and SUBMITTED jobs are worked on by the proof workers
+CREATE TABLE IF NOT EXISTS proof_jobs (
+    processing_id UUID PRIMARY KEY,
+    participant_id UUID NOT NULL REFERENCES challenge_participants(participant_id) ON DELETE CASCADE,
+    challenge_id INTEGER NOT NULL,
+    waypoint_sequence INTEGER NOT NULL,
+    image_path TEXT NOT NULL,
+    status proof_job_status NOT NULL DEFAULT 'QUEUED',
+    reasons TEXT[] NOT NULL DEFAULT '{}',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    last_error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_proof_jobs_due ON proof_jobs(next_attempt_at)
+    WHERE status IN ('QUEUED', 'SUBMITTED');
+-- A participant has at most one photo being verified per waypoint
+CREATE UNIQUE INDEX IF NOT EXISTS idx_proof_jobs_pending ON proof_jobs(participant_id, waypoint_sequence)
+    WHERE status IN ('QUEUED', 'SUBMITTED');
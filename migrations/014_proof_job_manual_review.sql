@@ -0,0 +1,3 @@
+-- Migration: Photos are held for manual review while the image checker is unavailable
+
+ALTER TYPE proof_job_status ADD VALUE IF NOT EXISTS 'MANUAL_REVIEW';
@@ -0,0 +1,21 @@
+-- Migration: Perceptual hashes of proof photos, to catch the same picture submitted again by
+-- another participant or in a later challenge at the same spot
+
+CREATE TABLE IF NOT EXISTS photo_fingerprints (
+    processing_id UUID PRIMARY KEY REFERENCES proof_jobs(processing_id) ON DELETE CASCADE,
+    participant_id UUID NOT NULL,
+    challenge_id INTEGER NOT NULL,
+    waypoint_sequence INTEGER NOT NULL,
+    -- Where the waypoint was, so later challenges on the same route find the photo
+    waypoint_lat DOUBLE PRECISION NOT NULL,
+    waypoint_lon DOUBLE PRECISION NOT NULL,
+    perceptual_hash BIGINT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_photo_fingerprints_waypoint
+    ON photo_fingerprints(challenge_id, waypoint_sequence);
+CREATE INDEX IF NOT EXISTS idx_photo_fingerprints_location
+    ON photo_fingerprints(waypoint_lat, waypoint_lon);
+
+ALTER TYPE audit_event_type ADD VALUE IF NOT EXISTS 'DUPLICATE_PHOTO_DETECTED';
@@ -0,0 +1,12 @@
+-- Migration: Moderators claim and decide photos held for manual review
+
+ALTER TABLE proof_jobs
+    ADD COLUMN IF NOT EXISTS photo_metadata JSONB,
+    ADD COLUMN IF NOT EXISTS reviewer_id INTEGER REFERENCES users(user_id) ON DELETE SET NULL,
+    ADD COLUMN IF NOT EXISTS claimed_at TIMESTAMP WITH TIME ZONE,
+    ADD COLUMN IF NOT EXISTS review_comment TEXT,
+    ADD COLUMN IF NOT EXISTS reviewed_at TIMESTAMP WITH TIME ZONE;
+
+-- The review queue of a challenge, oldest first
+CREATE INDEX IF NOT EXISTS idx_proof_jobs_review_queue ON proof_jobs(challenge_id, created_at)
+    WHERE status = 'MANUAL_REVIEW';
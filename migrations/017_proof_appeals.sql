@@ -0,0 +1,11 @@
+-- Migration: Participants appeal rejected photos to a moderator
+
+ALTER TABLE proof_jobs
+    ADD COLUMN IF NOT EXISTS appeal_comment TEXT,
+    ADD COLUMN IF NOT EXISTS appealed_at TIMESTAMP WITH TIME ZONE;
+
+-- Appeals are limited per participant and waypoint
+CREATE INDEX IF NOT EXISTS idx_proof_jobs_appeals ON proof_jobs(participant_id, waypoint_sequence)
+    WHERE appealed_at IS NOT NULL;
+
+ALTER TYPE audit_event_type ADD VALUE IF NOT EXISTS 'PROOF_APPEALED';
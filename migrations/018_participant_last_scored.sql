@@ -0,0 +1,10 @@
+-- Migration: Remember when each participant last scored, the standings break ties on it
+
+ALTER TABLE challenge_participants
+    ADD COLUMN IF NOT EXISTS last_scored_at TIMESTAMP WITH TIME ZONE;
+
+-- Best guess for participants who already scored
+UPDATE challenge_participants
+SET last_scored_at = last_updated
+WHERE last_scored_at IS NULL
+  AND (cardinality(completed_waypoints) > 0 OR cardinality(verified_bonus_waypoints) > 0);
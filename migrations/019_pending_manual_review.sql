@@ -0,0 +1,19 @@
+-- Migration: Photos held for a moderator are pending as well, a participant can have only one
+-- pending photo per waypoint
+
+-- Photos could pile up for a moderator before, keep the first pending one per waypoint
+UPDATE proof_jobs
+SET status = 'FAILED',
+    reasons = ARRAY['Superseded by an earlier photo of the waypoint that is still pending'],
+    completed_at = NOW()
+WHERE status IN ('QUEUED', 'SUBMITTED', 'MANUAL_REVIEW')
+  AND processing_id NOT IN (
+      SELECT DISTINCT ON (participant_id, waypoint_sequence) processing_id
+      FROM proof_jobs
+      WHERE status IN ('QUEUED', 'SUBMITTED', 'MANUAL_REVIEW')
+      ORDER BY participant_id, waypoint_sequence, created_at, processing_id
+  );
+
+DROP INDEX IF EXISTS idx_proof_jobs_pending;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_proof_jobs_pending ON proof_jobs(participant_id, waypoint_sequence)
+    WHERE status IN ('QUEUED', 'SUBMITTED', 'MANUAL_REVIEW');
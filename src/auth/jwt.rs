@@ -23,6 +23,16 @@ pub struct ParticipantClaims {
     pub iat: i64,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InvitationClaims {
+    pub iss: String,
+    pub upn: String, // Invited email address
+    pub inv: i32,    // Invitation ID
+    pub clg: i32,    // Challenge ID
+    pub exp: i64,
+    pub iat: i64,
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum AuthError {
     #[error("JWT token creation failed: {0}")]
@@ -135,6 +145,53 @@ impl JwtService {
         }
     }
 
+    pub fn create_invitation_token(
+        &self,
+        invitation_id: i32,
+        challenge_id: i32,
+        email: &str,
+        expires_at: chrono::DateTime<Utc>,
+    ) -> Result<String, AuthError> {
+        let now = Utc::now();
+        let claims = InvitationClaims {
+            iss: "scavenger-hunt-invitation".to_string(),
+            upn: email.to_string(),
+            inv: invitation_id,
+            clg: challenge_id,
+            exp: expires_at.timestamp(),
+            iat: now.timestamp(),
+        };
+
+        encode(&Header::default(), &claims, &self.encoding_key).map_err(AuthError::from)
+    }
+
+    pub fn validate_invitation_token(&self, token: &str) -> Result<InvitationClaims, AuthError> {
+        let validation = Validation::default();
+
+        match decode::<InvitationClaims>(token, &self.decoding_key, &validation) {
+            Ok(token_data) => {
+                // Check if token is expired
+                let now = Utc::now().timestamp();
+                if token_data.claims.exp < now {
+                    return Err(AuthError::TokenExpired);
+                }
+
+                // Validate issuer
+                if token_data.claims.iss != "scavenger-hunt-invitation" {
+                    return Err(AuthError::TokenValidationFailed(
+                        "Invalid issuer".to_string(),
+                    ));
+                }
+
+                Ok(token_data.claims)
+            }
+            Err(e) if *e.kind() == jsonwebtoken::errors::ErrorKind::ExpiredSignature => {
+                Err(AuthError::TokenExpired)
+            }
+            Err(e) => Err(AuthError::TokenValidationFailed(e.to_string())),
+        }
+    }
+
     pub fn extract_token_from_header(auth_header: &str) -> Result<&str, AuthError> {
         if let Some(token) = auth_header.strip_prefix("Bearer ") {
             Ok(token)
@@ -194,6 +251,40 @@ mod tests {
         assert_eq!(claims.iss, "scavenger-hunt-challenge");
     }
 
+    #[test]
+    fn test_create_and_validate_invitation_token() {
+        let jwt_service = JwtService::new("test-secret-key-that-is-long-enough-32chars");
+
+        let token = jwt_service
+            .create_invitation_token(7, 42, "invitee@example.com", Utc::now() + Duration::days(3))
+            .unwrap();
+        let claims = jwt_service.validate_invitation_token(&token).unwrap();
+
+        assert_eq!(claims.inv, 7);
+        assert_eq!(claims.clg, 42);
+        assert_eq!(claims.upn, "invitee@example.com");
+
+        // Invitation tokens cannot be used as user tokens and vice versa
+        assert!(jwt_service.validate_user_token(&token).is_err());
+        let user_token = jwt_service
+            .create_user_token("invitee@example.com", vec![])
+            .unwrap();
+        assert!(jwt_service.validate_invitation_token(&user_token).is_err());
+
+        let expired = jwt_service
+            .create_invitation_token(
+                7,
+                42,
+                "invitee@example.com",
+                Utc::now() - Duration::hours(1),
+            )
+            .unwrap();
+        assert!(matches!(
+            jwt_service.validate_invitation_token(&expired),
+            Err(AuthError::TokenExpired)
+        ));
+    }
+
     #[test]
     fn test_extract_token_from_header() {
         let header = "Bearer eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9";
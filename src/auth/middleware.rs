@@ -10,13 +10,17 @@ use axum::{
     Json,
 };
 use serde::Serialize;
+use sqlx::PgPool;
 use std::sync::Arc;
+use uuid::Uuid;
 
 use crate::auth::jwt::{AuthError, JwtService};
+use crate::models::challenge::{ChallengeParticipant, ParticipantStatus};
 
 #[derive(Clone)]
 pub struct AuthState {
     pub jwt_service: Arc<JwtService>,
+    pub pool: PgPool,
 }
 
 #[derive(Debug, Clone)]
@@ -28,7 +32,6 @@ pub struct AuthenticatedUser {
 #[derive(Debug, Clone)]
 pub struct AuthenticatedParticipant {
     pub participant_id: String,
-    #[allow(dead_code)]
     pub user_id: i32,
     #[allow(dead_code)]
     pub challenge_id: String,
@@ -170,6 +173,24 @@ where
             )
         })?;
 
+        // Tokens outlive forfeits and disqualifications, so the participant is checked on
+        // every request
+        let pool = parts.extensions.get::<PgPool>().ok_or((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                message: "Database not available".to_string(),
+            }),
+        ))?;
+        let participant_id = Uuid::parse_str(&claims.upn).map_err(|_| {
+            (
+                StatusCode::UNAUTHORIZED,
+                Json(ErrorResponse {
+                    message: "Invalid participant ID".to_string(),
+                }),
+            )
+        })?;
+        ensure_participant_active(pool, participant_id).await?;
+
         Ok(AuthenticatedParticipant {
             participant_id: claims.upn,
             user_id: claims.usr,
@@ -185,14 +206,55 @@ pub async fn jwt_middleware(
     mut request: Request<Body>,
     next: Next,
 ) -> Response {
-    // Add JWT service to request extensions
+    // Add JWT service and database pool to request extensions
     request
         .extensions_mut()
         .insert(auth_state.jwt_service.clone());
+    request.extensions_mut().insert(auth_state.pool.clone());
 
     next.run(request).await
 }
 
+/// Reject participant tokens of participants who forfeited, were disqualified or no longer
+/// exist
+pub async fn ensure_participant_active(
+    pool: &PgPool,
+    participant_id: Uuid,
+) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    let status = ChallengeParticipant::get_status(pool, participant_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to get participant status: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    message: "Failed to authenticate participant".to_string(),
+                }),
+            )
+        })?;
+
+    let message = match status {
+        Some(ParticipantStatus::Active) => return Ok(()),
+        Some(ParticipantStatus::Forfeited) => "Participant has forfeited the challenge",
+        Some(ParticipantStatus::Disqualified) => "Participant has been disqualified",
+        None => {
+            return Err((
+                StatusCode::UNAUTHORIZED,
+                Json(ErrorResponse {
+                    message: "Participant no longer exists".to_string(),
+                }),
+            ))
+        }
+    };
+
+    Err((
+        StatusCode::FORBIDDEN,
+        Json(ErrorResponse {
+            message: message.to_string(),
+        }),
+    ))
+}
+
 // Role validation helper functions
 impl AuthenticatedUser {
     #[allow(dead_code)]
@@ -221,6 +283,18 @@ impl AuthenticatedUser {
 }
 
 impl AuthenticatedParticipant {
+    /// Parse the participant id carried in the token
+    pub fn participant_uuid(&self) -> Result<Uuid, (StatusCode, Json<ErrorResponse>)> {
+        Uuid::parse_str(&self.participant_id).map_err(|_| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    message: "Invalid participant ID".to_string(),
+                }),
+            )
+        })
+    }
+
     #[allow(dead_code)]
     pub fn has_role(&self, role: &str) -> bool {
         self.roles.contains(&role.to_string())
@@ -3,5 +3,6 @@ pub mod middleware;
 
 pub use jwt::{AuthError, JwtService};
 pub use middleware::{
-    jwt_middleware, AuthState, AuthenticatedParticipant, AuthenticatedUser, ErrorResponse,
+    ensure_participant_active, jwt_middleware, AuthState, AuthenticatedParticipant,
+    AuthenticatedUser, ErrorResponse,
 };
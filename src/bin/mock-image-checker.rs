@@ -0,0 +1,45 @@
+//! Stand-in image checker for local runs: `IMAGE_CHECKER_URL=http://127.0.0.1:8080`.
+//!
+//! MOCK_CHECKER_ADDR      address to listen on (default 127.0.0.1:8080)
+//! MOCK_CHECKER_OUTCOME   outcome when nothing else matches (default accept)
+//! MOCK_CHECKER_RULES     comma separated `pattern=outcome` rules matched against the image
+//!                        file name or the expected content, e.g. `Bench=reject:No bench,Clock=slow:5`
+//!
+//! Outcomes are `accept`, `reject[:reason|reason]`, `uncertain[:reason|reason]`, `slow[:polls]`,
+//! `fail` and `error:<status>`;
+//! an `x-mock-outcome` header on `/validate` overrides them.
+
+use scavenger_hunt_game_server::testing::{MockImageChecker, MockOutcome};
+use std::env;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| "info".into()),
+        )
+        .init();
+
+    let address = env::var("MOCK_CHECKER_ADDR").unwrap_or_else(|_| "127.0.0.1:8080".to_string());
+    let default: MockOutcome = env::var("MOCK_CHECKER_OUTCOME")
+        .unwrap_or_else(|_| "accept".to_string())
+        .parse()?;
+
+    let mut checker = MockImageChecker::new(default);
+    for rule in env::var("MOCK_CHECKER_RULES")
+        .unwrap_or_default()
+        .split(',')
+        .filter(|rule| !rule.trim().is_empty())
+    {
+        let (pattern, outcome) = rule
+            .split_once('=')
+            .ok_or_else(|| format!("Rule '{rule}' is not pattern=outcome"))?;
+        checker = checker.with_rule(pattern.trim(), outcome.parse()?);
+    }
+
+    let listener = tokio::net::TcpListener::bind(&address).await?;
+    tracing::info!("Mock image checker listening on {}", address);
+    axum::serve(listener, checker.router()).await?;
+
+    Ok(())
+}
@@ -8,7 +8,90 @@ pub struct Config {
     pub host: String,
     pub port: u16,
     pub image_checker_url: String,
-    pub image_base_dir: String,
+    pub image_checker_callback_secret: Option<String>,
+    pub image_checker_client: ImageCheckerClientConfig,
+    pub image_store: ImageStoreConfig,
+    pub max_image_upload_bytes: usize,
+    pub proof_workers: usize,
+    pub notification_provider: NotificationProviderConfig,
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub smtp_security: SmtpSecurity,
+    pub smtp_from: String,
+    pub smtp_credentials: Option<(String, String)>,
+    pub app_base_url: String,
+    pub auto_start_poll_seconds: u64,
+}
+
+/// Push notification provider selected with NOTIFICATION_PROVIDER
+#[derive(Debug, Clone, PartialEq)]
+pub enum NotificationProviderConfig {
+    /// FCM/APNs-style HTTP push gateway
+    Http {
+        endpoint: String,
+        api_key: Option<String>,
+    },
+    /// Write notifications as JSON files into a local spool directory
+    Spool { spool_dir: String },
+}
+
+/// Timeouts, retries and circuit breaking for calls to the image checker
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageCheckerClientConfig {
+    pub timeout_seconds: u64,
+    pub max_retries: u32,
+    pub retry_base_delay_ms: u64,
+    pub breaker_threshold: u32,
+    pub breaker_cooldown_seconds: u64,
+}
+
+impl Default for ImageCheckerClientConfig {
+    fn default() -> Self {
+        Self {
+            timeout_seconds: 10,
+            max_retries: 3,
+            retry_base_delay_ms: 200,
+            breaker_threshold: 5,
+            breaker_cooldown_seconds: 30,
+        }
+    }
+}
+
+/// How the connection to the SMTP relay is secured, selected with SMTP_SECURITY
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SmtpSecurity {
+    /// Plain connection upgraded with STARTTLS, which the relay must support
+    StartTls,
+    /// TLS from the start, as on port 465
+    Tls,
+    /// Unencrypted, only for a local relay and never with credentials
+    None,
+}
+
+impl SmtpSecurity {
+    fn default_port(self) -> u16 {
+        match self {
+            SmtpSecurity::StartTls => 587,
+            SmtpSecurity::Tls => 465,
+            SmtpSecurity::None => 1025,
+        }
+    }
+}
+
+/// Proof image storage selected with IMAGE_STORE
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImageStoreConfig {
+    /// Files under a directory the image checker can read too
+    Local { base_dir: String },
+    /// An S3-compatible bucket, such as AWS S3 or MinIO
+    S3 {
+        endpoint: String,
+        bucket: String,
+        region: String,
+        access_key_id: String,
+        secret_access_key: String,
+        path_style: bool,
+    },
 }
 
 #[derive(Debug)]
@@ -58,8 +141,166 @@ impl Config {
             ConfigError::MissingEnvironmentVariable("IMAGE_CHECKER_URL".to_string())
         })?;
 
-        let image_base_dir = env::var("IMAGE_BASE_DIR")
-            .map_err(|_| ConfigError::MissingEnvironmentVariable("IMAGE_BASE_DIR".to_string()))?;
+        // Callbacks from the image checker are refused unless a shared secret is configured
+        let image_checker_callback_secret = env::var("IMAGE_CHECKER_CALLBACK_SECRET").ok();
+        if image_checker_callback_secret
+            .as_ref()
+            .is_some_and(|secret| secret.len() < 32)
+        {
+            return Err(ConfigError::InvalidValue(
+                "IMAGE_CHECKER_CALLBACK_SECRET must be at least 32 characters long".to_string(),
+            ));
+        }
+
+        let defaults = ImageCheckerClientConfig::default();
+        let image_checker_client = ImageCheckerClientConfig {
+            timeout_seconds: env_number(
+                "IMAGE_CHECKER_TIMEOUT_SECONDS",
+                defaults.timeout_seconds,
+                1,
+            )?,
+            max_retries: env_number("IMAGE_CHECKER_MAX_RETRIES", defaults.max_retries, 0)?,
+            retry_base_delay_ms: env_number(
+                "IMAGE_CHECKER_RETRY_BASE_DELAY_MS",
+                defaults.retry_base_delay_ms,
+                1,
+            )?,
+            breaker_threshold: env_number(
+                "IMAGE_CHECKER_BREAKER_THRESHOLD",
+                defaults.breaker_threshold,
+                1,
+            )?,
+            breaker_cooldown_seconds: env_number(
+                "IMAGE_CHECKER_BREAKER_COOLDOWN_SECONDS",
+                defaults.breaker_cooldown_seconds,
+                1,
+            )?,
+        };
+
+        let required = |name: &str| {
+            env::var(name).map_err(|_| ConfigError::MissingEnvironmentVariable(name.to_string()))
+        };
+
+        let image_store = match env::var("IMAGE_STORE")
+            .unwrap_or_else(|_| "local".to_string())
+            .as_str()
+        {
+            "local" => ImageStoreConfig::Local {
+                base_dir: required("IMAGE_BASE_DIR")?,
+            },
+            "s3" => ImageStoreConfig::S3 {
+                endpoint: required("S3_ENDPOINT")?,
+                bucket: required("S3_BUCKET")?,
+                region: env::var("S3_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
+                access_key_id: required("S3_ACCESS_KEY_ID")?,
+                secret_access_key: required("S3_SECRET_ACCESS_KEY")?,
+                path_style: env::var("S3_PATH_STYLE")
+                    .unwrap_or_else(|_| "true".to_string())
+                    .parse::<bool>()
+                    .map_err(|_| {
+                        ConfigError::InvalidValue(
+                            "S3_PATH_STYLE must be 'true' or 'false'".to_string(),
+                        )
+                    })?,
+            },
+            other => {
+                return Err(ConfigError::InvalidValue(format!(
+                    "IMAGE_STORE must be 'local' or 's3', got '{other}'"
+                )));
+            }
+        };
+
+        let max_image_upload_bytes = env::var("MAX_IMAGE_UPLOAD_BYTES")
+            .unwrap_or_else(|_| "10485760".to_string())
+            .parse::<usize>()
+            .ok()
+            .filter(|bytes| *bytes > 0)
+            .ok_or_else(|| {
+                ConfigError::InvalidValue(
+                    "MAX_IMAGE_UPLOAD_BYTES must be a positive number".to_string(),
+                )
+            })?;
+
+        let notification_provider = match env::var("NOTIFICATION_PROVIDER")
+            .unwrap_or_else(|_| "spool".to_string())
+            .as_str()
+        {
+            "http" => NotificationProviderConfig::Http {
+                endpoint: env::var("PUSH_GATEWAY_URL").map_err(|_| {
+                    ConfigError::MissingEnvironmentVariable("PUSH_GATEWAY_URL".to_string())
+                })?,
+                api_key: env::var("PUSH_GATEWAY_API_KEY").ok(),
+            },
+            "spool" => NotificationProviderConfig::Spool {
+                spool_dir: env::var("NOTIFICATION_SPOOL_DIR")
+                    .unwrap_or_else(|_| "./notification-spool".to_string()),
+            },
+            other => {
+                return Err(ConfigError::InvalidValue(format!(
+                    "NOTIFICATION_PROVIDER must be 'http' or 'spool', got '{other}'"
+                )));
+            }
+        };
+
+        let smtp_host = env::var("SMTP_HOST").unwrap_or_else(|_| "localhost".to_string());
+
+        let smtp_security = match env::var("SMTP_SECURITY")
+            .unwrap_or_else(|_| "starttls".to_string())
+            .as_str()
+        {
+            "starttls" => SmtpSecurity::StartTls,
+            "tls" => SmtpSecurity::Tls,
+            "none" => SmtpSecurity::None,
+            other => {
+                return Err(ConfigError::InvalidValue(format!(
+                    "SMTP_SECURITY must be 'starttls', 'tls' or 'none', got '{other}'"
+                )));
+            }
+        };
+
+        let smtp_port = match env::var("SMTP_PORT") {
+            Ok(port) => port.parse::<u16>().map_err(|_| {
+                ConfigError::InvalidValue("SMTP_PORT must be a valid number".to_string())
+            })?,
+            Err(_) => smtp_security.default_port(),
+        };
+
+        let smtp_from = env::var("SMTP_FROM")
+            .unwrap_or_else(|_| "Scavenger Hunt <noreply@localhost>".to_string());
+
+        let smtp_credentials = match (env::var("SMTP_USERNAME"), env::var("SMTP_PASSWORD")) {
+            (Ok(username), Ok(password)) => Some((username, password)),
+            _ => None,
+        };
+        if smtp_credentials.is_some() && smtp_security == SmtpSecurity::None {
+            return Err(ConfigError::InvalidValue(
+                "SMTP_USERNAME and SMTP_PASSWORD are only sent with SMTP_SECURITY 'starttls' or 'tls'"
+                    .to_string(),
+            ));
+        }
+
+        let proof_workers = env::var("PROOF_WORKERS")
+            .unwrap_or_else(|_| "4".to_string())
+            .parse::<usize>()
+            .ok()
+            .filter(|workers| *workers > 0)
+            .ok_or_else(|| {
+                ConfigError::InvalidValue("PROOF_WORKERS must be a positive number".to_string())
+            })?;
+
+        let app_base_url =
+            env::var("APP_BASE_URL").unwrap_or_else(|_| "http://localhost:3000".to_string());
+
+        let auto_start_poll_seconds = env::var("AUTO_START_POLL_SECONDS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse::<u64>()
+            .ok()
+            .filter(|seconds| *seconds > 0)
+            .ok_or_else(|| {
+                ConfigError::InvalidValue(
+                    "AUTO_START_POLL_SECONDS must be a positive number".to_string(),
+                )
+            })?;
 
         Ok(Config {
             database_url,
@@ -67,7 +308,19 @@ impl Config {
             host,
             port,
             image_checker_url,
-            image_base_dir,
+            image_checker_callback_secret,
+            image_checker_client,
+            image_store,
+            max_image_upload_bytes,
+            proof_workers,
+            notification_provider,
+            smtp_host,
+            smtp_port,
+            smtp_security,
+            smtp_from,
+            smtp_credentials,
+            app_base_url,
+            auto_start_poll_seconds,
         })
     }
 
@@ -76,6 +329,21 @@ impl Config {
     }
 }
 
+/// An optional numeric setting of at least `minimum`
+fn env_number<T>(name: &str, default: T, minimum: T) -> Result<T, ConfigError>
+where
+    T: std::str::FromStr + PartialOrd + fmt::Display,
+{
+    let value = match env::var(name) {
+        Ok(value) => value.parse::<T>().ok(),
+        Err(_) => Some(default),
+    };
+
+    value.filter(|value| *value >= minimum).ok_or_else(|| {
+        ConfigError::InvalidValue(format!("{name} must be a number of at least {minimum}"))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -109,7 +377,23 @@ mod tests {
             host: "localhost".to_string(),
             port: 8080,
             image_checker_url: "http://localhost:8080".to_string(),
-            image_base_dir: "/tmp".to_string(),
+            image_checker_callback_secret: None,
+            image_checker_client: ImageCheckerClientConfig::default(),
+            image_store: ImageStoreConfig::Local {
+                base_dir: "/tmp".to_string(),
+            },
+            max_image_upload_bytes: 10 * 1024 * 1024,
+            proof_workers: 4,
+            notification_provider: NotificationProviderConfig::Spool {
+                spool_dir: "/tmp/spool".to_string(),
+            },
+            smtp_host: "localhost".to_string(),
+            smtp_port: 1025,
+            smtp_security: SmtpSecurity::None,
+            smtp_from: "noreply@localhost".to_string(),
+            smtp_credentials: None,
+            app_base_url: "http://localhost:3000".to_string(),
+            auto_start_poll_seconds: 30,
         };
         assert_eq!(config.server_address(), "localhost:8080");
     }
@@ -1,6 +1,8 @@
 use axum::{extract::State, http::StatusCode, Json};
 
 use crate::auth::{AuthenticatedUser, ErrorResponse};
+use crate::models::challenge::ParticipantStatus;
+use crate::models::user::User;
 use crate::models::{CreateUserRequest, LoginRequest, UserError};
 use crate::routes::AppState;
 use crate::services::{
@@ -144,45 +146,95 @@ pub async fn create_participant_token(
             tracing::info!("Participant token created successfully");
             Ok((StatusCode::CREATED, Json(response)))
         }
-        Err(AuthServiceError::UserNotInvited) => {
+        Err(e) => Err(participant_token_error_response(e)),
+    }
+}
+
+pub(crate) fn participant_token_error_response(
+    error: AuthServiceError,
+) -> (StatusCode, Json<ErrorResponse>) {
+    match error {
+        AuthServiceError::UserNotInvited => {
             tracing::warn!("Participant token failed: User not invited to challenge");
-            Err((
+            (
                 StatusCode::FORBIDDEN,
                 Json(ErrorResponse {
                     message: "no participant attached to the challenge for this user".to_string(),
                 }),
-            ))
+            )
         }
-        Err(AuthServiceError::ChallengeNotFound) => {
+        AuthServiceError::ChallengeNotFound => {
             tracing::warn!("Participant token failed: Challenge not found");
-            Err((
+            (
                 StatusCode::NOT_FOUND,
                 Json(ErrorResponse {
                     message: "Challenge not found".to_string(),
                 }),
-            ))
+            )
         }
-        Err(AuthServiceError::ChallengeNotActive) => {
+        AuthServiceError::ChallengeNotActive => {
             tracing::warn!("Participant token failed: Challenge not active");
-            Err((
+            (
                 StatusCode::BAD_REQUEST,
                 Json(ErrorResponse {
                     message: "Challenge is not active".to_string(),
                 }),
-            ))
+            )
         }
-        Err(e) => {
+        AuthServiceError::ParticipantNotStarted => {
+            tracing::warn!("Participant token failed: Self-paced challenge not begun");
+            (
+                StatusCode::CONFLICT,
+                Json(ErrorResponse {
+                    message: "Begin the self-paced challenge to get a participant token"
+                        .to_string(),
+                }),
+            )
+        }
+        AuthServiceError::ParticipantNotActive(status) => {
+            tracing::warn!("Participant token failed: Participant is {}", status);
+            let message = match status {
+                ParticipantStatus::Disqualified => "Participant has been disqualified",
+                _ => "Participant has forfeited the challenge",
+            };
+            (
+                StatusCode::FORBIDDEN,
+                Json(ErrorResponse {
+                    message: message.to_string(),
+                }),
+            )
+        }
+        e => {
             tracing::error!("Participant token creation failed with error: {}", e);
-            Err((
+            (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(ErrorResponse {
                     message: "Token creation failed".to_string(),
                 }),
-            ))
+            )
         }
     }
 }
 
+/// Load the user record behind an authenticated user token
+pub(crate) async fn current_user(
+    state: &AppState,
+    auth_user: &AuthenticatedUser,
+) -> Result<User, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .auth_service
+        .get_user_by_username(&auth_user.username)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::UNAUTHORIZED,
+                Json(ErrorResponse {
+                    message: "User not found".to_string(),
+                }),
+            )
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
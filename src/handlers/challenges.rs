@@ -1,16 +1,101 @@
 use axum::{
     extract::{Path, State},
     http::StatusCode,
+    response::{IntoResponse, Response},
     Json,
 };
 
 use crate::auth::{AuthenticatedUser, ErrorResponse};
-use crate::models::challenge::{ChallengeParticipant, TemporalChallenge};
+use crate::handlers::auth::{current_user, participant_token_error_response};
+use crate::handlers::invitations::deliver_invitation;
+use crate::models::challenge::{
+    BeginChallengeResponse, ChallengeData, ChallengePacing, ChallengeParticipant,
+    ChallengeTimingResponse, ChallengeViewResponse, ExtendChallengeRequest, PauseChallengeRequest,
+    TemporalChallenge, WaypointView,
+};
+use crate::models::invitation::{ChallengeInvitation, InvitationStatus, DEFAULT_INVITATION_HOURS};
+use crate::models::user::{User, UserError};
 use crate::models::{
     ChallengeError, ChallengeResponse, CreateChallengeRequest, StartChallengeRequest,
     StartChallengeResponse,
 };
 use crate::routes::AppState;
+use crate::services::auth_service::AuthServiceError;
+use crate::services::challenge_scheduler::announce_challenge_start;
+use crate::services::{ChallengeEvent, ChallengeEventType};
+
+/// Resolve the calling user and the current version of a challenge, ensuring the caller
+/// is the owner or a co-moderator of the challenge (or is a game admin). Moderating is
+/// granted per challenge, no global role is needed.
+pub(crate) async fn authorize_challenge_moderator(
+    state: &AppState,
+    auth_user: &AuthenticatedUser,
+    challenge_id: i32,
+) -> Result<(User, TemporalChallenge, ChallengeData), (StatusCode, Json<ErrorResponse>)> {
+    let user = match state
+        .auth_service
+        .get_user_by_username(&auth_user.username)
+        .await
+    {
+        Ok(user) => user,
+        Err(_) => {
+            return Err((
+                StatusCode::UNAUTHORIZED,
+                Json(ErrorResponse {
+                    message: "User not found".to_string(),
+                }),
+            ));
+        }
+    };
+
+    let temporal_challenge =
+        match TemporalChallenge::get_current_by_id(&state.pool, challenge_id).await {
+            Ok(challenge) => challenge,
+            Err(ChallengeError::ChallengeNotFound) => {
+                return Err((
+                    StatusCode::NOT_FOUND,
+                    Json(ErrorResponse {
+                        message: "Challenge not found".to_string(),
+                    }),
+                ));
+            }
+            Err(e) => {
+                tracing::error!("Failed to get challenge: {}", e);
+                return Err((
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse {
+                        message: "Failed to get challenge".to_string(),
+                    }),
+                ));
+            }
+        };
+
+    let challenge_data = temporal_challenge.get_challenge_data().map_err(|e| {
+        tracing::error!("Failed to get challenge data: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                message: "Failed to get challenge data".to_string(),
+            }),
+        )
+    })?;
+
+    if !challenge_data.is_moderator(user.user_id) && !auth_user.has_role("game.admin") {
+        tracing::warn!(
+            "User {} is not moderator of challenge {}",
+            auth_user.username,
+            challenge_id
+        );
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse {
+                message: "You are not authorized to modify this challenge".to_string(),
+            }),
+        ));
+    }
+
+    Ok((user, temporal_challenge, challenge_data))
+}
 
 /// Create a new challenge
 /// POST /challenges
@@ -148,13 +233,13 @@ pub async fn create_challenge(
     }
 }
 
-/// Get a challenge by ID
+/// Get a challenge by ID. Only its moderators see the waypoints' proof secrets and answers.
 /// GET /challenges/{challenge_id}
 pub async fn get_challenge(
     auth_user: AuthenticatedUser,
     State(state): State<AppState>,
     Path(challenge_id): Path<i32>,
-) -> Result<(StatusCode, Json<ChallengeResponse>), (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
     tracing::info!(
         "Challenge retrieval request from user: {} for challenge: {}",
         auth_user.username,
@@ -178,13 +263,36 @@ pub async fn get_challenge(
                     .await
                     .unwrap_or_default();
 
-            let response = ChallengeResponse {
-                challenge: temporal_challenge,
-                waypoints,
+            let user = current_user(&state, &auth_user).await?;
+            let moderates = temporal_challenge
+                .get_challenge_data()
+                .is_ok_and(|data| data.is_moderator(user.user_id))
+                || auth_user.has_role("game.admin");
+            if moderates {
+                let response = ChallengeResponse {
+                    challenge: temporal_challenge,
+                    waypoints,
+                    participants,
+                };
+                return Ok((StatusCode::OK, Json(response)).into_response());
+            }
+
+            let challenge = temporal_challenge.redacted().map_err(|e| {
+                tracing::error!("Failed to redact challenge: {}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse {
+                        message: "Challenge retrieval failed".to_string(),
+                    }),
+                )
+            })?;
+            let response = ChallengeViewResponse {
+                challenge,
+                waypoints: waypoints.iter().map(WaypointView::from).collect(),
                 participants,
             };
 
-            Ok((StatusCode::OK, Json(response)))
+            Ok((StatusCode::OK, Json(response)).into_response())
         }
         Err(ChallengeError::ChallengeNotFound) => {
             tracing::warn!("Challenge not found: {}", challenge_id);
@@ -220,21 +328,7 @@ pub async fn start_challenge(
         request.challenge_id
     );
 
-    // Check if user has permission to start challenges
-    if !auth_user.has_any_role(&["challenge.moderator", "challenge.manager", "game.admin"]) {
-        tracing::warn!(
-            "User {} lacks permission to start challenges",
-            auth_user.username
-        );
-        return Err((
-            StatusCode::FORBIDDEN,
-            Json(ErrorResponse {
-                message: "Insufficient permissions to start challenges".to_string(),
-            }),
-        ));
-    }
-
-    // Get user details
+    // Get user details, whether they moderate the challenge is checked when starting
     let user = match state
         .auth_service
         .get_user_by_username(&auth_user.username)
@@ -343,6 +437,15 @@ pub async fn start_challenge(
             }
             */
 
+            announce_challenge_start(
+                &state.pool,
+                &state.event_hub,
+                &state.notification_service,
+                &started_challenge,
+                &challenge_data,
+            )
+            .await;
+
             tracing::info!(
                 "Challenge started successfully: {}",
                 started_challenge.challenge_id
@@ -359,7 +462,7 @@ pub async fn start_challenge(
             Err((
                 StatusCode::FORBIDDEN,
                 Json(ErrorResponse {
-                    message: "You are not the moderator of this challenge".to_string(),
+                    message: "You are not a moderator of this challenge".to_string(),
                 }),
             ))
         }
@@ -372,6 +475,15 @@ pub async fn start_challenge(
                 }),
             ))
         }
+        Err(ChallengeError::SelfPaced) => {
+            tracing::warn!("Challenge is self-paced: {}", request.challenge_id);
+            Err((
+                StatusCode::CONFLICT,
+                Json(ErrorResponse {
+                    message: "Self-paced challenges are started by each participant".to_string(),
+                }),
+            ))
+        }
         Err(ChallengeError::ChallengeNotActive) => {
             tracing::warn!("Challenge not active: {}", request.challenge_id);
             Err((
@@ -393,53 +505,21 @@ pub async fn start_challenge(
     }
 }
 
-/// Invite a user to participate in a challenge
-/// POST /challenges/{challenge_id}/invite/{user_id}
-pub async fn invite_participant(
+/// Begin a self-paced challenge, starting the participant's own clock
+/// POST /challenges/{challenge_id}/begin
+pub async fn begin_challenge(
     auth_user: AuthenticatedUser,
     State(state): State<AppState>,
-    Path((challenge_id, user_id)): Path<(i32, i32)>,
-    Json(nickname): Json<Option<String>>,
-) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    Path(challenge_id): Path<i32>,
+) -> Result<(StatusCode, Json<BeginChallengeResponse>), (StatusCode, Json<ErrorResponse>)> {
     tracing::info!(
-        "Participant invitation from user: {} for challenge: {}, inviting user: {}",
+        "Self-paced challenge begin from user: {} for challenge: {}",
         auth_user.username,
-        challenge_id,
-        user_id
+        challenge_id
     );
 
-    // Check if user has permission to invite participants
-    if !auth_user.has_any_role(&["challenge.moderator", "challenge.manager", "game.admin"]) {
-        tracing::warn!(
-            "User {} lacks permission to invite participants",
-            auth_user.username
-        );
-        return Err((
-            StatusCode::FORBIDDEN,
-            Json(ErrorResponse {
-                message: "Insufficient permissions to invite participants".to_string(),
-            }),
-        ));
-    }
-
-    // Get moderator user details
-    let moderator = match state
-        .auth_service
-        .get_user_by_username(&auth_user.username)
-        .await
-    {
-        Ok(user) => user,
-        Err(_) => {
-            return Err((
-                StatusCode::UNAUTHORIZED,
-                Json(ErrorResponse {
-                    message: "User not found".to_string(),
-                }),
-            ));
-        }
-    };
+    let user = current_user(&state, &auth_user).await?;
 
-    // Get challenge
     let temporal_challenge =
         match TemporalChallenge::get_current_by_id(&state.pool, challenge_id).await {
             Ok(challenge) => challenge,
@@ -462,7 +542,6 @@ pub async fn invite_participant(
             }
         };
 
-    // Get challenge data to check moderator
     let challenge_data = temporal_challenge.get_challenge_data().map_err(|e| {
         tracing::error!("Failed to get challenge data: {}", e);
         (
@@ -473,42 +552,164 @@ pub async fn invite_participant(
         )
     })?;
 
-    // Check if moderator is authorized for this challenge
-    if challenge_data.challenge_moderator != moderator.user_id && !auth_user.has_role("game.admin")
-    {
+    if challenge_data.pacing != ChallengePacing::SelfPaced {
+        return Err(challenge_timing_error_response(
+            ChallengeError::NotSelfPaced,
+        ));
+    }
+    if !challenge_data.active {
         return Err((
-            StatusCode::FORBIDDEN,
+            StatusCode::BAD_REQUEST,
             Json(ErrorResponse {
-                message: "You are not authorized to modify this challenge".to_string(),
+                message: "Challenge is not active".to_string(),
             }),
         ));
     }
+    if challenge_data.is_paused() {
+        return Err(challenge_timing_error_response(
+            ChallengeError::ChallengePaused,
+        ));
+    }
 
-    match ChallengeParticipant::create_for_challenge(
+    let mut participant =
+        ChallengeParticipant::get_accepted_for_user(&state.pool, challenge_id, user.user_id)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to get participant: {}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse {
+                        message: "Failed to get participant".to_string(),
+                    }),
+                )
+            })?
+            .ok_or_else(|| participant_token_error_response(AuthServiceError::UserNotInvited))?;
+
+    // Group members that begin after a teammate join the clock that is already running
+    if participant.started_at.is_none() {
+        if !temporal_challenge.is_available_at(&challenge_data, chrono::Utc::now()) {
+            return Err(challenge_timing_error_response(
+                ChallengeError::NotAvailable,
+            ));
+        }
+
+        participant
+            .begin(
+                &state.pool,
+                challenge_data.starting_waypoint(),
+                challenge_data.duration_minutes,
+            )
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to begin challenge: {}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse {
+                        message: "Failed to begin challenge".to_string(),
+                    }),
+                )
+            })?;
+
+        state.event_hub.publish(
+            ChallengeEvent::new(
+                ChallengeEventType::ChallengeStarted,
+                challenge_id,
+                serde_json::json!({
+                    "participant-id": participant.participant_id,
+                    "started-at": participant.started_at,
+                    "deadline": participant.deadline_at,
+                }),
+            )
+            .with_waypoint(participant.current_waypoint_id)
+            .with_recipients(vec![participant.participant_id]),
+        );
+
+        tracing::info!(
+            "Participant {} began self-paced challenge {}",
+            participant.participant_id,
+            challenge_id
+        );
+    }
+
+    let token = state
+        .auth_service
+        .create_participant_token(user.user_id, challenge_id)
+        .await
+        .map_err(participant_token_error_response)?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(BeginChallengeResponse {
+            token,
+            participant_id: participant.participant_id,
+            started_at: participant.started_at.unwrap_or_else(chrono::Utc::now),
+            deadline: participant.deadline_at.unwrap_or_else(chrono::Utc::now),
+            current_waypoint_id: participant.current_waypoint_id,
+        }),
+    ))
+}
+
+/// Invite a registered user to participate in a challenge, the user joins once they accept
+/// POST /challenges/{challenge_id}/invite/{user_id}
+pub async fn invite_participant(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path((challenge_id, user_id)): Path<(i32, i32)>,
+    Json(nickname): Json<Option<String>>,
+) -> Result<(StatusCode, Json<ChallengeInvitation>), (StatusCode, Json<ErrorResponse>)> {
+    tracing::info!(
+        "Participant invitation from user: {} for challenge: {}, inviting user: {}",
+        auth_user.username,
+        challenge_id,
+        user_id
+    );
+
+    let (moderator, temporal_challenge, _challenge_data) =
+        authorize_challenge_moderator(&state, &auth_user, challenge_id).await?;
+
+    let invitee = match User::get_by_id(&state.pool, user_id).await {
+        Ok(user) => user,
+        Err(UserError::UserNotFound) => {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    message: "User not found".to_string(),
+                }),
+            ));
+        }
+        Err(e) => {
+            tracing::error!("Failed to get invited user: {}", e);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    message: "Participant invitation failed".to_string(),
+                }),
+            ));
+        }
+    };
+
+    let invitation = ChallengeInvitation::create_or_refresh(
         &state.pool,
         challenge_id,
-        user_id,
-        nickname.clone(),
+        &invitee.username.to_lowercase(),
+        moderator.user_id,
+        chrono::Utc::now() + chrono::Duration::hours(DEFAULT_INVITATION_HOURS),
+        nickname,
     )
     .await
-    {
-        Ok(_participant) => {
-            // TODO: Update audit logging to use integer IDs instead of UUIDs
-            // Temporarily disabled during table replacement migration
-            /*
-            if let Err(e) = AuditLog::log_participant_invited(
-                &state.pool,
-                moderator.user_id,
-                participant.participant_id,
-                challenge_id,
-                user_id,
-                nickname.as_deref(),
-            )
-            .await
-            {
-                tracing::warn!("Failed to log participant invitation: {}", e);
-            }
-            */
+    .map_err(|e| {
+        tracing::error!("Participant invitation failed with error: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                message: "Participant invitation failed".to_string(),
+            }),
+        )
+    })?;
+
+    match invitation.status {
+        InvitationStatus::Pending => {
+            deliver_invitation(&state, &temporal_challenge, &invitation).await;
 
             tracing::info!(
                 "Participant invited successfully: {} to challenge: {}",
@@ -516,9 +717,9 @@ pub async fn invite_participant(
                 challenge_id
             );
 
-            Ok(StatusCode::CREATED)
+            Ok((StatusCode::CREATED, Json(invitation)))
         }
-        Err(ChallengeError::AlreadyParticipant) => {
+        InvitationStatus::Accepted => {
             tracing::warn!(
                 "User {} is already a participant in challenge {}",
                 user_id,
@@ -531,16 +732,196 @@ pub async fn invite_participant(
                 }),
             ))
         }
-        Err(e) => {
-            tracing::error!("Participant invitation failed with error: {}", e);
-            Err((
+        _ => Err((
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                message: "User has declined the invitation to this challenge".to_string(),
+            }),
+        )),
+    }
+}
+
+/// Pause a running challenge, freezing every participant clock
+/// POST /challenges/{challenge_id}/pause
+pub async fn pause_challenge(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(challenge_id): Path<i32>,
+    request: Option<Json<PauseChallengeRequest>>,
+) -> Result<Json<ChallengeTimingResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let (_moderator, temporal_challenge, _challenge_data) =
+        authorize_challenge_moderator(&state, &auth_user, challenge_id).await?;
+    let Json(request) = request.unwrap_or_default();
+
+    let paused = temporal_challenge
+        .pause(&state.pool, request.reason.clone())
+        .await
+        .map_err(challenge_timing_error_response)?;
+
+    tracing::info!(
+        "Challenge {} paused by moderator: {}",
+        challenge_id,
+        auth_user.username
+    );
+
+    let body = match &request.reason {
+        Some(reason) => format!("The clock is stopped: {reason}"),
+        None => "The clock is stopped until the moderator resumes the challenge".to_string(),
+    };
+    announce_timing_change(
+        &state,
+        &paused,
+        ChallengeEventType::ChallengePaused,
+        &format!("{} is paused", paused.challenge_name),
+        &body,
+    )
+    .await
+    .map(Json)
+}
+
+/// Resume a paused challenge, the paused time is added to every participant clock
+/// POST /challenges/{challenge_id}/resume
+pub async fn resume_challenge(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(challenge_id): Path<i32>,
+) -> Result<Json<ChallengeTimingResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let (_moderator, temporal_challenge, _challenge_data) =
+        authorize_challenge_moderator(&state, &auth_user, challenge_id).await?;
+
+    let resumed = temporal_challenge
+        .resume(&state.pool)
+        .await
+        .map_err(challenge_timing_error_response)?;
+
+    tracing::info!(
+        "Challenge {} resumed by moderator: {}",
+        challenge_id,
+        auth_user.username
+    );
+
+    announce_timing_change(
+        &state,
+        &resumed,
+        ChallengeEventType::ChallengeResumed,
+        &format!("{} has resumed", resumed.challenge_name),
+        "The clock is running again",
+    )
+    .await
+    .map(Json)
+}
+
+/// Add time to a challenge that has not ended yet
+/// POST /challenges/{challenge_id}/extend
+pub async fn extend_challenge(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(challenge_id): Path<i32>,
+    Json(request): Json<ExtendChallengeRequest>,
+) -> Result<Json<ChallengeTimingResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let (_moderator, temporal_challenge, _challenge_data) =
+        authorize_challenge_moderator(&state, &auth_user, challenge_id).await?;
+
+    let extended = temporal_challenge
+        .extend(&state.pool, request.minutes)
+        .await
+        .map_err(challenge_timing_error_response)?;
+
+    tracing::info!(
+        "Challenge {} extended by {} minutes by moderator: {}",
+        challenge_id,
+        request.minutes,
+        auth_user.username
+    );
+
+    announce_timing_change(
+        &state,
+        &extended,
+        ChallengeEventType::ChallengeExtended,
+        &format!("{} was extended", extended.challenge_name),
+        &format!("You have {} more minutes", request.minutes),
+    )
+    .await
+    .map(Json)
+}
+
+/// Publish a clock change to the challenge stream and push it to everyone playing. Clients
+/// should fetch a new participant token, its expiry follows the effective end time.
+async fn announce_timing_change(
+    state: &AppState,
+    challenge: &TemporalChallenge,
+    event_type: ChallengeEventType,
+    title: &str,
+    body: &str,
+) -> Result<ChallengeTimingResponse, (StatusCode, Json<ErrorResponse>)> {
+    let timing = challenge
+        .timing_response()
+        .map_err(challenge_timing_error_response)?;
+
+    state.event_hub.publish(ChallengeEvent::new(
+        event_type,
+        challenge.challenge_id,
+        serde_json::json!({
+            "paused": timing.paused,
+            "duration": timing.duration,
+            "end-time": timing.end_time,
+        }),
+    ));
+
+    let user_ids =
+        ChallengeParticipant::get_user_ids_for_challenge(&state.pool, challenge.challenge_id)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to get participant users: {}", e);
+                Vec::new()
+            });
+    if let Err(e) = state
+        .notification_service
+        .notify_users(
+            &user_ids,
+            Some(challenge.challenge_id),
+            title,
+            body,
+            serde_json::json!({
+                "challenge-id": challenge.challenge_id,
+                "paused": timing.paused,
+            }),
+        )
+        .await
+    {
+        tracing::warn!("Failed to queue challenge timing notifications: {}", e);
+    }
+
+    Ok(timing)
+}
+
+fn challenge_timing_error_response(error: ChallengeError) -> (StatusCode, Json<ErrorResponse>) {
+    let status = match &error {
+        ChallengeError::ChallengeNotActive
+        | ChallengeError::ChallengeNotStarted
+        | ChallengeError::ChallengeEnded
+        | ChallengeError::ChallengePaused
+        | ChallengeError::ChallengeNotPaused
+        | ChallengeError::NotSelfPaced
+        | ChallengeError::NotAvailable => StatusCode::CONFLICT,
+        ChallengeError::ValidationFailed(_) => StatusCode::BAD_REQUEST,
+        _ => {
+            tracing::error!("Challenge timing change failed with error: {}", error);
+            return (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(ErrorResponse {
-                    message: "Participant invitation failed".to_string(),
+                    message: "Failed to change challenge timing".to_string(),
                 }),
-            ))
+            );
         }
-    }
+    };
+
+    (
+        status,
+        Json(ErrorResponse {
+            message: error.to_string(),
+        }),
+    )
 }
 
 #[cfg(test)]
@@ -0,0 +1,110 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+
+use crate::auth::{AuthenticatedUser, ErrorResponse};
+use crate::handlers::auth::current_user;
+use crate::models::notification::{DeviceToken, NotificationError, RegisterDeviceRequest};
+use crate::routes::AppState;
+
+/// Register a push notification device token for the current user
+/// POST /users/devices
+pub async fn register_device(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(request): Json<RegisterDeviceRequest>,
+) -> Result<(StatusCode, Json<DeviceToken>), (StatusCode, Json<ErrorResponse>)> {
+    tracing::info!(
+        "Device registration from user: {} on platform: {:?}",
+        auth_user.username,
+        request.platform
+    );
+
+    let user = current_user(&state, &auth_user).await?;
+
+    match DeviceToken::register(
+        &state.pool,
+        user.user_id,
+        request.platform,
+        &request.device_token,
+    )
+    .await
+    {
+        Ok(device) => Ok((StatusCode::CREATED, Json(device))),
+        Err(NotificationError::EmptyDeviceToken) => Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                message: "Device token must not be empty".to_string(),
+            }),
+        )),
+        Err(e) => {
+            tracing::error!("Device registration failed with error: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    message: "Device registration failed".to_string(),
+                }),
+            ))
+        }
+    }
+}
+
+/// List the current user's registered devices
+/// GET /users/devices
+pub async fn list_devices(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<DeviceToken>>, (StatusCode, Json<ErrorResponse>)> {
+    let user = current_user(&state, &auth_user).await?;
+
+    match DeviceToken::get_for_user(&state.pool, user.user_id).await {
+        Ok(devices) => Ok(Json(devices)),
+        Err(e) => {
+            tracing::error!("Device retrieval failed with error: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    message: "Device retrieval failed".to_string(),
+                }),
+            ))
+        }
+    }
+}
+
+/// Unregister one of the current user's devices
+/// DELETE /users/devices/{device_id}
+pub async fn unregister_device(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(device_id): Path<i32>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    let user = current_user(&state, &auth_user).await?;
+
+    match DeviceToken::delete(&state.pool, user.user_id, device_id).await {
+        Ok(true) => {
+            tracing::info!(
+                "Device {} unregistered by user: {}",
+                device_id,
+                auth_user.username
+            );
+            Ok(StatusCode::NO_CONTENT)
+        }
+        Ok(false) => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                message: "Device not found".to_string(),
+            }),
+        )),
+        Err(e) => {
+            tracing::error!("Device removal failed with error: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    message: "Device removal failed".to_string(),
+                }),
+            ))
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::sse::{Event, KeepAlive, Sse},
+    Json,
+};
+use futures_util::{future, Stream, StreamExt};
+use serde::Deserialize;
+use std::convert::Infallible;
+use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream};
+use uuid::Uuid;
+
+use crate::auth::{ensure_participant_active, AuthenticatedUser, ErrorResponse, JwtService};
+use crate::handlers::challenges::authorize_challenge_moderator;
+use crate::routes::AppState;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EventStreamQuery {
+    /// Bearer token for clients that cannot set headers (e.g. browser EventSource)
+    pub token: Option<String>,
+}
+
+/// Who is listening on a challenge event stream
+#[derive(Debug, Clone, PartialEq)]
+enum EventSubscriber {
+    /// A participant only sees broadcasts and events addressed to them
+    Participant(Uuid),
+    /// A moderator of the challenge sees every event
+    Moderator,
+}
+
+/// Stream live challenge events as Server-Sent Events
+/// GET /challenges/{challenge_id}/events
+pub async fn stream_challenge_events(
+    State(state): State<AppState>,
+    Path(challenge_id): Path<i32>,
+    Query(query): Query<EventStreamQuery>,
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, Json<ErrorResponse>)> {
+    let token = match headers.get("authorization") {
+        Some(header) => {
+            let header = header.to_str().map_err(|_| unauthorized())?;
+            JwtService::extract_token_from_header(header)
+                .map_err(|_| unauthorized())?
+                .to_string()
+        }
+        None => query.token.ok_or((
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse {
+                message: "Missing authorization header".to_string(),
+            }),
+        ))?,
+    };
+
+    let subscriber = authorize_subscriber(&state, &token, challenge_id).await?;
+
+    tracing::info!(
+        "Event stream opened for challenge: {} by {:?}",
+        challenge_id,
+        subscriber
+    );
+
+    let receiver = state.event_hub.subscribe(challenge_id);
+
+    // Each item carries whether it is the last one: a participant who forfeits or is
+    // disqualified gets the status change and then the stream ends
+    let stream = BroadcastStream::new(receiver)
+        .filter_map(move |message| {
+            let subscriber = subscriber.clone();
+            async move {
+                match message {
+                    Ok(event) => {
+                        let mut last = false;
+                        if let EventSubscriber::Participant(participant_id) = subscriber {
+                            if !event.is_visible_to(participant_id) {
+                                return None;
+                            }
+                            last = event.removes_participant(participant_id);
+                        }
+
+                        let event_type = serde_json::to_value(event.event_type)
+                            .ok()
+                            .and_then(|v| v.as_str().map(str::to_string))
+                            .unwrap_or_default();
+
+                        match Event::default().event(event_type).json_data(&event) {
+                            Ok(sse_event) => Some((Ok(sse_event), last)),
+                            Err(e) => {
+                                tracing::error!("Failed to serialize challenge event: {}", e);
+                                None
+                            }
+                        }
+                    }
+                    Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                        tracing::warn!(
+                            "Event stream for challenge {} lagged, {} events skipped",
+                            challenge_id,
+                            skipped
+                        );
+                        Some((
+                            Ok(Event::default().event("lagged").data(skipped.to_string())),
+                            false,
+                        ))
+                    }
+                }
+            }
+        })
+        .scan(false, |ended, (item, last)| {
+            let item = (!*ended).then_some(item);
+            *ended = last;
+            future::ready(item)
+        });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+/// Accept a participant token for this challenge, or a user token of one of its moderators
+async fn authorize_subscriber(
+    state: &AppState,
+    token: &str,
+    challenge_id: i32,
+) -> Result<EventSubscriber, (StatusCode, Json<ErrorResponse>)> {
+    let jwt_service = &state.auth_state.jwt_service;
+
+    if let Ok(claims) = jwt_service.validate_participant_token(token) {
+        if claims.clg != challenge_id {
+            return Err((
+                StatusCode::FORBIDDEN,
+                Json(ErrorResponse {
+                    message: "Participant token is not valid for this challenge".to_string(),
+                }),
+            ));
+        }
+
+        let participant_id = Uuid::parse_str(&claims.upn).map_err(|_| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    message: "Invalid participant ID".to_string(),
+                }),
+            )
+        })?;
+        ensure_participant_active(&state.pool, participant_id).await?;
+
+        return Ok(EventSubscriber::Participant(participant_id));
+    }
+
+    let claims = jwt_service.validate_user_token(token).map_err(|e| {
+        (
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse {
+                message: format!("Token validation failed: {e}"),
+            }),
+        )
+    })?;
+
+    let auth_user = AuthenticatedUser {
+        username: claims.upn,
+        roles: claims.groups,
+    };
+
+    authorize_challenge_moderator(state, &auth_user, challenge_id).await?;
+
+    Ok(EventSubscriber::Moderator)
+}
+
+fn unauthorized() -> (StatusCode, Json<ErrorResponse>) {
+    (
+        StatusCode::UNAUTHORIZED,
+        Json(ErrorResponse {
+            message: "Invalid authorization header format".to_string(),
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_stream_query_deserialization() {
+        let query: EventStreamQuery = serde_json::from_str(r#"{"token": "abc"}"#).unwrap();
+        assert_eq!(query.token.as_deref(), Some("abc"));
+
+        let query: EventStreamQuery = serde_json::from_str("{}").unwrap();
+        assert!(query.token.is_none());
+    }
+}
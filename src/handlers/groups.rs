@@ -0,0 +1,262 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+
+use crate::auth::{AuthenticatedUser, ErrorResponse};
+use crate::handlers::auth::current_user;
+use crate::handlers::challenges::authorize_challenge_moderator;
+use crate::models::challenge::{ChallengeError, TemporalChallenge};
+use crate::models::group::{
+    CreateGroupRequest, GroupError, GroupResponse, JoinGroupRequest, ParticipantGroup,
+};
+use crate::routes::AppState;
+
+/// Create a participant group in a challenge, the caller becomes its captain
+/// POST /challenges/{challenge_id}/groups
+pub async fn create_group(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(challenge_id): Path<i32>,
+    Json(request): Json<CreateGroupRequest>,
+) -> Result<(StatusCode, Json<GroupResponse>), (StatusCode, Json<ErrorResponse>)> {
+    tracing::info!(
+        "Group creation from user: {} for challenge: {}",
+        auth_user.username,
+        challenge_id
+    );
+
+    let user = current_user(&state, &auth_user).await?;
+    ensure_groups_open(&state, challenge_id).await?;
+
+    let group = ParticipantGroup::create(
+        &state.pool,
+        challenge_id,
+        user.user_id,
+        &request.group_name,
+        request.max_size,
+    )
+    .await
+    .map_err(group_error_response)?;
+
+    tracing::info!(
+        "Group {} created in challenge {} with captain: {}",
+        group.group_id,
+        challenge_id,
+        auth_user.username
+    );
+
+    Ok((
+        StatusCode::CREATED,
+        Json(group_response(&state, group).await?),
+    ))
+}
+
+/// Join a participant group with its join code
+/// POST /challenges/{challenge_id}/groups/join
+pub async fn join_group(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(challenge_id): Path<i32>,
+    Json(request): Json<JoinGroupRequest>,
+) -> Result<Json<GroupResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let user = current_user(&state, &auth_user).await?;
+    ensure_groups_open(&state, challenge_id).await?;
+
+    let group = ParticipantGroup::join(&state.pool, challenge_id, &request.join_code, user.user_id)
+        .await
+        .map_err(group_error_response)?;
+
+    tracing::info!(
+        "User {} joined group {} in challenge {}",
+        auth_user.username,
+        group.group_id,
+        challenge_id
+    );
+
+    Ok(Json(group_response(&state, group).await?))
+}
+
+/// Leave the current group and play individually again
+/// POST /challenges/{challenge_id}/groups/leave
+pub async fn leave_group(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(challenge_id): Path<i32>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    let user = current_user(&state, &auth_user).await?;
+    ensure_groups_open(&state, challenge_id).await?;
+
+    ParticipantGroup::leave(&state.pool, challenge_id, user.user_id)
+        .await
+        .map_err(group_error_response)?;
+
+    tracing::info!(
+        "User {} left their group in challenge {}",
+        auth_user.username,
+        challenge_id
+    );
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Get the caller's group in a challenge
+/// GET /challenges/{challenge_id}/groups/mine
+pub async fn get_my_group(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(challenge_id): Path<i32>,
+) -> Result<Json<GroupResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let user = current_user(&state, &auth_user).await?;
+
+    let group = ParticipantGroup::get_for_user(&state.pool, challenge_id, user.user_id)
+        .await
+        .map_err(group_error_response)?
+        .ok_or_else(|| group_error_response(GroupError::NotInGroup))?;
+
+    Ok(Json(group_response(&state, group).await?))
+}
+
+/// Remove a member from the caller's group, only the captain may do this
+/// DELETE /challenges/{challenge_id}/groups/members/{user_id}
+pub async fn remove_group_member(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path((challenge_id, member_user_id)): Path<(i32, i32)>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    let user = current_user(&state, &auth_user).await?;
+    ensure_groups_open(&state, challenge_id).await?;
+
+    ParticipantGroup::remove_by_captain(&state.pool, challenge_id, user.user_id, member_user_id)
+        .await
+        .map_err(group_error_response)?;
+
+    tracing::info!(
+        "User {} removed from their group in challenge {} by captain: {}",
+        member_user_id,
+        challenge_id,
+        auth_user.username
+    );
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List the participant groups of a challenge
+/// GET /challenges/{challenge_id}/groups
+pub async fn list_groups(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(challenge_id): Path<i32>,
+) -> Result<Json<Vec<GroupResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    authorize_challenge_moderator(&state, &auth_user, challenge_id).await?;
+
+    let groups = ParticipantGroup::get_for_challenge(&state.pool, challenge_id)
+        .await
+        .map_err(group_error_response)?;
+
+    let mut responses = Vec::with_capacity(groups.len());
+    for group in groups {
+        responses.push(group_response(&state, group).await?);
+    }
+
+    Ok(Json(responses))
+}
+
+/// Groups are formed before the challenge starts and stay fixed while it runs
+async fn ensure_groups_open(
+    state: &AppState,
+    challenge_id: i32,
+) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    let challenge_data = TemporalChallenge::get_current_by_id(&state.pool, challenge_id)
+        .await
+        .and_then(|challenge| challenge.get_challenge_data())
+        .map_err(|e| match e {
+            ChallengeError::ChallengeNotFound => (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    message: "Challenge not found".to_string(),
+                }),
+            ),
+            e => {
+                tracing::error!("Failed to get challenge: {}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse {
+                        message: "Failed to get challenge".to_string(),
+                    }),
+                )
+            }
+        })?;
+
+    if challenge_data.actual_start_time.is_some() {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                message: "Groups can only be changed before the challenge starts".to_string(),
+            }),
+        ));
+    }
+
+    Ok(())
+}
+
+async fn group_response(
+    state: &AppState,
+    group: ParticipantGroup,
+) -> Result<GroupResponse, (StatusCode, Json<ErrorResponse>)> {
+    let members = group
+        .members(&state.pool)
+        .await
+        .map_err(group_error_response)?;
+
+    Ok(GroupResponse { group, members })
+}
+
+fn group_error_response(error: GroupError) -> (StatusCode, Json<ErrorResponse>) {
+    let status = match &error {
+        GroupError::NotParticipant | GroupError::NotCaptain => StatusCode::FORBIDDEN,
+        GroupError::NotInGroup | GroupError::GroupNotFound => StatusCode::NOT_FOUND,
+        GroupError::AlreadyInGroup | GroupError::GroupFull | GroupError::AlreadyBegun => {
+            StatusCode::CONFLICT
+        }
+        GroupError::InvalidGroupName | GroupError::InvalidGroupSize => StatusCode::BAD_REQUEST,
+        GroupError::DatabaseError(_) => {
+            tracing::error!("Group operation failed with error: {}", error);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    message: "Group operation failed".to_string(),
+                }),
+            );
+        }
+    };
+
+    (
+        status,
+        Json(ErrorResponse {
+            message: error.to_string(),
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_error_status_mapping() {
+        assert_eq!(
+            group_error_response(GroupError::GroupFull).0,
+            StatusCode::CONFLICT
+        );
+        assert_eq!(
+            group_error_response(GroupError::NotParticipant).0,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            group_error_response(GroupError::InvalidGroupSize).0,
+            StatusCode::BAD_REQUEST
+        );
+    }
+}
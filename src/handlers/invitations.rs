@@ -0,0 +1,466 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{Duration, Utc};
+
+use crate::auth::{AuthError, AuthenticatedUser, ErrorResponse};
+use crate::handlers::auth::current_user;
+use crate::handlers::challenges::authorize_challenge_moderator;
+use crate::models::challenge::{ChallengeError, TemporalChallenge};
+use crate::models::invitation::{
+    normalize_email, AcceptInvitationRequest, ChallengeInvitation, CreateInvitationsRequest,
+    InvitationAcceptedResponse, InvitationDetails, InvitationError, InvitationListQuery,
+    InvitationStatus, InvitationTokenRequest, InvitationsResponse, DEFAULT_INVITATION_HOURS,
+};
+use crate::models::user::User;
+use crate::routes::AppState;
+use crate::services::email_service::invitation_email;
+
+/// Invite users to a challenge by email address
+/// POST /challenges/{challenge_id}/invitations
+pub async fn create_invitations(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(challenge_id): Path<i32>,
+    Json(request): Json<CreateInvitationsRequest>,
+) -> Result<(StatusCode, Json<InvitationsResponse>), (StatusCode, Json<ErrorResponse>)> {
+    tracing::info!(
+        "Email invitations from user: {} for challenge: {}, {} addresses",
+        auth_user.username,
+        challenge_id,
+        request.emails.len()
+    );
+
+    let (moderator, temporal_challenge, _challenge_data) =
+        authorize_challenge_moderator(&state, &auth_user, challenge_id).await?;
+
+    if request.emails.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                message: "At least one email address is required".to_string(),
+            }),
+        ));
+    }
+
+    let emails = request
+        .emails
+        .iter()
+        .map(|email| normalize_email(email))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    message: e.to_string(),
+                }),
+            )
+        })?;
+
+    let hours = request
+        .expires_in_hours
+        .unwrap_or(DEFAULT_INVITATION_HOURS)
+        .clamp(1, 24 * 30);
+    let expires_at = Utc::now() + Duration::hours(hours);
+
+    let mut invitations = Vec::with_capacity(emails.len());
+
+    for email in emails {
+        let invitation = ChallengeInvitation::create_or_refresh(
+            &state.pool,
+            challenge_id,
+            &email,
+            moderator.user_id,
+            expires_at,
+            None,
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!("Invitation creation failed with error: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    message: "Invitation creation failed".to_string(),
+                }),
+            )
+        })?;
+
+        // Answered invitations are reported back but not sent again
+        if invitation.status == InvitationStatus::Pending {
+            deliver_invitation(&state, &temporal_challenge, &invitation).await;
+        }
+
+        invitations.push(invitation);
+    }
+
+    Ok((
+        StatusCode::CREATED,
+        Json(InvitationsResponse {
+            challenge_id,
+            invitations,
+        }),
+    ))
+}
+
+/// Look up the invitation behind an invitation link
+/// GET /invitations/{token}
+pub async fn get_invitation(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> Result<Json<InvitationDetails>, (StatusCode, Json<ErrorResponse>)> {
+    let invitation = resolve_invitation_token(&state, &token).await?;
+
+    let challenge = TemporalChallenge::get_current_by_id(&state.pool, invitation.challenge_id)
+        .await
+        .map_err(|e| match e {
+            ChallengeError::ChallengeNotFound => (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    message: "Challenge not found".to_string(),
+                }),
+            ),
+            e => {
+                tracing::error!("Failed to get challenge: {}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse {
+                        message: "Failed to get challenge".to_string(),
+                    }),
+                )
+            }
+        })?;
+
+    Ok(Json(InvitationDetails {
+        invitation,
+        challenge_name: challenge.challenge_name,
+        planned_start_time: challenge.planned_start_time,
+    }))
+}
+
+/// Accept an invitation from an invitation link and join the challenge
+/// POST /invitations/accept
+pub async fn accept_invitation(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(request): Json<InvitationTokenRequest>,
+) -> Result<Json<InvitationAcceptedResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let user = current_user(&state, &auth_user).await?;
+    let invitation = resolve_invitation_token(&state, &request.token).await?;
+
+    accept_as_user(&state, &user, invitation, request.nickname).await
+}
+
+/// Decline an invitation from an invitation link
+/// POST /invitations/decline
+pub async fn decline_invitation(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(request): Json<InvitationTokenRequest>,
+) -> Result<Json<ChallengeInvitation>, (StatusCode, Json<ErrorResponse>)> {
+    let user = current_user(&state, &auth_user).await?;
+    let invitation = resolve_invitation_token(&state, &request.token).await?;
+
+    decline_as_user(&state, &user, invitation).await
+}
+
+/// List the invitations addressed to the current user, optionally filtered by status
+/// GET /users/invitations
+pub async fn list_my_invitations(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(query): Query<InvitationListQuery>,
+) -> Result<Json<Vec<InvitationDetails>>, (StatusCode, Json<ErrorResponse>)> {
+    let user = current_user(&state, &auth_user).await?;
+
+    ChallengeInvitation::get_details_for_user(&state.pool, user.user_id, query.status)
+        .await
+        .map(Json)
+        .map_err(invitation_error_response)
+}
+
+/// Accept one of the current user's invitations and join the challenge
+/// POST /users/invitations/{invitation_id}/accept
+pub async fn accept_my_invitation(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(invitation_id): Path<i32>,
+    request: Option<Json<AcceptInvitationRequest>>,
+) -> Result<Json<InvitationAcceptedResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let user = current_user(&state, &auth_user).await?;
+    let invitation = ChallengeInvitation::get_by_id(&state.pool, invitation_id)
+        .await
+        .map_err(invitation_error_response)?;
+    let nickname = request.and_then(|Json(request)| request.nickname);
+
+    accept_as_user(&state, &user, invitation, nickname).await
+}
+
+/// Decline one of the current user's invitations
+/// POST /users/invitations/{invitation_id}/decline
+pub async fn decline_my_invitation(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(invitation_id): Path<i32>,
+) -> Result<Json<ChallengeInvitation>, (StatusCode, Json<ErrorResponse>)> {
+    let user = current_user(&state, &auth_user).await?;
+    let invitation = ChallengeInvitation::get_by_id(&state.pool, invitation_id)
+        .await
+        .map_err(invitation_error_response)?;
+
+    decline_as_user(&state, &user, invitation).await
+}
+
+/// List the invitations of a challenge, optionally filtered by status
+/// GET /challenges/{challenge_id}/invitations
+pub async fn list_challenge_invitations(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(challenge_id): Path<i32>,
+    Query(query): Query<InvitationListQuery>,
+) -> Result<Json<InvitationsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    authorize_challenge_moderator(&state, &auth_user, challenge_id).await?;
+
+    let invitations =
+        ChallengeInvitation::get_for_challenge(&state.pool, challenge_id, query.status)
+            .await
+            .map_err(invitation_error_response)?;
+
+    Ok(Json(InvitationsResponse {
+        challenge_id,
+        invitations,
+    }))
+}
+
+/// Revoke a pending or accepted invitation; accepted ones only before the challenge starts
+/// DELETE /challenges/{challenge_id}/invitations/{invitation_id}
+pub async fn revoke_invitation(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path((challenge_id, invitation_id)): Path<(i32, i32)>,
+) -> Result<Json<ChallengeInvitation>, (StatusCode, Json<ErrorResponse>)> {
+    let (_moderator, _temporal_challenge, challenge_data) =
+        authorize_challenge_moderator(&state, &auth_user, challenge_id).await?;
+
+    let mut invitation = ChallengeInvitation::get_by_id(&state.pool, invitation_id)
+        .await
+        .map_err(invitation_error_response)?;
+    if invitation.challenge_id != challenge_id {
+        return Err(invitation_error_response(
+            InvitationError::InvitationNotFound,
+        ));
+    }
+
+    if invitation.status == InvitationStatus::Accepted && challenge_data.actual_start_time.is_some()
+    {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                message: "Challenge has already started".to_string(),
+            }),
+        ));
+    }
+
+    invitation
+        .revoke(&state.pool, challenge_data.actual_start_time.is_some())
+        .await
+        .map_err(invitation_error_response)?;
+
+    tracing::info!(
+        "Invitation {} for challenge {} revoked by user: {}",
+        invitation_id,
+        challenge_id,
+        auth_user.username
+    );
+
+    Ok(Json(invitation))
+}
+
+async fn accept_as_user(
+    state: &AppState,
+    user: &User,
+    mut invitation: ChallengeInvitation,
+    nickname: Option<String>,
+) -> Result<Json<InvitationAcceptedResponse>, (StatusCode, Json<ErrorResponse>)> {
+    match invitation.accept(&state.pool, user, nickname).await {
+        Ok(participant) => {
+            tracing::info!(
+                "Invitation {} accepted by user: {}",
+                invitation.invitation_id,
+                user.username
+            );
+            Ok(Json(InvitationAcceptedResponse {
+                invitation,
+                participant_id: participant.participant_id,
+            }))
+        }
+        Err(e) => Err(invitation_error_response(e)),
+    }
+}
+
+async fn decline_as_user(
+    state: &AppState,
+    user: &User,
+    mut invitation: ChallengeInvitation,
+) -> Result<Json<ChallengeInvitation>, (StatusCode, Json<ErrorResponse>)> {
+    match invitation.decline(&state.pool, user).await {
+        Ok(()) => {
+            tracing::info!(
+                "Invitation {} declined by user: {}",
+                invitation.invitation_id,
+                user.username
+            );
+            Ok(Json(invitation))
+        }
+        Err(e) => Err(invitation_error_response(e)),
+    }
+}
+
+/// Send the invitation email, and a push notification when the invitee is already registered
+pub(crate) async fn deliver_invitation(
+    state: &AppState,
+    challenge: &TemporalChallenge,
+    invitation: &ChallengeInvitation,
+) {
+    let token = match state.auth_state.jwt_service.create_invitation_token(
+        invitation.invitation_id,
+        invitation.challenge_id,
+        &invitation.email,
+        invitation.expires_at,
+    ) {
+        Ok(token) => token,
+        Err(e) => {
+            tracing::error!("Failed to sign invitation token: {}", e);
+            return;
+        }
+    };
+
+    let email = invitation_email(
+        &invitation.email,
+        &challenge.challenge_name,
+        &state.app_base_url,
+        &token,
+        invitation.invited_user_id.is_some(),
+    );
+
+    // Delivery problems are logged; the moderator can re-send by inviting again
+    if let Err(e) = state.mailer.send(&email).await {
+        tracing::warn!(
+            "Failed to send invitation {} email: {}",
+            invitation.invitation_id,
+            e
+        );
+    }
+
+    if let Some(user_id) = invitation.invited_user_id {
+        if let Err(e) = state
+            .notification_service
+            .notify_users(
+                &[user_id],
+                Some(invitation.challenge_id),
+                &format!("Invitation to {}", challenge.challenge_name),
+                "You have been invited to a scavenger hunt",
+                serde_json::json!({
+                    "challenge-id": invitation.challenge_id,
+                    "invitation-id": invitation.invitation_id,
+                }),
+            )
+            .await
+        {
+            tracing::warn!("Failed to queue invitation notification: {}", e);
+        }
+    }
+}
+
+/// Validate a signed invitation token and load the invitation it refers to
+async fn resolve_invitation_token(
+    state: &AppState,
+    token: &str,
+) -> Result<ChallengeInvitation, (StatusCode, Json<ErrorResponse>)> {
+    let claims = state
+        .auth_state
+        .jwt_service
+        .validate_invitation_token(token)
+        .map_err(|e| match e {
+            AuthError::TokenExpired => (
+                StatusCode::GONE,
+                Json(ErrorResponse {
+                    message: "Invitation has expired".to_string(),
+                }),
+            ),
+            _ => (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    message: "Invalid invitation token".to_string(),
+                }),
+            ),
+        })?;
+
+    let invitation = ChallengeInvitation::get_by_id(&state.pool, claims.inv)
+        .await
+        .map_err(invitation_error_response)?;
+
+    // A token only stays valid for the invitation it was issued for
+    if invitation.challenge_id != claims.clg || invitation.email != claims.upn {
+        return Err(invitation_error_response(
+            InvitationError::InvitationNotFound,
+        ));
+    }
+
+    Ok(invitation)
+}
+
+fn invitation_error_response(error: InvitationError) -> (StatusCode, Json<ErrorResponse>) {
+    let status = match &error {
+        InvitationError::InvitationNotFound => StatusCode::NOT_FOUND,
+        InvitationError::InvitationExpired | InvitationError::InvitationRevoked => StatusCode::GONE,
+        InvitationError::AlreadyResponded | InvitationError::NotRevocable => StatusCode::CONFLICT,
+        InvitationError::WrongRecipient => StatusCode::FORBIDDEN,
+        InvitationError::InvalidEmail(_) => StatusCode::BAD_REQUEST,
+        InvitationError::DatabaseError(_)
+        | InvitationError::ChallengeError(_)
+        | InvitationError::UserError(_)
+        | InvitationError::GroupError(_) => {
+            tracing::error!("Invitation operation failed with error: {}", error);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    message: "Invitation operation failed".to_string(),
+                }),
+            );
+        }
+    };
+
+    (
+        status,
+        Json(ErrorResponse {
+            message: error.to_string(),
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invitation_error_status_mapping() {
+        assert_eq!(
+            invitation_error_response(InvitationError::InvitationExpired).0,
+            StatusCode::GONE
+        );
+        assert_eq!(
+            invitation_error_response(InvitationError::AlreadyResponded).0,
+            StatusCode::CONFLICT
+        );
+        assert_eq!(
+            invitation_error_response(InvitationError::WrongRecipient).0,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            invitation_error_response(InvitationError::NotRevocable).0,
+            StatusCode::CONFLICT
+        );
+    }
+}
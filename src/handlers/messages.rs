@@ -0,0 +1,295 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Deserialize;
+
+use crate::auth::{AuthenticatedParticipant, AuthenticatedUser, ErrorResponse};
+use crate::handlers::challenges::authorize_challenge_moderator;
+use crate::models::message::{
+    AnnouncementRequest, ChallengeMessage, InboxMessage, MarkReadRequest, MessageAudience,
+    MessageError, MessageSentResponse, MessageType, ReleaseHintRequest,
+};
+use crate::routes::AppState;
+use crate::services::{ChallengeEvent, ChallengeEventType};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct InboxQuery {
+    pub since: Option<i32>,
+    #[serde(default)]
+    pub unread: bool,
+}
+
+/// Release a waypoint hint to one participant, a group, or everyone at a waypoint
+/// POST /challenges/{challenge_id}/hints
+pub async fn release_hint(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(challenge_id): Path<i32>,
+    Json(request): Json<ReleaseHintRequest>,
+) -> Result<(StatusCode, Json<MessageSentResponse>), (StatusCode, Json<ErrorResponse>)> {
+    tracing::info!(
+        "Hint release from user: {} for challenge: {}, waypoint: {}",
+        auth_user.username,
+        challenge_id,
+        request.waypoint_sequence
+    );
+
+    let (moderator, _temporal_challenge, challenge_data) =
+        authorize_challenge_moderator(&state, &auth_user, challenge_id).await?;
+
+    let waypoint = challenge_data
+        .waypoints
+        .iter()
+        .find(|w| w.waypoint_sequence == request.waypoint_sequence)
+        .ok_or((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                message: "Waypoint not found".to_string(),
+            }),
+        ))?;
+
+    // Free text takes precedence over one of the prepared hints
+    let hint_text = match (&request.text, request.hint_index) {
+        (Some(text), _) => text.clone(),
+        (None, Some(index)) => waypoint.hints.get(index).cloned().ok_or((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                message: format!(
+                    "Waypoint {} has no hint at index {index}",
+                    request.waypoint_sequence
+                ),
+            }),
+        ))?,
+        (None, None) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    message: "Either hint-index or text must be provided".to_string(),
+                }),
+            ));
+        }
+    };
+
+    send_message(
+        &state,
+        challenge_id,
+        moderator.user_id,
+        MessageType::Hint,
+        Some(request.waypoint_sequence),
+        &hint_text,
+        &request.audience,
+    )
+    .await
+}
+
+/// Broadcast a free-text announcement, to every participant unless an audience is given
+/// POST /challenges/{challenge_id}/announcements
+pub async fn send_announcement(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(challenge_id): Path<i32>,
+    Json(request): Json<AnnouncementRequest>,
+) -> Result<(StatusCode, Json<MessageSentResponse>), (StatusCode, Json<ErrorResponse>)> {
+    tracing::info!(
+        "Announcement from user: {} for challenge: {}",
+        auth_user.username,
+        challenge_id
+    );
+
+    let (moderator, _temporal_challenge, _challenge_data) =
+        authorize_challenge_moderator(&state, &auth_user, challenge_id).await?;
+
+    let audience = request.audience.unwrap_or(MessageAudience::All);
+
+    send_message(
+        &state,
+        challenge_id,
+        moderator.user_id,
+        MessageType::Announcement,
+        None,
+        &request.message,
+        &audience,
+    )
+    .await
+}
+
+/// Poll the participant inbox
+/// GET /challenges/participant/inbox
+pub async fn get_participant_inbox(
+    auth_participant: AuthenticatedParticipant,
+    State(state): State<AppState>,
+    Query(query): Query<InboxQuery>,
+) -> Result<Json<Vec<InboxMessage>>, (StatusCode, Json<ErrorResponse>)> {
+    let participant_id = auth_participant.participant_uuid()?;
+
+    match InboxMessage::get_for_participant(&state.pool, participant_id, query.since, query.unread)
+        .await
+    {
+        Ok(messages) => Ok(Json(messages)),
+        Err(e) => {
+            tracing::error!("Inbox retrieval failed with error: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    message: "Inbox retrieval failed".to_string(),
+                }),
+            ))
+        }
+    }
+}
+
+/// Mark inbox messages as read
+/// POST /challenges/participant/inbox/read
+pub async fn mark_inbox_read(
+    auth_participant: AuthenticatedParticipant,
+    State(state): State<AppState>,
+    Json(request): Json<MarkReadRequest>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    let participant_id = auth_participant.participant_uuid()?;
+
+    match InboxMessage::mark_read(&state.pool, participant_id, &request.message_ids).await {
+        Ok(_) => Ok(StatusCode::NO_CONTENT),
+        Err(e) => {
+            tracing::error!("Marking inbox messages read failed with error: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    message: "Failed to update inbox".to_string(),
+                }),
+            ))
+        }
+    }
+}
+
+async fn send_message(
+    state: &AppState,
+    challenge_id: i32,
+    sender_user_id: i32,
+    message_type: MessageType,
+    waypoint_sequence: Option<i32>,
+    message_body: &str,
+    audience: &MessageAudience,
+) -> Result<(StatusCode, Json<MessageSentResponse>), (StatusCode, Json<ErrorResponse>)> {
+    let recipients = audience
+        .resolve(&state.pool, challenge_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to resolve message audience: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    message: "Failed to resolve message recipients".to_string(),
+                }),
+            )
+        })?;
+
+    match ChallengeMessage::create_and_deliver(
+        &state.pool,
+        challenge_id,
+        sender_user_id,
+        message_type,
+        waypoint_sequence,
+        message_body,
+        &recipients,
+    )
+    .await
+    {
+        Ok((message, delivered)) => {
+            tracing::info!(
+                "Message {} delivered to {} participants of challenge {}",
+                message.message_id,
+                delivered,
+                challenge_id
+            );
+
+            let event_type = match message.message_type {
+                MessageType::Hint => ChallengeEventType::Hint,
+                MessageType::Announcement => ChallengeEventType::Announcement,
+            };
+            let mut event = ChallengeEvent::new(
+                event_type,
+                challenge_id,
+                serde_json::json!({
+                    "message-id": message.message_id,
+                    "message": message.message_body,
+                }),
+            )
+            .with_waypoint(message.waypoint_sequence);
+            if *audience != MessageAudience::All {
+                event = event.with_recipients(recipients);
+            }
+            state.event_hub.publish(event);
+
+            Ok((
+                StatusCode::CREATED,
+                Json(MessageSentResponse {
+                    message_id: message.message_id,
+                    challenge_id,
+                    message_type: message.message_type,
+                    message: message.message_body,
+                    recipients: delivered,
+                }),
+            ))
+        }
+        Err(MessageError::EmptyMessage) => Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                message: "Message must not be empty".to_string(),
+            }),
+        )),
+        Err(MessageError::NoRecipients) => {
+            tracing::warn!(
+                "Message for challenge {} matched no participants",
+                challenge_id
+            );
+            Err((
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    message: "No participants match the requested audience".to_string(),
+                }),
+            ))
+        }
+        Err(e) => {
+            tracing::error!("Message delivery failed with error: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    message: "Message delivery failed".to_string(),
+                }),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_announcement_request_deserialization() {
+        let json = r#"{"message": "Meet at the fountain in 10 minutes"}"#;
+
+        let request: AnnouncementRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(request.message, "Meet at the fountain in 10 minutes");
+        assert!(request.audience.is_none());
+    }
+
+    #[test]
+    fn test_message_sent_response_serialization() {
+        let response = MessageSentResponse {
+            message_id: 1,
+            challenge_id: 7,
+            message_type: MessageType::Hint,
+            message: "Look up".to_string(),
+            recipients: 3,
+        };
+
+        let json = serde_json::to_string(&response).unwrap();
+        assert!(json.contains("message-id"));
+        assert!(json.contains("challenge-id"));
+        assert!(json.contains("\"HINT\""));
+        assert!(json.contains("\"recipients\":3"));
+    }
+}
@@ -1,10 +1,42 @@
 pub mod auth;
 pub mod challenges;
+pub mod devices;
+pub mod events;
+pub mod groups;
 pub mod health;
-// TODO: Update waypoints handler for temporal challenge system
-// pub mod waypoints;
+pub mod invitations;
+pub mod messages;
+pub mod moderators;
+pub mod participants;
+pub mod proofs;
+pub mod reviews;
+pub mod waypoints;
 
 pub use auth::{create_participant_token, login_user, register_user};
-pub use challenges::{create_challenge, get_challenge, invite_participant, start_challenge};
+pub use challenges::{
+    begin_challenge, create_challenge, extend_challenge, get_challenge, invite_participant,
+    pause_challenge, resume_challenge, start_challenge,
+};
+pub use devices::{list_devices, register_device, unregister_device};
+pub use events::stream_challenge_events;
+pub use groups::{
+    create_group, get_my_group, join_group, leave_group, list_groups, remove_group_member,
+};
 pub use health::health_check_handler;
-// pub use waypoints::{check_in_waypoint, submit_waypoint_proof};
+pub use invitations::{
+    accept_invitation, accept_my_invitation, create_invitations, decline_invitation,
+    decline_my_invitation, get_invitation, list_challenge_invitations, list_my_invitations,
+    revoke_invitation,
+};
+pub use messages::{get_participant_inbox, mark_inbox_read, release_hint, send_announcement};
+pub use moderators::{add_co_moderator, list_moderators, remove_co_moderator};
+pub use participants::{
+    disqualify_participant, forfeit_challenge, get_challenge_standings, get_participant_report,
+    override_waypoint, reinstate_participant,
+};
+pub use proofs::{get_proof_status, image_checker_callback};
+pub use reviews::{
+    approve_proof_review, claim_proof_review, get_proof_review_image, list_proof_reviews,
+    reject_proof_review,
+};
+pub use waypoints::{appeal_waypoint_proof, check_in_waypoint, submit_waypoint_proof};
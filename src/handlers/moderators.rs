@@ -0,0 +1,203 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+
+use crate::auth::{AuthenticatedUser, ErrorResponse};
+use crate::handlers::challenges::authorize_challenge_moderator;
+use crate::models::challenge::{
+    ChallengeData, ChallengeError, ChallengeModeratorInfo, ModeratorRole,
+};
+use crate::models::user::{User, UserError};
+use crate::routes::AppState;
+
+/// List the owner and co-moderators of a challenge
+/// GET /challenges/{challenge_id}/moderators
+pub async fn list_moderators(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(challenge_id): Path<i32>,
+) -> Result<Json<Vec<ChallengeModeratorInfo>>, (StatusCode, Json<ErrorResponse>)> {
+    let (_moderator, _temporal_challenge, challenge_data) =
+        authorize_challenge_moderator(&state, &auth_user, challenge_id).await?;
+
+    moderator_list(&state, &challenge_data).await.map(Json)
+}
+
+/// Add a co-moderator, only the owner (or a game admin) manages the moderator set. Users
+/// playing in the challenge cannot moderate it.
+/// POST /challenges/{challenge_id}/moderators/{user_id}
+pub async fn add_co_moderator(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path((challenge_id, user_id)): Path<(i32, i32)>,
+) -> Result<(StatusCode, Json<Vec<ChallengeModeratorInfo>>), (StatusCode, Json<ErrorResponse>)> {
+    let (moderator, temporal_challenge, challenge_data) =
+        authorize_challenge_moderator(&state, &auth_user, challenge_id).await?;
+    ensure_owner(&auth_user, &challenge_data, moderator.user_id)?;
+
+    let co_moderator = User::get_by_id(&state.pool, user_id)
+        .await
+        .map_err(user_error_response)?;
+
+    let updated = temporal_challenge
+        .add_co_moderator(&state.pool, co_moderator.user_id)
+        .await
+        .map_err(moderator_error_response)?;
+
+    tracing::info!(
+        "User {} added as co-moderator of challenge {} by user: {}",
+        user_id,
+        challenge_id,
+        auth_user.username
+    );
+
+    let challenge_data = updated
+        .get_challenge_data()
+        .map_err(moderator_error_response)?;
+    let moderators = moderator_list(&state, &challenge_data).await?;
+
+    Ok((StatusCode::CREATED, Json(moderators)))
+}
+
+/// Remove a co-moderator, co-moderators may also step down themselves
+/// DELETE /challenges/{challenge_id}/moderators/{user_id}
+pub async fn remove_co_moderator(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path((challenge_id, user_id)): Path<(i32, i32)>,
+) -> Result<Json<Vec<ChallengeModeratorInfo>>, (StatusCode, Json<ErrorResponse>)> {
+    let (moderator, temporal_challenge, challenge_data) =
+        authorize_challenge_moderator(&state, &auth_user, challenge_id).await?;
+    if moderator.user_id != user_id {
+        ensure_owner(&auth_user, &challenge_data, moderator.user_id)?;
+    }
+
+    let updated = temporal_challenge
+        .remove_co_moderator(&state.pool, user_id)
+        .await
+        .map_err(moderator_error_response)?;
+
+    tracing::info!(
+        "User {} removed as co-moderator of challenge {} by user: {}",
+        user_id,
+        challenge_id,
+        auth_user.username
+    );
+
+    let challenge_data = updated
+        .get_challenge_data()
+        .map_err(moderator_error_response)?;
+    moderator_list(&state, &challenge_data).await.map(Json)
+}
+
+fn ensure_owner(
+    auth_user: &AuthenticatedUser,
+    challenge_data: &ChallengeData,
+    user_id: i32,
+) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    if challenge_data.moderator_role(user_id) == Some(ModeratorRole::Owner)
+        || auth_user.has_role("game.admin")
+    {
+        return Ok(());
+    }
+
+    Err((
+        StatusCode::FORBIDDEN,
+        Json(ErrorResponse {
+            message: "Only the challenge owner can manage moderators".to_string(),
+        }),
+    ))
+}
+
+async fn moderator_list(
+    state: &AppState,
+    challenge_data: &ChallengeData,
+) -> Result<Vec<ChallengeModeratorInfo>, (StatusCode, Json<ErrorResponse>)> {
+    let mut moderators = Vec::with_capacity(challenge_data.co_moderators.len() + 1);
+    let user_ids = std::iter::once(challenge_data.challenge_moderator)
+        .chain(challenge_data.co_moderators.iter().copied());
+
+    for user_id in user_ids {
+        let nickname = match User::get_by_id(&state.pool, user_id).await {
+            Ok(user) => user.nickname,
+            Err(UserError::UserNotFound) => None,
+            Err(e) => return Err(user_error_response(e)),
+        };
+        moderators.push(ChallengeModeratorInfo {
+            user_id,
+            nickname,
+            role: challenge_data
+                .moderator_role(user_id)
+                .unwrap_or(ModeratorRole::CoModerator),
+        });
+    }
+
+    Ok(moderators)
+}
+
+fn user_error_response(error: UserError) -> (StatusCode, Json<ErrorResponse>) {
+    match error {
+        UserError::UserNotFound => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                message: "User not found".to_string(),
+            }),
+        ),
+        e => {
+            tracing::error!("Moderator update failed with error: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    message: "Moderator update failed".to_string(),
+                }),
+            )
+        }
+    }
+}
+
+fn moderator_error_response(error: ChallengeError) -> (StatusCode, Json<ErrorResponse>) {
+    let (status, message) = match error {
+        ChallengeError::AlreadyModerator => (
+            StatusCode::CONFLICT,
+            "User already moderates this challenge",
+        ),
+        ChallengeError::NotModerator => (
+            StatusCode::NOT_FOUND,
+            "User is not a co-moderator of this challenge",
+        ),
+        ChallengeError::AlreadyParticipant => (
+            StatusCode::CONFLICT,
+            "User plays in this challenge and cannot moderate it",
+        ),
+        e => {
+            tracing::error!("Moderator update failed with error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Moderator update failed")
+        }
+    };
+
+    (
+        status,
+        Json(ErrorResponse {
+            message: message.to_string(),
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_moderator_error_mapping() {
+        assert_eq!(
+            moderator_error_response(ChallengeError::AlreadyModerator).0,
+            StatusCode::CONFLICT
+        );
+
+        let (status, Json(body)) = moderator_error_response(ChallengeError::NotModerator);
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body.message, "User is not a co-moderator of this challenge");
+    }
+}
@@ -0,0 +1,456 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::auth::{AuthenticatedParticipant, AuthenticatedUser, ErrorResponse};
+use crate::handlers::challenges::authorize_challenge_moderator;
+use crate::models::audit_log::{AuditLog, WaypointOverrideParams};
+use crate::models::challenge::{
+    ChallengeError, ChallengeParticipant, ChallengeStanding, ParticipantStatus,
+    ParticipantStatusRequest, RouteProgress, TemporalChallenge, WaypointOverrideRequest,
+    WaypointOverrideResponse,
+};
+use crate::models::group::ParticipantGroup;
+use crate::models::proof_job::ProofAppeal;
+use crate::routes::AppState;
+use crate::services::ChallengeEvent;
+
+/// Everything about a participant's game: where they are on the route and what became of
+/// their appeals
+#[derive(serde::Serialize)]
+pub struct ParticipantReport {
+    #[serde(rename = "participant-id")]
+    pub participant_id: Uuid,
+    pub status: ParticipantStatus,
+    pub challenge: ParticipantChallengeReport,
+    pub appeals: Vec<ProofAppeal>,
+    #[serde(rename = "appeals-per-waypoint")]
+    pub appeals_per_waypoint: i32,
+}
+
+#[derive(serde::Serialize)]
+pub struct ParticipantChallengeReport {
+    #[serde(rename = "challenge-id")]
+    pub challenge_id: i32,
+    #[serde(rename = "actual-start-time")]
+    pub actual_start_time: Option<DateTime<Utc>>, // The participant's own start when self-paced
+    #[serde(rename = "waypoint-num")]
+    pub waypoint_num: usize, // Waypoints on the route, bonus waypoints aside
+    #[serde(flatten)]
+    pub progress: RouteProgress,
+}
+
+/// The participant's full state report
+/// GET /challenges/participant/full
+pub async fn get_participant_report(
+    auth_participant: AuthenticatedParticipant,
+    State(state): State<AppState>,
+) -> Result<Json<ParticipantReport>, (StatusCode, Json<ErrorResponse>)> {
+    let participant_id = auth_participant.participant_uuid()?;
+    let report_error = |e: &dyn std::fmt::Display| {
+        tracing::error!("Participant report failed with error: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                message: "Participant report failed".to_string(),
+            }),
+        )
+    };
+
+    let participant = ChallengeParticipant::get_by_id(&state.pool, participant_id)
+        .await
+        .map_err(participant_status_error_response)?;
+    let challenge_data =
+        TemporalChallenge::get_current_by_id(&state.pool, participant.challenge_id)
+            .await
+            .and_then(|challenge| challenge.get_challenge_data())
+            .map_err(|e| report_error(&e))?;
+    let appeals = ProofAppeal::list_for_participant(&state.pool, participant_id)
+        .await
+        .map_err(|e| report_error(&e))?;
+
+    Ok(Json(ParticipantReport {
+        participant_id,
+        status: participant.participant_status,
+        challenge: ParticipantChallengeReport {
+            challenge_id: participant.challenge_id,
+            actual_start_time: participant.clock_started_at(&challenge_data),
+            waypoint_num: challenge_data
+                .waypoints
+                .iter()
+                .filter(|w| w.bonus.is_none())
+                .count(),
+            progress: participant.route_progress(&challenge_data),
+        },
+        appeals,
+        appeals_per_waypoint: challenge_data.appeals_per_waypoint,
+    }))
+}
+
+/// The challenge leaderboard: active participants ranked by route waypoints and bonus points
+/// GET /challenges/{challenge_id}/standings
+pub async fn get_challenge_standings(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(challenge_id): Path<i32>,
+) -> Result<Json<Vec<ChallengeStanding>>, (StatusCode, Json<ErrorResponse>)> {
+    let (_moderator, _temporal_challenge, challenge_data) =
+        authorize_challenge_moderator(&state, &auth_user, challenge_id).await?;
+
+    let participants =
+        ChallengeParticipant::get_participants_for_challenge(&state.pool, challenge_id)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to get participants for standings: {}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse {
+                        message: "Failed to get challenge standings".to_string(),
+                    }),
+                )
+            })?;
+
+    Ok(Json(challenge_data.standings(&participants)))
+}
+
+/// Withdraw from the challenge, a group forfeits through its captain
+/// POST /challenges/participant/forfeit
+pub async fn forfeit_challenge(
+    auth_participant: AuthenticatedParticipant,
+    State(state): State<AppState>,
+    request: Option<Json<ParticipantStatusRequest>>,
+) -> Result<Json<ChallengeParticipant>, (StatusCode, Json<ErrorResponse>)> {
+    let participant_id = auth_participant.participant_uuid()?;
+    let Json(request) = request.unwrap_or_default();
+
+    let participant = ChallengeParticipant::get_by_id(&state.pool, participant_id)
+        .await
+        .map_err(participant_status_error_response)?;
+
+    let group = ParticipantGroup::get_for_user(
+        &state.pool,
+        participant.challenge_id,
+        auth_participant.user_id,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to get participant group: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                message: "Failed to forfeit challenge".to_string(),
+            }),
+        )
+    })?;
+    if group.is_some_and(|group| group.captain_user_id != auth_participant.user_id) {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse {
+                message: "Only the group captain can forfeit for the group".to_string(),
+            }),
+        ));
+    }
+
+    let participant = change_participant_status(
+        &state,
+        participant,
+        auth_participant.user_id,
+        ParticipantStatus::Forfeited,
+        request.reason,
+    )
+    .await?;
+
+    Ok(Json(participant))
+}
+
+/// Disqualify a participant, their tokens stop working and they drop out of the standings
+/// POST /challenges/{challenge_id}/participants/{participant_id}/disqualify
+pub async fn disqualify_participant(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path((challenge_id, participant_id)): Path<(i32, Uuid)>,
+    Json(request): Json<ParticipantStatusRequest>,
+) -> Result<Json<ChallengeParticipant>, (StatusCode, Json<ErrorResponse>)> {
+    moderate_participant_status(
+        &state,
+        &auth_user,
+        challenge_id,
+        participant_id,
+        ParticipantStatus::Disqualified,
+        request,
+    )
+    .await
+    .map(Json)
+}
+
+/// Return a forfeited or disqualified participant to the game
+/// POST /challenges/{challenge_id}/participants/{participant_id}/reinstate
+pub async fn reinstate_participant(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path((challenge_id, participant_id)): Path<(i32, Uuid)>,
+    Json(request): Json<ParticipantStatusRequest>,
+) -> Result<Json<ChallengeParticipant>, (StatusCode, Json<ErrorResponse>)> {
+    moderate_participant_status(
+        &state,
+        &auth_user,
+        challenge_id,
+        participant_id,
+        ParticipantStatus::Active,
+        request,
+    )
+    .await
+    .map(Json)
+}
+
+/// Force-verify, reject or reset a participant's waypoint, overriding the image checker
+/// POST /challenges/{challenge_id}/participants/{participant_id}/waypoints/{waypoint_id}/override
+pub async fn override_waypoint(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path((challenge_id, participant_id, waypoint_sequence)): Path<(i32, Uuid, i32)>,
+    Json(request): Json<WaypointOverrideRequest>,
+) -> Result<Json<WaypointOverrideResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let (moderator, _temporal_challenge, challenge_data) =
+        authorize_challenge_moderator(&state, &auth_user, challenge_id).await?;
+
+    let justification = request.justification.trim();
+    if justification.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                message: "A justification is required".to_string(),
+            }),
+        ));
+    }
+
+    let mut participant = ChallengeParticipant::get_by_id(&state.pool, participant_id)
+        .await
+        .map_err(participant_status_error_response)?;
+    if participant.challenge_id != challenge_id {
+        return Err(participant_status_error_response(
+            ChallengeError::ParticipantNotFound,
+        ));
+    }
+
+    // Hold the participant's row until the override is recorded, so a proof checked
+    // meanwhile cannot slip in between
+    let mut tx = participant
+        .lock_for_update(&state.pool)
+        .await
+        .map_err(participant_status_error_response)?;
+    let previous_waypoint = participant.current_waypoint_id;
+    let previous_state = participant.current_state;
+
+    participant
+        .override_waypoint(&mut tx, &challenge_data, waypoint_sequence, request.action)
+        .await
+        .map_err(participant_status_error_response)?;
+
+    AuditLog::log_waypoint_overridden(
+        &mut tx,
+        WaypointOverrideParams {
+            user_id: moderator.user_id,
+            participant_id,
+            challenge_id,
+            waypoint_sequence,
+            action: request.action,
+            justification,
+            previous_waypoint,
+            previous_state,
+            current_waypoint: participant.current_waypoint_id,
+            state: participant.current_state,
+        },
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to log waypoint override: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                message: "Waypoint override failed".to_string(),
+            }),
+        )
+    })?;
+    tx.commit()
+        .await
+        .map_err(|e| participant_status_error_response(e.into()))?;
+
+    let changed = if challenge_data.bonus_waypoint(waypoint_sequence).is_some() {
+        Some((
+            waypoint_sequence,
+            participant.bonus_state(waypoint_sequence),
+        ))
+    } else {
+        participant
+            .current_waypoint_id
+            .map(|current_waypoint| (current_waypoint, participant.current_state))
+    };
+    if let Some((waypoint, waypoint_state)) = changed {
+        state
+            .event_hub
+            .publish(ChallengeEvent::waypoint_state_changed(
+                challenge_id,
+                participant_id,
+                waypoint,
+                waypoint_state,
+            ));
+    }
+
+    tracing::info!(
+        "Waypoint {} of participant {} in challenge {} overridden with {} by user: {}",
+        waypoint_sequence,
+        participant_id,
+        challenge_id,
+        request.action,
+        auth_user.username
+    );
+
+    Ok(Json(WaypointOverrideResponse {
+        participant_id,
+        overridden_waypoint: waypoint_sequence,
+        action: request.action,
+        progress: participant.route_progress(&challenge_data),
+    }))
+}
+
+/// Moderator status changes must give a reason
+async fn moderate_participant_status(
+    state: &AppState,
+    auth_user: &AuthenticatedUser,
+    challenge_id: i32,
+    participant_id: Uuid,
+    status: ParticipantStatus,
+    request: ParticipantStatusRequest,
+) -> Result<ChallengeParticipant, (StatusCode, Json<ErrorResponse>)> {
+    let (moderator, _temporal_challenge, _challenge_data) =
+        authorize_challenge_moderator(state, auth_user, challenge_id).await?;
+
+    let reason = request
+        .reason
+        .map(|reason| reason.trim().to_string())
+        .filter(|reason| !reason.is_empty())
+        .ok_or((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                message: "A reason is required".to_string(),
+            }),
+        ))?;
+
+    let participant = ChallengeParticipant::get_by_id(&state.pool, participant_id)
+        .await
+        .map_err(participant_status_error_response)?;
+    if participant.challenge_id != challenge_id {
+        return Err(participant_status_error_response(
+            ChallengeError::ParticipantNotFound,
+        ));
+    }
+
+    change_participant_status(state, participant, moderator.user_id, status, Some(reason)).await
+}
+
+/// Apply a status change, audit it and let the participant know
+async fn change_participant_status(
+    state: &AppState,
+    mut participant: ChallengeParticipant,
+    changed_by: i32,
+    status: ParticipantStatus,
+    reason: Option<String>,
+) -> Result<ChallengeParticipant, (StatusCode, Json<ErrorResponse>)> {
+    let previous_status = participant.participant_status;
+
+    participant
+        .change_status(&state.pool, status, reason.clone())
+        .await
+        .map_err(participant_status_error_response)?;
+
+    if let Err(e) = AuditLog::log_participant_status_changed(
+        &state.pool,
+        changed_by,
+        participant.participant_id,
+        participant.challenge_id,
+        previous_status,
+        status,
+        reason.as_deref(),
+    )
+    .await
+    {
+        tracing::warn!("Failed to log participant status change: {}", e);
+    }
+
+    // Also ends the participant's open event streams when they leave the game
+    state
+        .event_hub
+        .publish(ChallengeEvent::participant_status_changed(
+            participant.challenge_id,
+            participant.participant_id,
+            status,
+            reason.as_deref(),
+        ));
+
+    tracing::info!(
+        "Participant {} in challenge {} changed from {} to {} by user: {}",
+        participant.participant_id,
+        participant.challenge_id,
+        previous_status,
+        status,
+        changed_by
+    );
+
+    Ok(participant)
+}
+
+fn participant_status_error_response(error: ChallengeError) -> (StatusCode, Json<ErrorResponse>) {
+    let status = match &error {
+        ChallengeError::ParticipantNotFound | ChallengeError::WaypointNotFound => {
+            StatusCode::NOT_FOUND
+        }
+        ChallengeError::InvalidStatusChange { .. }
+        | ChallengeError::ParticipantNotActive
+        | ChallengeError::InvalidWaypointSequence => StatusCode::CONFLICT,
+        _ => {
+            tracing::error!("Participant status change failed with error: {}", error);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    message: "Participant status change failed".to_string(),
+                }),
+            );
+        }
+    };
+
+    (
+        status,
+        Json(ErrorResponse {
+            message: error.to_string(),
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_participant_status_error_mapping() {
+        let (status, Json(body)) =
+            participant_status_error_response(ChallengeError::InvalidStatusChange {
+                from: ParticipantStatus::Disqualified,
+                to: ParticipantStatus::Forfeited,
+            });
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(
+            body.message,
+            "Participant is DISQUALIFIED and cannot become FORFEITED"
+        );
+
+        assert_eq!(
+            participant_status_error_response(ChallengeError::ParticipantNotFound).0,
+            StatusCode::NOT_FOUND
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+use uuid::Uuid;
+
+use crate::auth::{AuthenticatedParticipant, ErrorResponse};
+use crate::models::proof_job::{ProofJob, ProofJobError, ProofJobStatus};
+use crate::routes::AppState;
+use crate::services::image_service::ValidationCallback;
+
+/// Header carrying `sha256=<hex HMAC-SHA256 of the body>` on image checker callbacks
+pub const CALLBACK_SIGNATURE_HEADER: &str = "x-image-checker-signature";
+
+#[derive(serde::Serialize)]
+pub struct CallbackResponse {
+    #[serde(rename = "processing-id")]
+    pub processing_id: Uuid,
+    pub status: ProofJobStatus,
+    /// False when the proof already had an outcome and the callback changed nothing
+    pub applied: bool,
+}
+
+/// Follow up on a photo proof: QUEUED and SUBMITTED while it is being verified, then
+/// ACCEPTED, REJECTED with the reasons, or FAILED when no verdict could be had. MANUAL_REVIEW
+/// while a moderator has to decide.
+/// GET /proofs/{processing_id}
+pub async fn get_proof_status(
+    auth_participant: AuthenticatedParticipant,
+    State(state): State<AppState>,
+    Path(processing_id): Path<Uuid>,
+) -> Result<Json<ProofJob>, (StatusCode, Json<ErrorResponse>)> {
+    let participant_id = auth_participant.participant_uuid()?;
+
+    let job = ProofJob::get_by_id(&state.pool, processing_id)
+        .await
+        .map_err(proof_job_error_response)?;
+
+    // Other participants' proofs are not theirs to know about
+    if job.participant_id != participant_id {
+        return Err(proof_job_error_response(ProofJobError::NotFound));
+    }
+
+    Ok(Json(job))
+}
+
+/// The image checker reports a verdict instead of waiting to be polled. The body is signed
+/// with IMAGE_CHECKER_CALLBACK_SECRET; verdicts for proofs that already have an outcome are
+/// acknowledged without effect, so the image checker can safely deliver a callback twice.
+/// POST /internal/image-checker/callback
+pub async fn image_checker_callback(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<CallbackResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let callback_error = |status: StatusCode, message: &str| {
+        (
+            status,
+            Json(ErrorResponse {
+                message: message.to_string(),
+            }),
+        )
+    };
+
+    let Some(secret) = state.image_checker_callback_secret.as_deref() else {
+        return Err(callback_error(
+            StatusCode::NOT_FOUND,
+            "Image checker callbacks are not enabled",
+        ));
+    };
+
+    let signature = headers
+        .get(CALLBACK_SIGNATURE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    if !ValidationCallback::verify_signature(secret, &body, signature) {
+        tracing::warn!("Refused image checker callback with a missing or invalid signature");
+        return Err(callback_error(
+            StatusCode::UNAUTHORIZED,
+            "Invalid callback signature",
+        ));
+    }
+
+    let callback: ValidationCallback = serde_json::from_slice(&body).map_err(|e| {
+        callback_error(
+            StatusCode::BAD_REQUEST,
+            &format!("Invalid callback body: {e}"),
+        )
+    })?;
+    if !matches!(
+        callback.result.resolution.as_str(),
+        "accepted" | "rejected" | "uncertain"
+    ) {
+        return Err(callback_error(
+            StatusCode::BAD_REQUEST,
+            "resolution must be 'accepted', 'rejected' or 'uncertain'",
+        ));
+    }
+
+    tracing::info!(
+        "Image checker called back with {} for proof {}",
+        callback.result.resolution,
+        callback.processing_id
+    );
+
+    let mut job = ProofJob::get_by_id(&state.pool, callback.processing_id)
+        .await
+        .map_err(proof_job_error_response)?;
+    let applied = state
+        .proof_jobs
+        .apply_result(&mut job, callback.result)
+        .await
+        .map_err(proof_job_error_response)?;
+
+    if !applied {
+        tracing::info!(
+            "Proof {} already had an outcome, callback ignored",
+            job.processing_id
+        );
+    }
+
+    Ok(Json(CallbackResponse {
+        processing_id: job.processing_id,
+        status: job.status,
+        applied,
+    }))
+}
+
+pub(crate) fn proof_job_error_response(error: ProofJobError) -> (StatusCode, Json<ErrorResponse>) {
+    let status = match error {
+        ProofJobError::AlreadyPending
+        | ProofJobError::NotInReview
+        | ProofJobError::ClaimedByAnother
+        | ProofJobError::AlreadyAppealed
+        | ProofJobError::AppealLimitReached(_) => StatusCode::CONFLICT,
+        ProofJobError::NotFound | ProofJobError::NothingToAppeal => StatusCode::NOT_FOUND,
+        ProofJobError::DatabaseError(ref e) => {
+            tracing::error!("Proof request failed with error: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    message: "Proof request failed".to_string(),
+                }),
+            );
+        }
+    };
+
+    (
+        status,
+        Json(ErrorResponse {
+            message: error.to_string(),
+        }),
+    )
+}
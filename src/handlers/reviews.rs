@@ -0,0 +1,261 @@
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::auth::{AuthenticatedUser, ErrorResponse};
+use crate::handlers::challenges::authorize_challenge_moderator;
+use crate::handlers::proofs::proof_job_error_response;
+use crate::models::challenge::ChallengeData;
+use crate::models::proof_job::{ProofJobError, ProofReview};
+use crate::routes::AppState;
+use crate::services::image_store::ImageStoreError;
+use crate::services::location_service::GeoLocation;
+use crate::services::ProofVerdict;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ReviewDecisionRequest {
+    pub comment: Option<String>,
+}
+
+/// A held photo with what a moderator needs to judge it
+#[derive(serde::Serialize)]
+pub struct ProofReviewResponse {
+    #[serde(flatten)]
+    pub review: ProofReview,
+    #[serde(rename = "image-url")]
+    pub image_url: Option<String>,
+    #[serde(rename = "image-subject")]
+    pub image_subject: Option<String>, // What the photo should show
+    #[serde(rename = "waypoint-location")]
+    pub waypoint_location: Option<GeoLocation>,
+    #[serde(rename = "waypoint-radius-meters")]
+    pub waypoint_radius_meters: Option<f64>,
+}
+
+/// The photos of a challenge waiting for a moderator, oldest first
+/// GET /challenges/{challenge_id}/reviews
+pub async fn list_proof_reviews(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(challenge_id): Path<i32>,
+) -> Result<Json<Vec<ProofReviewResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let (_moderator, _temporal_challenge, challenge_data) =
+        authorize_challenge_moderator(&state, &auth_user, challenge_id).await?;
+
+    let reviews = ProofReview::list_pending(&state.pool, challenge_id)
+        .await
+        .map_err(proof_job_error_response)?;
+
+    Ok(Json(
+        reviews
+            .into_iter()
+            .map(|review| review_response(&state, &challenge_data, review))
+            .collect(),
+    ))
+}
+
+/// Take a held photo so other moderators leave it alone while it is being looked at
+/// POST /challenges/{challenge_id}/reviews/{processing_id}/claim
+pub async fn claim_proof_review(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path((challenge_id, processing_id)): Path<(i32, Uuid)>,
+) -> Result<Json<ProofReviewResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let (moderator, _temporal_challenge, challenge_data) =
+        authorize_challenge_moderator(&state, &auth_user, challenge_id).await?;
+    review_in_challenge(&state, challenge_id, processing_id).await?;
+
+    let review = ProofReview::claim(&state.pool, processing_id, moderator.user_id)
+        .await
+        .map_err(proof_job_error_response)?;
+
+    tracing::info!(
+        "Proof {} in challenge {} claimed for review by user: {}",
+        processing_id,
+        challenge_id,
+        auth_user.username
+    );
+
+    Ok(Json(review_response(&state, &challenge_data, review)))
+}
+
+/// Accept a held photo, which verifies the participant's waypoint
+/// POST /challenges/{challenge_id}/reviews/{processing_id}/approve
+pub async fn approve_proof_review(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path((challenge_id, processing_id)): Path<(i32, Uuid)>,
+    request: Option<Json<ReviewDecisionRequest>>,
+) -> Result<Json<ProofReviewResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let Json(request) = request.unwrap_or_default();
+    let comment = request
+        .comment
+        .map(|comment| comment.trim().to_string())
+        .filter(|comment| !comment.is_empty());
+
+    decide_proof_review(
+        &state,
+        &auth_user,
+        challenge_id,
+        processing_id,
+        ProofVerdict::Accepted,
+        comment,
+    )
+    .await
+    .map(Json)
+}
+
+/// Reject a held photo with a comment the participant gets as the reason. They stay checked
+/// in and may send another photo.
+/// POST /challenges/{challenge_id}/reviews/{processing_id}/reject
+pub async fn reject_proof_review(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path((challenge_id, processing_id)): Path<(i32, Uuid)>,
+    Json(request): Json<ReviewDecisionRequest>,
+) -> Result<Json<ProofReviewResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let comment = request
+        .comment
+        .map(|comment| comment.trim().to_string())
+        .filter(|comment| !comment.is_empty())
+        .ok_or((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                message: "A comment is required".to_string(),
+            }),
+        ))?;
+
+    decide_proof_review(
+        &state,
+        &auth_user,
+        challenge_id,
+        processing_id,
+        ProofVerdict::Rejected {
+            reasons: vec![comment.clone()],
+        },
+        Some(comment),
+    )
+    .await
+    .map(Json)
+}
+
+/// The held photo itself, for image stores that cannot hand moderators a URL of their own
+/// GET /challenges/{challenge_id}/reviews/{processing_id}/image
+pub async fn get_proof_review_image(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path((challenge_id, processing_id)): Path<(i32, Uuid)>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    authorize_challenge_moderator(&state, &auth_user, challenge_id).await?;
+    let review = review_in_challenge(&state, challenge_id, processing_id).await?;
+
+    let (image, content_type) = state
+        .image_uploader
+        .review_image(&review.image_path)
+        .await
+        .map_err(|e| match e {
+            ImageStoreError::NotFound(_) => (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    message: "Photo not found".to_string(),
+                }),
+            ),
+            e => {
+                tracing::error!("Failed to read photo of proof {}: {}", processing_id, e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse {
+                        message: "Failed to read photo".to_string(),
+                    }),
+                )
+            }
+        })?;
+
+    Ok(([(header::CONTENT_TYPE, content_type)], image).into_response())
+}
+
+async fn decide_proof_review(
+    state: &AppState,
+    auth_user: &AuthenticatedUser,
+    challenge_id: i32,
+    processing_id: Uuid,
+    verdict: ProofVerdict,
+    comment: Option<String>,
+) -> Result<ProofReviewResponse, (StatusCode, Json<ErrorResponse>)> {
+    let (moderator, _temporal_challenge, challenge_data) =
+        authorize_challenge_moderator(state, auth_user, challenge_id).await?;
+    let mut review = review_in_challenge(state, challenge_id, processing_id).await?;
+
+    state
+        .proof_jobs
+        .decide_review(
+            &mut review,
+            moderator.user_id,
+            verdict.clone(),
+            comment.as_deref(),
+        )
+        .await
+        .map_err(proof_job_error_response)?;
+
+    tracing::info!(
+        "Proof {} in challenge {} {} on review by user: {}",
+        processing_id,
+        challenge_id,
+        verdict.resolution(),
+        auth_user.username
+    );
+
+    Ok(review_response(state, &challenge_data, review))
+}
+
+/// The proof, as long as it belongs to the moderated challenge
+async fn review_in_challenge(
+    state: &AppState,
+    challenge_id: i32,
+    processing_id: Uuid,
+) -> Result<ProofReview, (StatusCode, Json<ErrorResponse>)> {
+    let review = ProofReview::get_by_id(&state.pool, processing_id)
+        .await
+        .map_err(proof_job_error_response)?;
+
+    if review.challenge_id != challenge_id {
+        return Err(proof_job_error_response(ProofJobError::NotFound));
+    }
+
+    Ok(review)
+}
+
+fn review_response(
+    state: &AppState,
+    challenge_data: &ChallengeData,
+    review: ProofReview,
+) -> ProofReviewResponse {
+    let image_url = match state.image_uploader.review_url(&review.image_path) {
+        Ok(Some(url)) => Some(url),
+        Ok(None) => Some(format!(
+            "/challenges/{}/reviews/{}/image",
+            review.challenge_id, review.processing_id
+        )),
+        Err(e) => {
+            tracing::warn!("No review URL for proof {}: {}", review.processing_id, e);
+            None
+        }
+    };
+    let waypoint = challenge_data
+        .waypoints
+        .iter()
+        .find(|w| w.waypoint_sequence == review.waypoint_sequence);
+
+    ProofReviewResponse {
+        image_url,
+        image_subject: waypoint.map(|w| w.image_subject.clone()),
+        waypoint_location: waypoint.map(|w| w.location.clone()),
+        waypoint_radius_meters: waypoint.map(|w| w.radius_meters),
+        review,
+    }
+}
@@ -1,12 +1,32 @@
 use axum::{
-    extract::{Multipart, Path, State},
-    http::StatusCode,
+    extract::{FromRequest, Multipart, Path, Request, State},
+    http::{header::CONTENT_TYPE, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
+
 use crate::auth::{AuthenticatedParticipant, ErrorResponse};
-use crate::models::{TemporalChallenge, ChallengeParticipant, WaypointData, WaypointState};
+use crate::handlers::proofs::proof_job_error_response;
+use crate::models::audit_log::{
+    AuditLog, DuplicatePhotoParams, ProofAppealParams, WaypointCheckInParams,
+    WaypointProofSubmissionParams, WaypointVerificationParams,
+};
+use crate::models::challenge::{
+    ChallengeData, ChallengeError, ChallengeParticipant, DuplicatePhotoPolicy, RouteProgress,
+    TemporalChallenge, WaypointData, WaypointState,
+};
+use crate::models::proof_job::{
+    DuplicatePhotoMatch, PhotoFingerprint, ProofAppeal, ProofJob, ProofJobStatus,
+};
 use crate::routes::AppState;
-use crate::services::LocationValidationRequest;
+use crate::services::exif::PhotoRequirements;
+use crate::services::image_upload::StoredImage;
+use crate::services::proof_jobs::complete_proven_waypoint;
+use crate::services::proof_verifier;
+use crate::services::{
+    ChallengeEvent, LocationValidationRequest, PerceptualHash, ProofError, ProofSubmission,
+    ProofVerdict, UploadError,
+};
 
 #[derive(serde::Serialize)]
 pub struct CheckInResponse {
@@ -19,6 +39,12 @@ pub struct CheckInResponse {
     pub waypoint_id: i32,
     pub state: String,
     pub proof: String,
+    #[serde(rename = "proof-kind")]
+    pub proof_kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<Vec<String>>,
+    #[serde(rename = "completed-waypoints")]
+    pub completed_waypoints: Vec<i32>,
 }
 
 #[derive(serde::Serialize)]
@@ -31,103 +57,72 @@ pub struct ProofResponse {
     #[serde(rename = "waypoint-id")]
     pub waypoint_id: i32,
     pub state: String,
+    #[serde(rename = "processing-id", skip_serializing_if = "Option::is_none")]
+    pub processing_id: Option<uuid::Uuid>,
+    #[serde(rename = "completed-waypoints")]
+    pub completed_waypoints: Vec<i32>,
+    #[serde(rename = "bonus-points")]
+    pub bonus_points: i32,
+    pub score: i32,
 }
 
-/// Handle waypoint check-in
+/// Check in at a waypoint the route allows next: the presented one on linear routes, any
+/// remaining one on unordered routes, one of the branches after the last waypoint, or any
+/// open bonus waypoint
 /// POST /challenges/waypoints/{waypoint_id}/checkin
 pub async fn check_in_waypoint(
     auth_participant: AuthenticatedParticipant,
     State(state): State<AppState>,
-    Path(waypoint_id): Path<i32>,
+    Path(waypoint_sequence): Path<i32>,
     Json(request): Json<LocationValidationRequest>,
 ) -> Result<Json<CheckInResponse>, (StatusCode, Json<ErrorResponse>)> {
     tracing::info!(
         "Waypoint check-in from participant: {} for waypoint: {}",
         auth_participant.participant_id,
-        waypoint_id
+        waypoint_sequence
     );
 
-    // Get participant
-    let participant_id = Uuid::parse_str(&auth_participant.participant_id).map_err(|_| {
-        (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                message: "Invalid participant ID".to_string(),
-            }),
-        )
-    })?;
-
-    let mut participant = match ChallengeParticipant::get_by_id(&state.pool, participant_id).await {
-        Ok(participant) => participant,
-        Err(_) => {
-            return Err((
-                StatusCode::NOT_FOUND,
-                Json(ErrorResponse {
-                    message: "Participant not found".to_string(),
-                }),
-            ));
-        }
-    };
+    let participant_id = auth_participant.participant_uuid()?;
+    let mut participant = ChallengeParticipant::get_by_id(&state.pool, participant_id)
+        .await
+        .map_err(waypoint_error_response)?;
 
-    // Get waypoint
-    let waypoint = match Waypoint::get_by_id(&state.pool, waypoint_id).await {
-        Ok(waypoint) => waypoint,
-        Err(_) => {
-            return Err((
-                StatusCode::NOT_FOUND,
-                Json(ErrorResponse {
-                    message: "Waypoint not found".to_string(),
-                }),
-            ));
-        }
-    };
+    let challenge_data =
+        TemporalChallenge::get_current_by_id(&state.pool, participant.challenge_id)
+            .await
+            .and_then(|challenge| challenge.get_challenge_data())
+            .map_err(waypoint_error_response)?;
 
-    // Verify participant belongs to the same challenge as the waypoint
-    if participant.challenge_id != waypoint.challenge_id {
-        return Err((
-            StatusCode::FORBIDDEN,
-            Json(ErrorResponse {
-                message: "Waypoint does not belong to participant's challenge".to_string(),
-            }),
-        ));
-    }
+    let waypoint = challenge_data
+        .waypoints
+        .iter()
+        .find(|w| w.waypoint_sequence == waypoint_sequence)
+        .ok_or_else(|| waypoint_error_response(ChallengeError::WaypointNotFound))?;
 
-    // Check if this is the participant's current waypoint
-    if participant.current_waypoint_id != Some(waypoint_id) {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                message: "This is not your current waypoint".to_string(),
-            }),
-        ));
-    }
+    participant
+        .can_check_in(&challenge_data, waypoint_sequence, chrono::Utc::now())
+        .map_err(waypoint_error_response)?;
 
-    // Validate location
-    let validation_result = match state
+    let validation_result = state
         .location_service
-        .validate_waypoint_location(waypoint_id, &request.location)
-        .await
-    {
-        Ok(result) => result,
-        Err(e) => {
-            tracing::error!("Location validation failed: {}", e);
-            return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
+        .validate_waypoint_location(waypoint, &request.location)
+        .map_err(|e| {
+            tracing::warn!("Location validation failed: {}", e);
+            (
+                StatusCode::BAD_REQUEST,
                 Json(ErrorResponse {
-                    message: "Location validation failed".to_string(),
+                    message: e.to_string(),
                 }),
-            ));
-        }
-    };
+            )
+        })?;
 
-    // Log the check-in attempt
     if let Err(e) = AuditLog::log_waypoint_checked_in(
         &state.pool,
-        crate::models::audit_log::WaypointCheckInParams {
+        WaypointCheckInParams {
             participant_id,
             challenge_id: participant.challenge_id,
-            waypoint_id,
-            waypoint_sequence: waypoint.waypoint_sequence,
+            waypoint_id: waypoint_sequence,
+            waypoint_sequence,
             location_lat: request.location.lat,
             location_lon: request.location.lon,
             distance_from_target: validation_result.distance_meters,
@@ -139,7 +134,6 @@ pub async fn check_in_waypoint(
         tracing::warn!("Failed to log waypoint check-in: {}", e);
     }
 
-    // Log participant location
     if let Err(e) = state
         .location_service
         .log_participant_location(participant_id, &request.location, None)
@@ -152,7 +146,7 @@ pub async fn check_in_waypoint(
         tracing::warn!(
             "Check-in failed for participant {} at waypoint {}: too far from target",
             participant_id,
-            waypoint_id
+            waypoint_sequence
         );
         return Err((
             StatusCode::BAD_REQUEST,
@@ -162,350 +156,595 @@ pub async fn check_in_waypoint(
         ));
     }
 
-    // Update participant state to CHECKED_IN
-    if let Err(e) = participant
-        .update_waypoint_state(&state.pool, waypoint_id, WaypointState::CheckedIn)
+    participant
+        .check_in(&state.pool, &challenge_data, waypoint_sequence)
         .await
-    {
-        tracing::error!("Failed to update participant state: {}", e);
-        return Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                message: "Failed to update check-in state".to_string(),
-            }),
+        .map_err(waypoint_error_response)?;
+
+    state
+        .event_hub
+        .publish(ChallengeEvent::waypoint_state_changed(
+            participant.challenge_id,
+            participant_id,
+            waypoint_sequence,
+            WaypointState::CheckedIn,
         ));
-    }
 
     tracing::info!(
         "Check-in successful for participant {} at waypoint {}",
         participant_id,
-        waypoint_id
+        waypoint_sequence
     );
 
+    let RouteProgress {
+        completed_waypoints,
+        ..
+    } = participant.route_progress(&challenge_data);
+
     Ok(Json(CheckInResponse {
         challenge_id: participant.challenge_id.to_string(),
         participant_id: participant_id.to_string(),
         timestamp: chrono::Utc::now(),
-        waypoint_id,
+        waypoint_id: waypoint_sequence,
         state: "CHECKED_IN".to_string(),
-        proof: waypoint.image_subject.clone(),
+        proof: waypoint.proof.prompt(&waypoint.image_subject),
+        proof_kind: waypoint.proof.name(),
+        options: waypoint.proof.options().map(<[String]>::to_vec),
+        completed_waypoints,
     }))
 }
 
-/// Handle waypoint proof submission
+/// Prove a checked-in waypoint with whatever its proof kind asks for: a multipart `image`
+/// upload for photos, JSON with a `code`, `answer` or `choice` for the other kinds. Photos are
+/// verified in the background and answered with 202 and a processing id to follow up on at
+/// `GET /proofs/{processing_id}`, the other kinds are verified on the spot.
 /// POST /challenges/waypoints/{waypoint_id}/proof
 pub async fn submit_waypoint_proof(
     auth_participant: AuthenticatedParticipant,
     State(state): State<AppState>,
-    Path(waypoint_id): Path<i32>,
-    mut multipart: Multipart,
-) -> Result<Json<ProofResponse>, (StatusCode, Json<ErrorResponse>)> {
+    Path(waypoint_sequence): Path<i32>,
+    request: Request,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
     tracing::info!(
         "Waypoint proof submission from participant: {} for waypoint: {}",
         auth_participant.participant_id,
-        waypoint_id
+        waypoint_sequence
     );
 
-    // Get participant
-    let participant_id = Uuid::parse_str(&auth_participant.participant_id).map_err(|_| {
-        (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                message: "Invalid participant ID".to_string(),
-            }),
-        )
-    })?;
+    let participant_id = auth_participant.participant_uuid()?;
+    let mut participant = ChallengeParticipant::get_by_id(&state.pool, participant_id)
+        .await
+        .map_err(waypoint_error_response)?;
 
-    let mut participant = match ChallengeParticipant::get_by_id(&state.pool, participant_id).await {
-        Ok(participant) => participant,
-        Err(_) => {
-            return Err((
-                StatusCode::NOT_FOUND,
-                Json(ErrorResponse {
-                    message: "Participant not found".to_string(),
-                }),
-            ));
-        }
+    let challenge_data =
+        TemporalChallenge::get_current_by_id(&state.pool, participant.challenge_id)
+            .await
+            .and_then(|challenge| challenge.get_challenge_data())
+            .map_err(waypoint_error_response)?;
+
+    let waypoint = challenge_data
+        .waypoints
+        .iter()
+        .find(|w| w.waypoint_sequence == waypoint_sequence)
+        .ok_or_else(|| waypoint_error_response(ChallengeError::WaypointNotFound))?;
+
+    participant
+        .can_submit_proof(&challenge_data, waypoint_sequence, chrono::Utc::now())
+        .map_err(waypoint_error_response)?;
+
+    let Some(verifier) = proof_verifier::verifier_for(&waypoint.proof) else {
+        return submit_photo_proof(&state, &participant, &challenge_data, waypoint, request).await;
     };
 
-    // Get waypoint
-    let waypoint = match Waypoint::get_by_id(&state.pool, waypoint_id).await {
-        Ok(waypoint) => waypoint,
-        Err(_) => {
-            return Err((
-                StatusCode::NOT_FOUND,
-                Json(ErrorResponse {
-                    message: "Waypoint not found".to_string(),
-                }),
-            ));
-        }
+    let submission = if request.headers().get(CONTENT_TYPE).is_none() {
+        // Location-only waypoints need no body at all
+        ProofSubmission::default()
+    } else {
+        let Json(submission) = Json::<ProofSubmission>::from_request(request, &state)
+            .await
+            .map_err(|e| proof_request_error(e.body_text()))?;
+        submission
     };
 
-    // Verify participant belongs to the same challenge as the waypoint
-    if participant.challenge_id != waypoint.challenge_id {
-        return Err((
-            StatusCode::FORBIDDEN,
-            Json(ErrorResponse {
-                message: "Waypoint does not belong to participant's challenge".to_string(),
-            }),
-        ));
+    let verification_start = std::time::Instant::now();
+    let verdict = verifier
+        .verify(waypoint, &submission)
+        .await
+        .map_err(|e| proof_request_error(e.to_string()))?;
+    let processing_time = verification_start.elapsed().as_secs_f64();
+
+    if let ProofVerdict::Rejected { reasons } = verdict {
+        return Err(reject_proof(
+            &state,
+            &participant,
+            waypoint_sequence,
+            &reasons,
+            processing_time,
+        )
+        .await);
     }
 
-    // Check if participant is checked in to this waypoint
-    if participant.current_waypoint_id != Some(waypoint_id)
-        || participant.current_state != WaypointState::CheckedIn
+    if let Err(e) = AuditLog::log_waypoint_verified(
+        &state.pool,
+        WaypointVerificationParams {
+            participant_id,
+            challenge_id: participant.challenge_id,
+            waypoint_id: waypoint_sequence,
+            waypoint_sequence,
+            verification_result: verdict.resolution(),
+            verification_reasons: None,
+            processing_time_seconds: processing_time,
+            outcome_payload: None,
+        },
+    )
+    .await
     {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                message: "You must check in to this waypoint before submitting proof".to_string(),
-            }),
-        ));
+        tracing::warn!("Failed to log waypoint verification: {}", e);
     }
 
-    // Extract image from multipart form
-    let mut image_data: Option<Vec<u8>> = None;
-    let mut image_filename: Option<String> = None;
+    complete_proven_waypoint(
+        &state.pool,
+        &state.event_hub,
+        &mut participant,
+        &challenge_data,
+        waypoint_sequence,
+    )
+    .await
+    .map_err(waypoint_error_response)?;
 
-    while let Some(field) = multipart.next_field().await.map_err(|e| {
-        tracing::error!("Failed to read multipart field: {}", e);
-        (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                message: "Invalid multipart data".to_string(),
-            }),
-        )
-    })? {
-        if field.name() == Some("image") {
-            image_filename = field.file_name().map(|s| s.to_string());
-            image_data = Some(
-                field
-                    .bytes()
-                    .await
-                    .map_err(|e| {
-                        tracing::error!("Failed to read image data: {}", e);
-                        (
-                            StatusCode::BAD_REQUEST,
-                            Json(ErrorResponse {
-                                message: "Failed to read image data".to_string(),
-                            }),
-                        )
-                    })?
-                    .to_vec(),
-            );
-            break;
+    tracing::info!(
+        "Proof verification successful for participant {} at waypoint {}",
+        participant_id,
+        waypoint_sequence
+    );
+
+    Ok(Json(proof_response(
+        &participant,
+        &challenge_data,
+        waypoint_sequence,
+        "VERIFIED",
+        None,
+    ))
+    .into_response())
+}
+
+/// Store the photo and queue it for the image checker, unless its EXIF location or capture
+/// time already rules it out. Photos that look like one submitted before for the waypoint
+/// are rejected or held for a moderator, as the challenge's duplicate policy says.
+/// Rejected photos are recorded all the same, so they can be appealed.
+async fn submit_photo_proof(
+    state: &AppState,
+    participant: &ChallengeParticipant,
+    challenge_data: &ChallengeData,
+    waypoint: &WaypointData,
+    request: Request,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let participant_id = participant.participant_id;
+    let waypoint_sequence = waypoint.waypoint_sequence;
+
+    // Before the upload, a refused photo would be left behind in the image store
+    ProofJob::ensure_none_pending(&state.pool, participant_id, waypoint_sequence)
+        .await
+        .map_err(proof_job_error_response)?;
+
+    let mut multipart = Multipart::from_request(request, state)
+        .await
+        .map_err(|_| proof_request_error(ProofError::MissingSubmission("an image").to_string()))?;
+    let image = state
+        .image_uploader
+        .receive(&mut multipart, participant.challenge_id, participant_id)
+        .await
+        .map_err(upload_error_response)?;
+    tracing::info!(
+        "Stored {} proof image {} ({} bytes) for participant {}",
+        image.format.content_type(),
+        image.relative_path,
+        image.size_bytes,
+        participant_id
+    );
+
+    let now = chrono::Utc::now();
+    let mut reasons = PhotoRequirements {
+        waypoint,
+        window: participant
+            .clock_started_at(challenge_data)
+            .map(|started_at| (started_at, now)),
+        missing_exif: challenge_data.missing_exif,
+    }
+    .check(&image.metadata);
+
+    let duplicates = match image.perceptual_hash {
+        Some(hash) if reasons.is_empty() => {
+            find_duplicate_photos(state, participant, challenge_data, waypoint, &image, hash).await
         }
+        _ => Vec::new(),
+    };
+    let hold_for_review =
+        !duplicates.is_empty() && challenge_data.duplicate_photos == DuplicatePhotoPolicy::Review;
+    if !duplicates.is_empty() && !hold_for_review {
+        reasons.push(DUPLICATE_PHOTO_REASON.to_string());
     }
 
-    let _image_data = image_data.ok_or_else(|| {
-        (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                message: "No image provided".to_string(),
-            }),
+    let job = if !reasons.is_empty() {
+        // Kept so the participant can appeal the rejection
+        ProofJob::create_rejected(
+            &state.pool,
+            participant_id,
+            participant.challenge_id,
+            waypoint_sequence,
+            &image.relative_path,
+            &image.metadata,
+            &reasons,
         )
-    })?;
+        .await
+        .map_err(proof_job_error_response)?
+    } else if hold_for_review {
+        state
+            .proof_jobs
+            .enqueue_for_review(
+                participant_id,
+                participant.challenge_id,
+                waypoint_sequence,
+                &image.relative_path,
+                &image.metadata,
+                DUPLICATE_PHOTO_REASON,
+            )
+            .await
+            .map_err(proof_job_error_response)?
+    } else {
+        state
+            .proof_jobs
+            .enqueue(
+                participant_id,
+                participant.challenge_id,
+                waypoint_sequence,
+                &image.relative_path,
+                &image.metadata,
+            )
+            .await
+            .map_err(proof_job_error_response)?
+    };
+    let rejected = job.status == ProofJobStatus::Rejected;
 
-    let image_filename = image_filename.ok_or_else(|| {
-        (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                message: "No image filename provided".to_string(),
-            }),
-        )
-    })?;
+    if let Some(hash) = image.perceptual_hash.filter(|_| !rejected) {
+        if let Err(e) = PhotoFingerprint::record(&state.pool, &job, &waypoint.location, hash).await
+        {
+            tracing::warn!(
+                "Failed to record fingerprint of proof {}: {}",
+                job.processing_id,
+                e
+            );
+        }
+    }
 
-    // Validate image format
-    if let Err(e) = state.image_service.validate_image_format(&image_filename) {
-        tracing::warn!("Invalid image format: {}", e);
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                message: format!("Invalid image format: {e}"),
-            }),
-        ));
+    if let Err(e) = AuditLog::log_waypoint_proof_submitted(
+        &state.pool,
+        WaypointProofSubmissionParams {
+            participant_id,
+            challenge_id: participant.challenge_id,
+            waypoint_id: waypoint_sequence,
+            waypoint_sequence,
+            image_path: &image.relative_path,
+            processing_id: &job.processing_id.to_string(),
+            photo_metadata: Some(&image.metadata),
+        },
+    )
+    .await
+    {
+        tracing::warn!("Failed to log waypoint proof submission: {}", e);
     }
 
-    // Create a unique filename for the image
-    let unique_filename = format!(
-        "{}/{}/{}_{}_{}",
-        participant.challenge_id,
+    // Photos ruled out by their EXIF data never reach the image checker
+    if rejected {
+        return Err(reject_proof(state, participant, waypoint_sequence, &reasons, 0.0).await);
+    }
+
+    tracing::info!(
+        "Queued proof {} for participant {} at waypoint {} as {:?}",
+        job.processing_id,
         participant_id,
-        waypoint_id,
-        chrono::Utc::now().timestamp(),
-        image_filename
+        waypoint_sequence,
+        job.status
     );
 
-    // TODO: Save image to storage (local or cloud)
-    // For now, we'll use the unique filename as the image path
-    let image_path = unique_filename;
+    let proof_state = if job.status == ProofJobStatus::ManualReview {
+        "MANUAL_REVIEW"
+    } else {
+        "PROCESSING"
+    };
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(proof_response(
+            participant,
+            challenge_data,
+            waypoint_sequence,
+            proof_state,
+            Some(job.processing_id),
+        )),
+    )
+        .into_response())
+}
 
-    // Get waypoint location for validation
-    let waypoint_location = waypoint.get_location();
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct ProofAppealRequest {
+    pub comment: Option<String>,
+}
 
-    // Submit image for validation
-    let processing_id = uuid::Uuid::new_v4().to_string();
+/// Appeal the rejection of the latest photo sent for a waypoint the participant is still
+/// checked in at. The photo goes to the moderators' review queue with the comment, and only
+/// `appeals_per_waypoint` photos per waypoint can be appealed.
+/// POST /challenges/waypoints/{waypoint_id}/appeal
+pub async fn appeal_waypoint_proof(
+    auth_participant: AuthenticatedParticipant,
+    State(state): State<AppState>,
+    Path(waypoint_sequence): Path<i32>,
+    Json(request): Json<ProofAppealRequest>,
+) -> Result<(StatusCode, Json<ProofAppeal>), (StatusCode, Json<ErrorResponse>)> {
+    let comment = request
+        .comment
+        .map(|comment| comment.trim().to_string())
+        .filter(|comment| !comment.is_empty())
+        .ok_or_else(|| proof_request_error("A comment is required"))?;
+
+    let participant_id = auth_participant.participant_uuid()?;
+    let participant = ChallengeParticipant::get_by_id(&state.pool, participant_id)
+        .await
+        .map_err(waypoint_error_response)?;
 
-    // Log proof submission
-    if let Err(e) = AuditLog::log_waypoint_proof_submitted(
+    let challenge_data =
+        TemporalChallenge::get_current_by_id(&state.pool, participant.challenge_id)
+            .await
+            .and_then(|challenge| challenge.get_challenge_data())
+            .map_err(waypoint_error_response)?;
+
+    let waypoint = challenge_data
+        .waypoints
+        .iter()
+        .find(|w| w.waypoint_sequence == waypoint_sequence)
+        .ok_or_else(|| waypoint_error_response(ChallengeError::WaypointNotFound))?;
+    if proof_verifier::verifier_for(&waypoint.proof).is_some() {
+        return Err(proof_request_error("Only photo proofs can be appealed"));
+    }
+
+    // Once the waypoint is verified or left behind there is nothing left to decide
+    participant
+        .can_submit_proof(&challenge_data, waypoint_sequence, chrono::Utc::now())
+        .map_err(waypoint_error_response)?;
+
+    let appeal = ProofAppeal::file(
         &state.pool,
         participant_id,
-        participant.challenge_id,
-        waypoint_id,
-        waypoint.waypoint_sequence,
-        &image_path,
-        &processing_id,
+        waypoint_sequence,
+        &comment,
+        challenge_data.appeals_per_waypoint,
+    )
+    .await
+    .map_err(proof_job_error_response)?;
+
+    if let Err(e) = AuditLog::log_proof_appealed(
+        &state.pool,
+        ProofAppealParams {
+            user_id: auth_participant.user_id,
+            appeal: &appeal,
+            appeals_per_waypoint: challenge_data.appeals_per_waypoint,
+        },
     )
     .await
     {
-        tracing::warn!("Failed to log waypoint proof submission: {}", e);
+        tracing::warn!("Failed to log proof appeal: {}", e);
     }
 
-    // Validate image with the external service
-    let validation_start = std::time::Instant::now();
-    let validation_result = match state
-        .image_service
-        .validate_image(
-            &image_path,
-            &waypoint.image_subject,
-            Some(&waypoint_location),
-            Some(waypoint.radius_meters),
-            None, // TODO: Add time constraints if needed
-        )
-        .await
+    state
+        .event_hub
+        .publish(ChallengeEvent::proof_appealed(&appeal));
+
+    tracing::info!(
+        "Participant {} appealed the rejection of proof {} at waypoint {}",
+        participant_id,
+        appeal.processing_id,
+        waypoint_sequence
+    );
+
+    Ok((StatusCode::ACCEPTED, Json(appeal)))
+}
+
+const DUPLICATE_PHOTO_REASON: &str =
+    "This photo matches a proof already submitted for this waypoint";
+
+/// Earlier photos of the waypoint this one near-duplicates, audited when there are any. A
+/// failed lookup lets the photo through rather than failing the submission.
+async fn find_duplicate_photos(
+    state: &AppState,
+    participant: &ChallengeParticipant,
+    challenge_data: &ChallengeData,
+    waypoint: &WaypointData,
+    image: &StoredImage,
+    hash: PerceptualHash,
+) -> Vec<DuplicatePhotoMatch> {
+    let duplicates = match PhotoFingerprint::find_near_duplicates(
+        &state.pool,
+        participant.participant_id,
+        participant.challenge_id,
+        waypoint.waypoint_sequence,
+        &waypoint.location,
+        waypoint.radius_meters,
+        hash,
+    )
+    .await
     {
-        Ok(result) => result,
+        Ok(duplicates) => duplicates,
         Err(e) => {
-            tracing::error!("Image validation failed: {}", e);
-
-            // Log validation failure
-            if let Err(log_err) = AuditLog::log_waypoint_verified(
-                &state.pool,
-                crate::models::audit_log::WaypointVerificationParams {
-                    participant_id,
-                    challenge_id: participant.challenge_id,
-                    waypoint_id,
-                    waypoint_sequence: waypoint.waypoint_sequence,
-                    verification_result: "failed",
-                    verification_reasons: Some(&[format!("Validation service error: {e}")]),
-                    processing_time_seconds: validation_start.elapsed().as_secs_f64(),
-                    outcome_payload: None,
-                },
-            )
-            .await
-            {
-                tracing::warn!("Failed to log waypoint verification failure: {}", log_err);
-            }
-
-            return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    message: "Image validation service unavailable".to_string(),
-                }),
-            ));
+            tracing::error!("Failed to look up duplicate photos: {}", e);
+            return Vec::new();
         }
     };
+    if duplicates.is_empty() {
+        return duplicates;
+    }
+
+    tracing::warn!(
+        "Proof photo {} from participant {} duplicates {} earlier photo(s), closest from participant {}",
+        image.relative_path,
+        participant.participant_id,
+        duplicates.len(),
+        duplicates[0].participant_id
+    );
+    if let Err(e) = AuditLog::log_duplicate_photo_detected(
+        &state.pool,
+        DuplicatePhotoParams {
+            participant_id: participant.participant_id,
+            challenge_id: participant.challenge_id,
+            waypoint_sequence: waypoint.waypoint_sequence,
+            image_path: &image.relative_path,
+            perceptual_hash: &hash.to_string(),
+            matches: &duplicates,
+            policy: challenge_data.duplicate_photos,
+        },
+    )
+    .await
+    {
+        tracing::warn!("Failed to log duplicate photo: {}", e);
+    }
 
-    let processing_time = validation_start.elapsed().as_secs_f64();
+    duplicates
+}
 
-    // Check validation result
-    if validation_result.resolution == "accepted" {
-        // Update participant state to VERIFIED and advance to next waypoint
-        if let Err(e) = participant
-            .update_waypoint_state(&state.pool, waypoint_id, WaypointState::Verified)
-            .await
-        {
-            tracing::error!("Failed to update participant state to verified: {}", e);
-        }
+/// Audit a rejected proof and tell the participant why it was rejected
+async fn reject_proof(
+    state: &AppState,
+    participant: &ChallengeParticipant,
+    waypoint_sequence: i32,
+    reasons: &[String],
+    processing_time: f64,
+) -> (StatusCode, Json<ErrorResponse>) {
+    if let Err(e) = AuditLog::log_waypoint_verified(
+        &state.pool,
+        WaypointVerificationParams {
+            participant_id: participant.participant_id,
+            challenge_id: participant.challenge_id,
+            waypoint_id: waypoint_sequence,
+            waypoint_sequence,
+            verification_result: "rejected",
+            verification_reasons: Some(reasons),
+            processing_time_seconds: processing_time,
+            outcome_payload: None,
+        },
+    )
+    .await
+    {
+        tracing::warn!("Failed to log waypoint verification: {}", e);
+    }
 
-        // Try to advance to next waypoint
-        match participant.advance_to_next_waypoint(&state.pool).await {
-            Ok(next_waypoint) => {
-                if next_waypoint.is_some() {
-                    tracing::info!("Participant {} advanced to next waypoint", participant_id);
-                } else {
-                    tracing::info!("Participant {} completed all waypoints", participant_id);
-                }
-            }
-            Err(e) => {
-                tracing::warn!("Failed to advance participant to next waypoint: {}", e);
-            }
-        }
+    // Build error message with reasons
+    let mut error_message = "Failed to provide a proof.".to_string();
+    for (i, reason) in reasons.iter().enumerate() {
+        error_message.push_str(&format!(" [{}] {}", i + 1, reason));
+    }
 
-        // Log successful verification
-        if let Err(e) = AuditLog::log_waypoint_verified(
-            &state.pool,
-            crate::models::audit_log::WaypointVerificationParams {
-                participant_id,
-                challenge_id: participant.challenge_id,
-                waypoint_id,
-                waypoint_sequence: waypoint.waypoint_sequence,
-                verification_result: "accepted",
-                verification_reasons: validation_result.reasons.as_deref(),
-                processing_time_seconds: processing_time,
-                outcome_payload: None,
-            },
-        )
-        .await
-        {
-            tracing::warn!("Failed to log waypoint verification success: {}", e);
-        }
+    tracing::warn!(
+        "Proof verification failed for participant {} at waypoint {}: {}",
+        participant.participant_id,
+        waypoint_sequence,
+        error_message
+    );
 
-        tracing::info!(
-            "Proof verification successful for participant {} at waypoint {}",
-            participant_id,
-            waypoint_id
-        );
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ErrorResponse {
+            message: error_message,
+        }),
+    )
+}
 
-        Ok(Json(ProofResponse {
-            challenge_id: participant.challenge_id.to_string(),
-            participant_id: participant_id.to_string(),
-            timestamp: chrono::Utc::now(),
-            waypoint_id,
-            state: "VERIFIED".to_string(),
-        }))
-    } else {
-        // Log failed verification
-        if let Err(e) = AuditLog::log_waypoint_verified(
-            &state.pool,
-            crate::models::audit_log::WaypointVerificationParams {
-                participant_id,
-                challenge_id: participant.challenge_id,
-                waypoint_id,
-                waypoint_sequence: waypoint.waypoint_sequence,
-                verification_result: "rejected",
-                verification_reasons: validation_result.reasons.as_deref(),
-                processing_time_seconds: processing_time,
-                outcome_payload: None,
-            },
-        )
-        .await
-        {
-            tracing::warn!("Failed to log waypoint verification failure: {}", e);
-        }
+fn proof_response(
+    participant: &ChallengeParticipant,
+    challenge_data: &ChallengeData,
+    waypoint_sequence: i32,
+    state: &str,
+    processing_id: Option<uuid::Uuid>,
+) -> ProofResponse {
+    let RouteProgress {
+        completed_waypoints,
+        bonus_points,
+        score,
+        ..
+    } = participant.route_progress(challenge_data);
+
+    ProofResponse {
+        challenge_id: participant.challenge_id.to_string(),
+        participant_id: participant.participant_id.to_string(),
+        timestamp: chrono::Utc::now(),
+        waypoint_id: waypoint_sequence,
+        state: state.to_string(),
+        processing_id,
+        completed_waypoints,
+        bonus_points,
+        score,
+    }
+}
 
-        // Build error message with reasons
-        let mut error_message = "Failed to provide a proof.".to_string();
-        if let Some(reasons) = validation_result.reasons {
-            for (i, reason) in reasons.iter().enumerate() {
-                error_message.push_str(&format!(" [{}] {}", i + 1, reason));
-            }
+fn upload_error_response(error: UploadError) -> (StatusCode, Json<ErrorResponse>) {
+    let status = match error {
+        UploadError::MissingImage | UploadError::Multipart(_) | UploadError::TooManyPixels(_) => {
+            StatusCode::BAD_REQUEST
+        }
+        UploadError::TooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+        UploadError::UnsupportedFormat => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        UploadError::Storage(ref e) => {
+            tracing::error!("Failed to store proof image: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    message: "Failed to store image".to_string(),
+                }),
+            );
         }
+    };
 
-        tracing::warn!(
-            "Proof verification failed for participant {} at waypoint {}: {}",
-            participant_id,
-            waypoint_id,
-            error_message
-        );
+    (
+        status,
+        Json(ErrorResponse {
+            message: error.to_string(),
+        }),
+    )
+}
 
-        Err((
+fn proof_request_error(message: impl Into<String>) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ErrorResponse {
+            message: message.into(),
+        }),
+    )
+}
+
+fn waypoint_error_response(error: ChallengeError) -> (StatusCode, Json<ErrorResponse>) {
+    let (status, message) = match &error {
+        ChallengeError::ParticipantNotFound
+        | ChallengeError::ChallengeNotFound
+        | ChallengeError::WaypointNotFound => (StatusCode::NOT_FOUND, error.to_string()),
+        ChallengeError::InvalidWaypointSequence => (
             StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                message: error_message,
-            }),
-        ))
-    }
+            "This waypoint is not available to you".to_string(),
+        ),
+        ChallengeError::NotCheckedIn => (
+            StatusCode::BAD_REQUEST,
+            "You must check in to this waypoint before submitting proof".to_string(),
+        ),
+        ChallengeError::ParticipantNotActive => (StatusCode::FORBIDDEN, error.to_string()),
+        ChallengeError::ChallengeNotStarted
+        | ChallengeError::ChallengePaused
+        | ChallengeError::ChallengeEnded => (StatusCode::CONFLICT, error.to_string()),
+        _ => {
+            tracing::error!("Waypoint request failed with error: {}", error);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Waypoint request failed".to_string(),
+            )
+        }
+    };
+
+    (status, Json(ErrorResponse { message }))
 }
 
 #[cfg(test)]
@@ -537,6 +776,9 @@ mod tests {
             waypoint_id: 1,
             state: "CHECKED_IN".to_string(),
             proof: "Red post box".to_string(),
+            proof_kind: "IMAGE",
+            options: None,
+            completed_waypoints: vec![1, 2],
         };
 
         let json = serde_json::to_string(&response).unwrap();
@@ -544,6 +786,7 @@ mod tests {
         assert!(json.contains("participant-id"));
         assert!(json.contains("waypoint-id"));
         assert!(json.contains("CHECKED_IN"));
+        assert!(json.contains("completed-waypoints"));
     }
 
     #[test]
@@ -556,6 +799,10 @@ mod tests {
             timestamp: Utc::now(),
             waypoint_id: 1,
             state: "VERIFIED".to_string(),
+            processing_id: None,
+            completed_waypoints: vec![1],
+            bonus_points: 5,
+            score: 15,
         };
 
         let json = serde_json::to_string(&response).unwrap();
@@ -563,5 +810,7 @@ mod tests {
         assert!(json.contains("participant-id"));
         assert!(json.contains("waypoint-id"));
         assert!(json.contains("VERIFIED"));
+        assert!(json.contains("bonus-points"));
+        assert!(!json.contains("processing-id"));
     }
 }
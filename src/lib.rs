@@ -5,6 +5,8 @@ pub mod handlers;
 pub mod models;
 pub mod routes;
 pub mod services;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod utils;
 
 pub use config::Config;
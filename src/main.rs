@@ -13,10 +13,15 @@ use tracing::{error, info};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use auth::{AuthState, JwtService};
-use config::Config;
+use config::{Config, ImageStoreConfig, NotificationProviderConfig};
 use db::{create_connection_pool, run_migrations};
 use routes::{create_api_router, AppState};
-use services::{AuthService, ImageService, LocationService};
+use services::{
+    AuthService, ChallengeEventHub, ChallengeScheduler, HttpPushNotifier, ImageCheckerPolicy,
+    ImageService, ImageStore, ImageUploader, LocalImageStore, LocationService, Mailer,
+    NotificationService, Notifier, ProofJobService, S3ImageStore, S3Settings, SmtpMailer,
+    SpoolNotifier,
+};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -59,22 +64,116 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let jwt_service = Arc::new(JwtService::new(&config.jwt_secret));
     let auth_service = Arc::new(AuthService::new(jwt_service.clone(), pool.clone()));
     let location_service = Arc::new(LocationService::new(pool.clone()));
+    let image_store: Arc<dyn ImageStore> = match &config.image_store {
+        ImageStoreConfig::Local { base_dir } => Arc::new(LocalImageStore::new(base_dir)),
+        ImageStoreConfig::S3 {
+            endpoint,
+            bucket,
+            region,
+            access_key_id,
+            secret_access_key,
+            path_style,
+        } => Arc::new(S3ImageStore::new(S3Settings {
+            endpoint: endpoint.clone(),
+            bucket: bucket.clone(),
+            region: region.clone(),
+            access_key_id: access_key_id.clone(),
+            secret_access_key: secret_access_key.clone(),
+            path_style: *path_style,
+        })),
+    };
+    let image_checker = &config.image_checker_client;
     let image_service = Arc::new(ImageService::new(
         config.image_checker_url.clone(),
-        config.image_base_dir.clone(),
+        image_store.clone(),
+        ImageCheckerPolicy {
+            request_timeout: std::time::Duration::from_secs(image_checker.timeout_seconds),
+            max_retries: image_checker.max_retries,
+            retry_base_delay: std::time::Duration::from_millis(image_checker.retry_base_delay_ms),
+            breaker_threshold: image_checker.breaker_threshold,
+            breaker_cooldown: std::time::Duration::from_secs(
+                image_checker.breaker_cooldown_seconds,
+            ),
+        },
+    ));
+    let image_uploader = Arc::new(ImageUploader::new(
+        image_store,
+        config.max_image_upload_bytes,
+    ));
+    let event_hub = Arc::new(ChallengeEventHub::default());
+    let notifier: Arc<dyn Notifier> = match &config.notification_provider {
+        NotificationProviderConfig::Http { endpoint, api_key } => {
+            Arc::new(HttpPushNotifier::new(endpoint.clone(), api_key.clone()))
+        }
+        NotificationProviderConfig::Spool { spool_dir } => {
+            Arc::new(SpoolNotifier::new(spool_dir.clone()))
+        }
+    };
+    let notification_service = Arc::new(NotificationService::new(pool.clone(), notifier));
+
+    // Drain the notification outbox in the background
+    tokio::spawn(
+        notification_service
+            .clone()
+            .run_dispatcher(std::time::Duration::from_secs(15)),
+    );
+
+    // Verify proof photos with the image checker in the background
+    let proof_jobs = Arc::new(ProofJobService::new(
+        pool.clone(),
+        image_service,
+        event_hub.clone(),
+    ));
+    tokio::spawn(
+        proof_jobs
+            .clone()
+            .run_workers(config.proof_workers, std::time::Duration::from_secs(1)),
+    );
+
+    // Start auto-start challenges at their planned start time
+    let challenge_scheduler = Arc::new(ChallengeScheduler::new(
+        pool.clone(),
+        event_hub.clone(),
+        notification_service.clone(),
     ));
+    tokio::spawn(challenge_scheduler.run(std::time::Duration::from_secs(
+        config.auto_start_poll_seconds,
+    )));
+
+    let mailer: Arc<dyn Mailer> = Arc::new(
+        SmtpMailer::new(
+            &config.smtp_host,
+            config.smtp_port,
+            config.smtp_security,
+            &config.smtp_from,
+            config.smtp_credentials.clone(),
+        )
+        .map_err(|e| {
+            error!("Failed to configure SMTP mailer: {}", e);
+            e
+        })?,
+    );
 
     info!("Services initialized");
 
     // Create auth state for middleware
-    let auth_state = AuthState { jwt_service };
+    let auth_state = AuthState {
+        jwt_service,
+        pool: pool.clone(),
+    };
 
     // Create application state
     let app_state = AppState {
         pool: pool.clone(),
         auth_service,
         location_service,
-        image_service,
+        image_uploader,
+        proof_jobs,
+        event_hub,
+        notification_service,
+        mailer,
+        app_base_url: config.app_base_url.clone(),
+        image_checker_callback_secret: config.image_checker_callback_secret.clone(),
         auth_state,
     };
 
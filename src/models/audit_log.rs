@@ -1,9 +1,15 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
-use sqlx::{FromRow, PgPool, Type};
+use sqlx::{FromRow, PgConnection, PgPool, Type};
 use uuid::Uuid;
 
+use crate::models::challenge::{
+    DuplicatePhotoPolicy, ParticipantStatus, WaypointOverrideAction, WaypointState,
+};
+use crate::models::proof_job::{DuplicatePhotoMatch, ProofAppeal};
+use crate::services::exif::PhotoMetadata;
+
 #[derive(Debug, Clone, Serialize, Deserialize, Type, PartialEq)]
 #[sqlx(type_name = "audit_event_type", rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum AuditEventType {
@@ -17,6 +23,12 @@ pub enum AuditEventType {
     WaypointProofSubmitted,
     WaypointVerified,
     LocationUpdated,
+    ParticipantForfeited,
+    ParticipantDisqualified,
+    ParticipantReinstated,
+    WaypointOverridden,
+    DuplicatePhotoDetected,
+    ProofAppealed,
 }
 
 #[derive(Debug, Clone, FromRow, Serialize)]
@@ -85,6 +97,8 @@ pub struct WaypointProofSubmittedData {
     pub image_path: String,
     pub processing_id: String,
     pub submission_time: DateTime<Utc>,
+    #[serde(default)]
+    pub photo_metadata: Option<PhotoMetadata>, // What the photo's EXIF data says, image proofs only
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -103,6 +117,42 @@ pub struct LocationUpdatedData {
     pub update_source: String, // "check_in", "periodic_update", etc.
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParticipantStatusChangedData {
+    pub previous_status: ParticipantStatus,
+    pub status: ParticipantStatus,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaypointOverriddenData {
+    pub waypoint_sequence: i32,
+    pub action: WaypointOverrideAction,
+    pub justification: String,
+    pub previous_waypoint: Option<i32>,
+    pub previous_state: WaypointState,
+    pub current_waypoint: Option<i32>,
+    pub state: WaypointState,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicatePhotoDetectedData {
+    pub waypoint_sequence: i32,
+    pub image_path: String,
+    pub perceptual_hash: String,
+    pub matches: Vec<DuplicatePhotoMatch>,
+    pub policy: DuplicatePhotoPolicy, // What was done with the photo
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProofAppealedData {
+    pub processing_id: Uuid,
+    pub waypoint_sequence: i32,
+    pub comment: String,
+    pub rejection_reasons: Vec<String>,
+    pub appeals_per_waypoint: i32,
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum AuditError {
     #[error("Database error: {0}")]
@@ -189,6 +239,18 @@ pub struct WaypointCheckInParams {
     pub within_radius: bool,
 }
 
+/// Parameters for logging waypoint proof submissions
+#[derive(Debug, Clone)]
+pub struct WaypointProofSubmissionParams<'a> {
+    pub participant_id: Uuid,
+    pub challenge_id: i32,
+    pub waypoint_id: i32,
+    pub waypoint_sequence: i32,
+    pub image_path: &'a str,
+    pub processing_id: &'a str,
+    pub photo_metadata: Option<&'a PhotoMetadata>,
+}
+
 /// Parameters for logging waypoint verification events
 #[derive(Debug, Clone)]
 pub struct WaypointVerificationParams<'a> {
@@ -202,9 +264,52 @@ pub struct WaypointVerificationParams<'a> {
     pub outcome_payload: Option<JsonValue>,
 }
 
+/// Parameters for logging moderator waypoint overrides
+#[derive(Debug, Clone)]
+pub struct WaypointOverrideParams<'a> {
+    pub user_id: i32,
+    pub participant_id: Uuid,
+    pub challenge_id: i32,
+    pub waypoint_sequence: i32,
+    pub action: WaypointOverrideAction,
+    pub justification: &'a str,
+    pub previous_waypoint: Option<i32>,
+    pub previous_state: WaypointState,
+    pub current_waypoint: Option<i32>,
+    pub state: WaypointState,
+}
+
+/// Parameters for logging a proof photo found to duplicate earlier ones
+#[derive(Debug, Clone)]
+pub struct DuplicatePhotoParams<'a> {
+    pub participant_id: Uuid,
+    pub challenge_id: i32,
+    pub waypoint_sequence: i32,
+    pub image_path: &'a str,
+    pub perceptual_hash: &'a str,
+    pub matches: &'a [DuplicatePhotoMatch],
+    pub policy: DuplicatePhotoPolicy,
+}
+
+/// Parameters for logging a participant's appeal of a rejected photo
+#[derive(Debug, Clone)]
+pub struct ProofAppealParams<'a> {
+    pub user_id: i32, // Who appealed, the captain when the participant is a group
+    pub appeal: &'a ProofAppeal,
+    pub appeals_per_waypoint: i32,
+}
+
 impl AuditLog {
     /// Create a new audit log entry
     pub async fn create(pool: &PgPool, entry: AuditLogEntry) -> Result<AuditLog, AuditError> {
+        Self::create_in(&mut *pool.acquire().await?, entry).await
+    }
+
+    /// Create a new audit log entry on a connection, inside the caller's transaction
+    pub async fn create_in(
+        conn: &mut PgConnection,
+        entry: AuditLogEntry,
+    ) -> Result<AuditLog, AuditError> {
         let audit_log = sqlx::query_as!(
             AuditLog,
             r#"
@@ -227,7 +332,7 @@ impl AuditLog {
             entry.outcome,
             entry.outcome_payload
         )
-        .fetch_one(pool)
+        .fetch_one(conn)
         .await?;
 
         Ok(audit_log)
@@ -400,26 +505,22 @@ impl AuditLog {
     /// Log waypoint proof submission event
     pub async fn log_waypoint_proof_submitted(
         pool: &PgPool,
-        participant_id: Uuid,
-        challenge_id: i32, // Now integer for temporal challenges
-        waypoint_id: i32,
-        waypoint_sequence: i32,
-        image_path: &str,
-        processing_id: &str,
+        params: WaypointProofSubmissionParams<'_>,
     ) -> Result<AuditLog, AuditError> {
         let event_data = WaypointProofSubmittedData {
-            waypoint_sequence,
-            image_path: image_path.to_string(),
-            processing_id: processing_id.to_string(),
+            waypoint_sequence: params.waypoint_sequence,
+            image_path: params.image_path.to_string(),
+            processing_id: params.processing_id.to_string(),
             submission_time: Utc::now(),
+            photo_metadata: params.photo_metadata.cloned(),
         };
 
         Self::create(
             pool,
             AuditLogEntry::new(AuditEventType::WaypointProofSubmitted)
-                .with_participant_id(participant_id)
-                .with_challenge_id(challenge_id)
-                .with_waypoint_id(waypoint_id)
+                .with_participant_id(params.participant_id)
+                .with_challenge_id(params.challenge_id)
+                .with_waypoint_id(params.waypoint_id)
                 .with_event_data(serde_json::to_value(event_data)?)
                 .with_outcome("submitted".to_string()),
         )
@@ -452,6 +553,124 @@ impl AuditLog {
         Self::create(pool, entry).await
     }
 
+    /// Log a participant forfeiting, or a moderator disqualifying or reinstating them. The
+    /// user is whoever made the change.
+    pub async fn log_participant_status_changed(
+        pool: &PgPool,
+        user_id: i32,
+        participant_id: Uuid,
+        challenge_id: i32,
+        previous_status: ParticipantStatus,
+        status: ParticipantStatus,
+        reason: Option<&str>,
+    ) -> Result<AuditLog, AuditError> {
+        let event_type = match status {
+            ParticipantStatus::Active => AuditEventType::ParticipantReinstated,
+            ParticipantStatus::Forfeited => AuditEventType::ParticipantForfeited,
+            ParticipantStatus::Disqualified => AuditEventType::ParticipantDisqualified,
+        };
+        let event_data = ParticipantStatusChangedData {
+            previous_status,
+            status,
+            reason: reason.map(|r| r.to_string()),
+        };
+
+        Self::create(
+            pool,
+            AuditLogEntry::new(event_type)
+                .with_user_id(user_id)
+                .with_participant_id(participant_id)
+                .with_challenge_id(challenge_id)
+                .with_event_data(serde_json::to_value(event_data)?)
+                .with_outcome("success".to_string()),
+        )
+        .await
+    }
+
+    /// Log a moderator forcing, rejecting or resetting a participant's waypoint
+    pub async fn log_waypoint_overridden(
+        conn: &mut PgConnection,
+        params: WaypointOverrideParams<'_>,
+    ) -> Result<AuditLog, AuditError> {
+        let event_data = WaypointOverriddenData {
+            waypoint_sequence: params.waypoint_sequence,
+            action: params.action,
+            justification: params.justification.to_string(),
+            previous_waypoint: params.previous_waypoint,
+            previous_state: params.previous_state,
+            current_waypoint: params.current_waypoint,
+            state: params.state,
+        };
+
+        Self::create_in(
+            conn,
+            AuditLogEntry::new(AuditEventType::WaypointOverridden)
+                .with_user_id(params.user_id)
+                .with_participant_id(params.participant_id)
+                .with_challenge_id(params.challenge_id)
+                .with_waypoint_id(params.waypoint_sequence)
+                .with_event_data(serde_json::to_value(event_data)?)
+                .with_outcome(params.action.to_string().to_lowercase()),
+        )
+        .await
+    }
+
+    /// Log a proof photo that near-duplicates photos other participants submitted
+    pub async fn log_duplicate_photo_detected(
+        pool: &PgPool,
+        params: DuplicatePhotoParams<'_>,
+    ) -> Result<AuditLog, AuditError> {
+        let event_data = DuplicatePhotoDetectedData {
+            waypoint_sequence: params.waypoint_sequence,
+            image_path: params.image_path.to_string(),
+            perceptual_hash: params.perceptual_hash.to_string(),
+            matches: params.matches.to_vec(),
+            policy: params.policy,
+        };
+        let outcome = match params.policy {
+            DuplicatePhotoPolicy::Review => "manual_review",
+            DuplicatePhotoPolicy::Reject => "rejected",
+        };
+
+        Self::create(
+            pool,
+            AuditLogEntry::new(AuditEventType::DuplicatePhotoDetected)
+                .with_participant_id(params.participant_id)
+                .with_challenge_id(params.challenge_id)
+                .with_waypoint_id(params.waypoint_sequence)
+                .with_event_data(serde_json::to_value(event_data)?)
+                .with_outcome(outcome.to_string()),
+        )
+        .await
+    }
+
+    /// Log a participant appealing a rejected photo to the moderators
+    pub async fn log_proof_appealed(
+        pool: &PgPool,
+        params: ProofAppealParams<'_>,
+    ) -> Result<AuditLog, AuditError> {
+        let appeal = params.appeal;
+        let event_data = ProofAppealedData {
+            processing_id: appeal.processing_id,
+            waypoint_sequence: appeal.waypoint_sequence,
+            comment: appeal.comment.clone(),
+            rejection_reasons: appeal.reasons.clone(),
+            appeals_per_waypoint: params.appeals_per_waypoint,
+        };
+
+        Self::create(
+            pool,
+            AuditLogEntry::new(AuditEventType::ProofAppealed)
+                .with_user_id(params.user_id)
+                .with_participant_id(appeal.participant_id)
+                .with_challenge_id(appeal.challenge_id)
+                .with_waypoint_id(appeal.waypoint_sequence)
+                .with_event_data(serde_json::to_value(event_data)?)
+                .with_outcome("manual_review".to_string()),
+        )
+        .await
+    }
+
     /// Log location update event
     #[allow(dead_code)]
     pub async fn log_location_updated(
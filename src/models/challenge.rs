@@ -1,10 +1,33 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, PgPool, Type};
+use sqlx::{FromRow, PgConnection, PgPool, Postgres, Transaction, Type};
+use std::collections::{BTreeMap, HashSet};
 use uuid::Uuid;
 
+use crate::services::auth_service::ParticipantAuthResponse;
 use crate::services::location_service::GeoLocation;
 
+/// Longest single extension a moderator can add to a running challenge
+const MAX_EXTENSION_MINUTES: i32 = 24 * 60;
+
+/// Advisory lock class serializing challenge starts and other changes to the current version
+/// across server replicas, the challenge id is the second lock key
+const CHALLENGE_VERSION_LOCK_CLASS: i32 = 0x5348_0001;
+
+/// Rejected photos a participant may appeal per waypoint unless the challenge says otherwise
+const DEFAULT_APPEALS_PER_WAYPOINT: i32 = 1;
+
+fn default_appeals_per_waypoint() -> i32 {
+    DEFAULT_APPEALS_PER_WAYPOINT
+}
+
+/// Points a verified route waypoint scores unless the challenge says otherwise
+const DEFAULT_WAYPOINT_POINTS: i32 = 10;
+
+fn default_waypoint_points() -> i32 {
+    DEFAULT_WAYPOINT_POINTS
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Type, PartialEq)]
 #[sqlx(type_name = "challenge_type", rename_all = "UPPERCASE")]
 pub enum ChallengeType {
@@ -35,6 +58,327 @@ pub enum WaypointState {
     Verified,
 }
 
+/// Whether a participant is still in the game. Only active participants can act, receive
+/// clues and count towards standings.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq)]
+#[sqlx(type_name = "participant_status", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ParticipantStatus {
+    Active,
+    Forfeited,
+    Disqualified,
+}
+
+impl std::fmt::Display for ParticipantStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            ParticipantStatus::Active => "ACTIVE",
+            ParticipantStatus::Forfeited => "FORFEITED",
+            ParticipantStatus::Disqualified => "DISQUALIFIED",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl ParticipantStatus {
+    /// Participants forfeit or get disqualified while active, a disqualification also
+    /// overrides a forfeit; reinstating returns either to the game
+    pub fn can_change_to(self, next: ParticipantStatus) -> bool {
+        matches!(
+            (self, next),
+            (ParticipantStatus::Active, ParticipantStatus::Forfeited)
+                | (ParticipantStatus::Active, ParticipantStatus::Disqualified)
+                | (
+                    ParticipantStatus::Forfeited,
+                    ParticipantStatus::Disqualified
+                )
+                | (ParticipantStatus::Forfeited, ParticipantStatus::Active)
+                | (ParticipantStatus::Disqualified, ParticipantStatus::Active)
+        )
+    }
+}
+
+/// How the challenge clock runs: one moderator-started clock for everyone, or a clock per
+/// participant started whenever they begin within the availability window
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ChallengePacing {
+    #[default]
+    Synchronized,
+    SelfPaced,
+}
+
+/// What happens to a proof photo whose EXIF data has no GPS location or capture time
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MissingExifPolicy {
+    /// Leave it to the image checker to judge on content
+    #[default]
+    Allow,
+    Reject,
+}
+
+/// What happens to a proof photo that matches one already submitted for the waypoint, in
+/// this challenge or an earlier one at the same spot
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DuplicatePhotoPolicy {
+    /// Hold it for a moderator to decide
+    #[default]
+    Review,
+    Reject,
+}
+
+/// Order in which waypoints may be visited. Waypoint sequences identify waypoints in every
+/// topology; branching routes start at the first waypoint and finish at any waypoint with
+/// nothing after it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(tag = "kind", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RouteTopology {
+    #[default]
+    Linear,
+    /// Any `required` of the waypoints, in any order
+    Unordered { required: i32 },
+    /// The waypoints a participant may choose from after each waypoint
+    Branching { branches: Vec<RouteBranch> },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RouteBranch {
+    pub from: i32,
+    pub to: Vec<i32>,
+}
+
+impl RouteTopology {
+    pub fn validate(&self, sequences: &[i32]) -> Result<(), ChallengeError> {
+        match self {
+            RouteTopology::Linear => Ok(()),
+            RouteTopology::Unordered { required } => {
+                if *required < 1 || *required as usize > sequences.len() {
+                    return Err(ChallengeError::ValidationFailed(format!(
+                        "Unordered routes must require between 1 and {} waypoints",
+                        sequences.len()
+                    )));
+                }
+                Ok(())
+            }
+            RouteTopology::Branching { branches } => {
+                let connects_waypoints = branches.iter().all(|branch| {
+                    sequences.contains(&branch.from)
+                        && branch.to.iter().all(|to| sequences.contains(to))
+                });
+                if !connects_waypoints {
+                    return Err(ChallengeError::ValidationFailed(
+                        "Branches must connect waypoints of the challenge".to_string(),
+                    ));
+                }
+
+                let next: BTreeMap<i32, &[i32]> = branches
+                    .iter()
+                    .map(|branch| (branch.from, branch.to.as_slice()))
+                    .collect();
+                if next.len() != branches.len() {
+                    return Err(ChallengeError::ValidationFailed(
+                        "Each waypoint can only have one set of branches".to_string(),
+                    ));
+                }
+
+                let mut visited = HashSet::new();
+                if let Some(&start) = sequences.iter().min() {
+                    if Self::has_cycle_from(&next, start, &mut Vec::new(), &mut visited) {
+                        return Err(ChallengeError::ValidationFailed(
+                            "Branches must not lead back to an earlier waypoint".to_string(),
+                        ));
+                    }
+                }
+                if visited.len() != sequences.len() {
+                    return Err(ChallengeError::ValidationFailed(
+                        "Every waypoint must be reachable from the first waypoint".to_string(),
+                    ));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Depth-first walk marking reachable waypoints, true when a branch leads back onto the
+    /// path that reached it
+    fn has_cycle_from(
+        next: &BTreeMap<i32, &[i32]>,
+        waypoint: i32,
+        path: &mut Vec<i32>,
+        visited: &mut HashSet<i32>,
+    ) -> bool {
+        if path.contains(&waypoint) {
+            return true;
+        }
+        if !visited.insert(waypoint) {
+            return false;
+        }
+
+        path.push(waypoint);
+        let cyclic = next
+            .get(&waypoint)
+            .into_iter()
+            .flat_map(|to| to.iter())
+            .any(|&to| Self::has_cycle_from(next, to, path, visited));
+        path.pop();
+
+        cyclic
+    }
+}
+
+/// How a participant proves they found a waypoint once checked in
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(tag = "kind", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ProofKind {
+    /// A photo the image checker matches against the waypoint's `image_subject`
+    #[default]
+    Image,
+    /// The payload of a QR sticker placed at the waypoint
+    QrCode { secret: String },
+    /// A typed answer, compared ignoring case and punctuation and allowing `max_typos` typos
+    /// (one per five characters of the answer when not set)
+    TextAnswer {
+        accepted_answers: Vec<String>,
+        #[serde(default)]
+        max_typos: Option<usize>,
+    },
+    /// One of `options`, `correct` holds the indexes of the right ones
+    MultipleChoice {
+        options: Vec<String>,
+        correct: Vec<usize>,
+    },
+    /// Checking in within the waypoint radius is proof enough
+    LocationOnly,
+}
+
+impl ProofKind {
+    pub fn validate(
+        &self,
+        waypoint_sequence: i32,
+        image_subject: &str,
+    ) -> Result<(), ChallengeError> {
+        let problem = match self {
+            ProofKind::Image if image_subject.trim().is_empty() => {
+                Some("image proofs need an image subject")
+            }
+            ProofKind::QrCode { secret } if secret.trim().is_empty() => {
+                Some("QR code proofs need a secret")
+            }
+            ProofKind::TextAnswer {
+                accepted_answers, ..
+            } if !accepted_answers.iter().any(|a| !a.trim().is_empty()) => {
+                Some("text answer proofs need an accepted answer")
+            }
+            ProofKind::MultipleChoice { options, correct }
+                if options.len() < 2
+                    || correct.is_empty()
+                    || correct.iter().any(|&i| i >= options.len()) =>
+            {
+                Some("multiple choice proofs need two options and correct options among them")
+            }
+            _ => None,
+        };
+
+        match problem {
+            Some(problem) => Err(ChallengeError::ValidationFailed(format!(
+                "Waypoint {waypoint_sequence}: {problem}"
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProofKind::Image => "IMAGE",
+            ProofKind::QrCode { .. } => "QR_CODE",
+            ProofKind::TextAnswer { .. } => "TEXT_ANSWER",
+            ProofKind::MultipleChoice { .. } => "MULTIPLE_CHOICE",
+            ProofKind::LocationOnly => "LOCATION_ONLY",
+        }
+    }
+
+    /// What the participant is asked for after checking in. Secrets and answers stay out.
+    pub fn prompt(&self, image_subject: &str) -> String {
+        match self {
+            ProofKind::Image => image_subject.to_string(),
+            ProofKind::QrCode { .. } => "Scan the QR code at the waypoint".to_string(),
+            ProofKind::TextAnswer { .. } => "Answer the riddle".to_string(),
+            ProofKind::MultipleChoice { .. } => "Choose the right option".to_string(),
+            ProofKind::LocationOnly => "Confirm your check-in".to_string(),
+        }
+    }
+
+    /// The options of a multiple choice proof, the only kind whose details participants see
+    pub fn options(&self) -> Option<&[String]> {
+        match self {
+            ProofKind::MultipleChoice { options, .. } => Some(options),
+            _ => None,
+        }
+    }
+}
+
+/// A waypoint's proof as participants see it: its kind, and the options of a multiple choice
+/// proof, never a secret or an answer
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ProofView {
+    Image,
+    QrCode,
+    TextAnswer,
+    MultipleChoice { options: Vec<String> },
+    LocationOnly,
+}
+
+impl From<&ProofKind> for ProofView {
+    fn from(proof: &ProofKind) -> Self {
+        match proof {
+            ProofKind::Image => ProofView::Image,
+            ProofKind::QrCode { .. } => ProofView::QrCode,
+            ProofKind::TextAnswer { .. } => ProofView::TextAnswer,
+            ProofKind::MultipleChoice { options, .. } => ProofView::MultipleChoice {
+                options: options.clone(),
+            },
+            ProofKind::LocationOnly => ProofView::LocationOnly,
+        }
+    }
+}
+
+/// An optional side quest off the main route, worth `points` when proven within its window.
+/// Bonus waypoints can be visited in any order and never hold up the route.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BonusWaypoint {
+    pub points: i32,
+    #[serde(default)]
+    pub available_from: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub available_until: Option<DateTime<Utc>>,
+}
+
+impl BonusWaypoint {
+    pub fn validate(&self, waypoint_sequence: i32) -> Result<(), ChallengeError> {
+        if self.points < 1 {
+            return Err(ChallengeError::ValidationFailed(format!(
+                "Waypoint {waypoint_sequence}: bonus waypoints must be worth at least one point"
+            )));
+        }
+        if let (Some(from), Some(until)) = (self.available_from, self.available_until) {
+            if until <= from {
+                return Err(ChallengeError::ValidationFailed(format!(
+                    "Waypoint {waypoint_sequence}: bonus availability must end after it begins"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_available_at(&self, at: DateTime<Utc>) -> bool {
+        self.available_from.is_none_or(|from| at >= from)
+            && self.available_until.is_none_or(|until| at < until)
+    }
+}
+
 // New temporal challenge structure for JSON storage
 #[derive(Debug, Clone, FromRow, Serialize)]
 pub struct TemporalChallenge {
@@ -54,13 +398,242 @@ pub struct TemporalChallenge {
 pub struct ChallengeData {
     pub challenge_id: i32, // For backwards compatibility
     pub challenge_description: Option<String>,
-    pub challenge_moderator: i32,
+    pub challenge_moderator: i32, // Owner, the moderator who created the challenge
+    #[serde(default)]
+    pub co_moderators: Vec<i32>,
     pub actual_start_time: Option<DateTime<Utc>>,
     pub duration_minutes: i32,
     pub challenge_type: ChallengeType,
     pub active: bool,
     pub waypoints: Vec<WaypointData>,
     pub metadata: ChallengeMetadata,
+    #[serde(default)]
+    pub pacing: ChallengePacing,
+    #[serde(default)]
+    pub available_from: Option<DateTime<Utc>>, // Self-paced only, defaults to planned start
+    #[serde(default)]
+    pub available_until: Option<DateTime<Utc>>, // Self-paced only, open-ended when null
+    #[serde(default)]
+    pub auto_start: bool, // Synchronized only - started by the scheduler at planned start
+    #[serde(default)]
+    pub pauses: Vec<ChallengePause>,
+    #[serde(default)]
+    pub route: RouteTopology,
+    #[serde(default)]
+    pub missing_exif: MissingExifPolicy,
+    #[serde(default)]
+    pub duplicate_photos: DuplicatePhotoPolicy,
+    #[serde(default = "default_appeals_per_waypoint")]
+    pub appeals_per_waypoint: i32, // Rejected photos a participant may appeal per waypoint
+    #[serde(default = "default_waypoint_points")]
+    pub waypoint_points: i32, // Scored per verified route waypoint, bonus waypoints score their own
+}
+
+impl ChallengeData {
+    pub fn moderator_role(&self, user_id: i32) -> Option<ModeratorRole> {
+        if self.challenge_moderator == user_id {
+            Some(ModeratorRole::Owner)
+        } else if self.co_moderators.contains(&user_id) {
+            Some(ModeratorRole::CoModerator)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_moderator(&self, user_id: i32) -> bool {
+        self.moderator_role(user_id).is_some()
+    }
+
+    /// The bonus details of a waypoint, None for waypoints on the main route
+    pub fn bonus_waypoint(&self, waypoint_sequence: i32) -> Option<&BonusWaypoint> {
+        self.waypoints
+            .iter()
+            .find(|w| w.waypoint_sequence == waypoint_sequence)
+            .and_then(|w| w.bonus.as_ref())
+    }
+
+    /// Points earned with the verified bonus waypoints
+    pub fn bonus_points(&self, verified: &[i32]) -> i32 {
+        verified
+            .iter()
+            .filter_map(|&sequence| self.bonus_waypoint(sequence))
+            .map(|bonus| bonus.points)
+            .sum()
+    }
+
+    /// A participant's score: `waypoint_points` for every verified route waypoint plus the
+    /// points of their verified bonus waypoints
+    pub fn score(&self, completed: &[i32], verified_bonus: &[i32]) -> i32 {
+        completed.len() as i32 * self.waypoint_points + self.bonus_points(verified_bonus)
+    }
+
+    /// Active participants ranked by score, ties going to whoever got there first.
+    /// Participants with the same score and time share a rank.
+    pub fn standings(&self, participants: &[ChallengeParticipant]) -> Vec<ChallengeStanding> {
+        let mut standings: Vec<ChallengeStanding> = participants
+            .iter()
+            .filter(|participant| participant.participant_status == ParticipantStatus::Active)
+            .map(|participant| {
+                let progress = participant.route_progress(self);
+                ChallengeStanding {
+                    rank: 0,
+                    participant_id: participant.participant_id,
+                    participant_nickname: participant.participant_nickname.clone(),
+                    completed_waypoints: progress.completed_waypoints.len(),
+                    finished: progress.finished,
+                    bonus_points: progress.bonus_points,
+                    score: progress.score,
+                    last_scored_at: participant.last_scored_at,
+                }
+            })
+            .collect();
+        standings.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then(a.last_scored_at.cmp(&b.last_scored_at))
+        });
+
+        for i in 0..standings.len() {
+            standings[i].rank = match i.checked_sub(1).map(|j| &standings[j]) {
+                Some(previous)
+                    if previous.score == standings[i].score
+                        && previous.last_scored_at == standings[i].last_scored_at =>
+                {
+                    previous.rank
+                }
+                _ => i + 1,
+            };
+        }
+
+        standings
+    }
+
+    /// Waypoints a participant may go to after completing `completed` (in the order they
+    /// completed them), empty once the route is finished. Bonus waypoints are not part of it.
+    pub fn next_waypoints(&self, completed: &[i32]) -> Vec<i32> {
+        let mut remaining: Vec<i32> = self
+            .waypoints
+            .iter()
+            .filter(|w| w.bonus.is_none())
+            .map(|w| w.waypoint_sequence)
+            .filter(|sequence| !completed.contains(sequence))
+            .collect();
+        remaining.sort_unstable();
+
+        match &self.route {
+            RouteTopology::Linear => remaining.into_iter().take(1).collect(),
+            RouteTopology::Unordered { required } => {
+                if completed.len() >= *required as usize {
+                    Vec::new()
+                } else {
+                    remaining
+                }
+            }
+            RouteTopology::Branching { branches } => match completed.last() {
+                None => remaining.into_iter().take(1).collect(),
+                Some(last) => branches
+                    .iter()
+                    .filter(|branch| branch.from == *last)
+                    .flat_map(|branch| branch.to.iter().copied())
+                    .filter(|sequence| remaining.contains(sequence))
+                    .collect(),
+            },
+        }
+    }
+
+    /// The waypoint presented to everyone at the start, None when participants pick their own
+    pub fn starting_waypoint(&self) -> Option<i32> {
+        match self.next_waypoints(&[]).as_slice() {
+            [only] => Some(*only),
+            _ => None,
+        }
+    }
+
+    pub fn is_route_finished(&self, completed: &[i32]) -> bool {
+        !completed.is_empty() && self.next_waypoints(completed).is_empty()
+    }
+
+    /// Drop a waypoint from the completed ones. On ordered routes everything completed after
+    /// it goes too, since it was reached through that waypoint.
+    pub fn rewind_to(&self, completed: &mut Vec<i32>, waypoint_sequence: i32) {
+        if let Some(position) = completed.iter().position(|&s| s == waypoint_sequence) {
+            match self.route {
+                RouteTopology::Unordered { .. } => {
+                    completed.remove(position);
+                }
+                _ => completed.truncate(position),
+            }
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.pauses
+            .last()
+            .is_some_and(|pause| pause.resumed_at.is_none())
+    }
+
+    /// Time the clock has been frozen up to `at`, an ongoing pause counts until `at`
+    pub fn paused_duration_at(&self, at: DateTime<Utc>) -> chrono::Duration {
+        self.pauses
+            .iter()
+            .filter(|pause| pause.paused_at < at)
+            .map(|pause| pause.resumed_at.unwrap_or(at).min(at) - pause.paused_at)
+            .fold(chrono::Duration::zero(), |total, paused| total + paused)
+    }
+
+    /// Effective end of a started synchronized challenge: start plus duration plus every
+    /// pause, so while paused the end keeps moving later
+    pub fn end_time_at(&self, at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.actual_start_time.map(|start| {
+            start
+                + chrono::Duration::minutes(self.duration_minutes as i64)
+                + self.paused_duration_at(at)
+        })
+    }
+
+    pub fn is_ended_at(&self, at: DateTime<Utc>) -> bool {
+        self.end_time_at(at).is_some_and(|end| at > end)
+    }
+
+    /// When self-paced deadlines stopped running: the start of the pause in progress, `at`
+    /// otherwise. Participants whose stored deadline is later are still playing.
+    pub fn deadlines_frozen_at(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        match self.pauses.last() {
+            Some(pause) if pause.resumed_at.is_none() => pause.paused_at.min(at),
+            _ => at,
+        }
+    }
+
+    /// A self-paced participant deadline pushed back by the pause in progress, completed
+    /// pauses are already applied to stored deadlines on resume
+    pub fn participant_deadline_at(
+        &self,
+        deadline: DateTime<Utc>,
+        at: DateTime<Utc>,
+    ) -> DateTime<Utc> {
+        match self.pauses.last() {
+            Some(pause) if pause.resumed_at.is_none() && deadline > pause.paused_at => {
+                deadline + (at - pause.paused_at).max(chrono::Duration::zero())
+            }
+            _ => deadline,
+        }
+    }
+}
+
+/// Owners manage the moderator set, co-moderators run the challenge alongside them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ModeratorRole {
+    Owner,
+    CoModerator,
+}
+
+/// A stretch of time in which a moderator froze the challenge clock
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChallengePause {
+    pub paused_at: DateTime<Utc>,
+    pub resumed_at: Option<DateTime<Utc>>, // Null while the challenge is paused
+    pub reason: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -73,6 +646,10 @@ pub struct WaypointData {
     pub hints: Vec<String>,
     pub waypoint_time_minutes: Option<i32>,
     pub image_subject: String,
+    #[serde(default)]
+    pub proof: ProofKind,
+    #[serde(default)]
+    pub bonus: Option<BonusWaypoint>, // Off the main route when set
     pub created_at: Option<DateTime<Utc>>,
 }
 
@@ -84,19 +661,62 @@ pub struct ChallengeMetadata {
     pub version_notes: Option<String>,
 }
 
+/// A waypoint as participants see it, with its proof redacted
+#[derive(Debug, Clone, Serialize)]
+pub struct WaypointView {
+    pub waypoint_id: Option<i32>,
+    pub waypoint_sequence: i32,
+    pub location: GeoLocation,
+    pub radius_meters: f64,
+    pub waypoint_clue: String,
+    pub hints: Vec<String>,
+    pub waypoint_time_minutes: Option<i32>,
+    pub image_subject: String,
+    pub proof: ProofView,
+    pub bonus: Option<BonusWaypoint>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl From<&WaypointData> for WaypointView {
+    fn from(waypoint: &WaypointData) -> Self {
+        Self {
+            waypoint_id: waypoint.waypoint_id,
+            waypoint_sequence: waypoint.waypoint_sequence,
+            location: waypoint.location.clone(),
+            radius_meters: waypoint.radius_meters,
+            waypoint_clue: waypoint.waypoint_clue.clone(),
+            hints: waypoint.hints.clone(),
+            waypoint_time_minutes: waypoint.waypoint_time_minutes,
+            image_subject: waypoint.image_subject.clone(),
+            proof: ProofView::from(&waypoint.proof),
+            bonus: waypoint.bonus.clone(),
+            created_at: waypoint.created_at,
+        }
+    }
+}
+
 // Legacy Challenge struct removed - now using TemporalChallenge with JSON storage
 // Legacy Waypoint struct removed - now embedded in challenge JSON as WaypointData
 
 #[derive(Debug, Clone, FromRow, Serialize)]
 pub struct ChallengeParticipant {
-    pub participant_id: Uuid,                 // UUID PRIMARY KEY - never null
-    pub challenge_id: i32,                    // NOT NULL FK to temporal_challenges - now integer
-    pub user_id: i32,                         // NOT NULL FK to users
-    pub participant_nickname: Option<String>, // Can be null
-    pub current_waypoint_id: Option<i32>,     // Can be null - now obsolete (waypoints in JSON)
-    pub current_state: WaypointState,         // DEFAULT 'PRESENTED' - never null
-    pub joined_at: DateTime<Utc>,             // DEFAULT NOW() - never null
-    pub last_updated: DateTime<Utc>,          // DEFAULT NOW() - never null
+    pub participant_id: Uuid,                  // UUID PRIMARY KEY - never null
+    pub challenge_id: i32,                     // NOT NULL FK to temporal_challenges - now integer
+    pub user_id: i32,                          // NOT NULL FK to users
+    pub participant_nickname: Option<String>,  // Can be null
+    pub current_waypoint_id: Option<i32>, // Can be null - waypoint_sequence currently presented
+    pub current_state: WaypointState,     // DEFAULT 'PRESENTED' - never null
+    pub joined_at: DateTime<Utc>,         // DEFAULT NOW() - never null
+    pub last_updated: DateTime<Utc>,      // DEFAULT NOW() - never null
+    pub started_at: Option<DateTime<Utc>>, // Self-paced only - when the participant began
+    pub deadline_at: Option<DateTime<Utc>>, // Self-paced only - started_at + duration
+    pub participant_status: ParticipantStatus, // DEFAULT 'ACTIVE' - never null
+    pub status_reason: Option<String>,    // Why the participant forfeited or was disqualified
+    pub status_changed_at: Option<DateTime<Utc>>,
+    pub completed_waypoints: Vec<i32>, // Verified waypoint sequences in the order completed
+    pub checked_in_bonus_waypoints: Vec<i32>, // Bonus waypoints awaiting a proof
+    pub verified_bonus_waypoints: Vec<i32>,
+    pub last_scored_at: Option<DateTime<Utc>>, // When the score last went up, breaks ties
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -107,6 +727,22 @@ pub struct CreateChallengeRequest {
     pub duration_minutes: i32,
     pub challenge_type: ChallengeType,
     pub waypoints: Vec<CreateWaypointRequest>,
+    #[serde(default)]
+    pub pacing: ChallengePacing,
+    pub available_from: Option<DateTime<Utc>>,
+    pub available_until: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub auto_start: bool,
+    #[serde(default)]
+    pub route: RouteTopology,
+    #[serde(default)]
+    pub missing_exif: MissingExifPolicy,
+    #[serde(default)]
+    pub duplicate_photos: DuplicatePhotoPolicy,
+    #[serde(default = "default_appeals_per_waypoint")]
+    pub appeals_per_waypoint: i32, // Rejected photos a participant may appeal per waypoint
+    #[serde(default = "default_waypoint_points")]
+    pub waypoint_points: i32, // Scored per verified route waypoint, bonus waypoints score their own
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -117,7 +753,12 @@ pub struct CreateWaypointRequest {
     pub waypoint_clue: String,
     pub hints: Vec<String>,
     pub waypoint_time_minutes: Option<i32>,
-    pub image_subject: String,
+    #[serde(default)]
+    pub image_subject: String, // Only image proofs need one
+    #[serde(default)]
+    pub proof: ProofKind,
+    #[serde(default)]
+    pub bonus: Option<BonusWaypoint>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -138,6 +779,173 @@ pub struct StartChallengeResponse {
     pub participants: Vec<ParticipantInfo>,
 }
 
+/// What a moderator does to a participant's waypoint when the image checker got it wrong
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum WaypointOverrideAction {
+    Verify,
+    Reject,
+    Reset,
+}
+
+impl WaypointOverrideAction {
+    /// Only a waypoint the participant may work on next can be verified, and only waypoints
+    /// already reached can be rejected or reset
+    pub fn applies_to(self, progress: &RouteProgress, waypoint_sequence: i32) -> bool {
+        if let Some(bonus) = progress
+            .bonus_waypoints
+            .iter()
+            .find(|bonus| bonus.waypoint_id == waypoint_sequence)
+        {
+            return match self {
+                WaypointOverrideAction::Verify => bonus.state != WaypointState::Verified,
+                WaypointOverrideAction::Reject | WaypointOverrideAction::Reset => {
+                    bonus.state != WaypointState::Presented
+                }
+            };
+        }
+
+        match self {
+            WaypointOverrideAction::Verify => {
+                progress.available_waypoints.contains(&waypoint_sequence)
+            }
+            WaypointOverrideAction::Reject | WaypointOverrideAction::Reset => {
+                progress.current_waypoint_id == Some(waypoint_sequence)
+                    || progress.completed_waypoints.contains(&waypoint_sequence)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for WaypointOverrideAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            WaypointOverrideAction::Verify => "VERIFY",
+            WaypointOverrideAction::Reject => "REJECT",
+            WaypointOverrideAction::Reset => "RESET",
+        };
+        write!(f, "{s}")
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WaypointOverrideRequest {
+    pub action: WaypointOverrideAction,
+    pub justification: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WaypointOverrideResponse {
+    #[serde(rename = "participant-id")]
+    pub participant_id: Uuid,
+    #[serde(rename = "overridden-waypoint-id")]
+    pub overridden_waypoint: i32,
+    pub action: WaypointOverrideAction,
+    #[serde(flatten)]
+    pub progress: RouteProgress,
+}
+
+/// Where a participant is on the route and where they may go next
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteProgress {
+    #[serde(rename = "waypoint-id")]
+    pub current_waypoint_id: Option<i32>,
+    pub state: WaypointState,
+    #[serde(rename = "completed-waypoints")]
+    pub completed_waypoints: Vec<i32>,
+    #[serde(rename = "available-waypoints")]
+    pub available_waypoints: Vec<i32>,
+    pub finished: bool,
+    #[serde(rename = "bonus-waypoints")]
+    pub bonus_waypoints: Vec<BonusProgress>,
+    #[serde(rename = "bonus-points")]
+    pub bonus_points: i32,
+    pub score: i32, // Route waypoints and bonus points together
+}
+
+/// A participant's place in the challenge standings
+#[derive(Debug, Clone, Serialize)]
+pub struct ChallengeStanding {
+    pub rank: usize,
+    #[serde(rename = "participant-id")]
+    pub participant_id: Uuid,
+    #[serde(rename = "participant-nickname")]
+    pub participant_nickname: Option<String>,
+    #[serde(rename = "completed-waypoints")]
+    pub completed_waypoints: usize, // Route waypoints verified
+    pub finished: bool,
+    #[serde(rename = "bonus-points")]
+    pub bonus_points: i32,
+    pub score: i32,
+    #[serde(rename = "last-scored-at")]
+    pub last_scored_at: Option<DateTime<Utc>>, // When the score last went up, breaks ties
+}
+
+/// A participant's state at a bonus waypoint: presented until they check in there
+#[derive(Debug, Clone, Serialize)]
+pub struct BonusProgress {
+    #[serde(rename = "waypoint-id")]
+    pub waypoint_id: i32,
+    pub state: WaypointState,
+    pub points: i32,
+    #[serde(rename = "available-from")]
+    pub available_from: Option<DateTime<Utc>>,
+    #[serde(rename = "available-until")]
+    pub available_until: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ParticipantStatusRequest {
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChallengeModeratorInfo {
+    #[serde(rename = "user-id")]
+    pub user_id: i32,
+    pub nickname: Option<String>,
+    pub role: ModeratorRole,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PauseChallengeRequest {
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExtendChallengeRequest {
+    pub minutes: i32,
+}
+
+/// Clock of a challenge after a pause, resume or extension
+#[derive(Debug, Clone, Serialize)]
+pub struct ChallengeTimingResponse {
+    #[serde(rename = "challenge-id")]
+    pub challenge_id: i32,
+    pub paused: bool,
+    pub duration: i32,
+    #[serde(rename = "actual-start-time")]
+    pub actual_start_time: Option<DateTime<Utc>>,
+    /// Effective end, moves later for as long as the challenge stays paused
+    #[serde(rename = "end-time")]
+    pub end_time: Option<DateTime<Utc>>,
+    pub pauses: Vec<ChallengePause>,
+}
+
+/// Participant token and clock of a participant who began a self-paced challenge
+#[derive(Debug, Clone, Serialize)]
+pub struct BeginChallengeResponse {
+    #[serde(flatten)]
+    pub token: ParticipantAuthResponse,
+    #[serde(rename = "participant-id")]
+    pub participant_id: Uuid,
+    #[serde(rename = "started-at")]
+    pub started_at: DateTime<Utc>,
+    pub deadline: DateTime<Utc>,
+    #[serde(rename = "waypoint-id")]
+    pub current_waypoint_id: Option<i32>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct ParticipantInfo {
     #[serde(rename = "user-id")]
@@ -153,6 +961,14 @@ pub struct ChallengeResponse {
     pub participants: Vec<ChallengeParticipant>,
 }
 
+/// A challenge as anyone but its moderators sees it, proofs redacted everywhere
+#[derive(Debug, Clone, Serialize)]
+pub struct ChallengeViewResponse {
+    pub challenge: TemporalChallenge, // Its challenge JSON redacted as well
+    pub waypoints: Vec<WaypointView>,
+    pub participants: Vec<ChallengeParticipant>,
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum ChallengeError {
     #[error("Database error: {0}")]
@@ -169,12 +985,37 @@ pub enum ChallengeError {
     ChallengeNotActive,
     #[error("User not moderator of challenge")]
     NotModerator,
+    #[error("User already moderates challenge")]
+    AlreadyModerator,
     #[error("User already participant in challenge")]
     AlreadyParticipant,
     #[error("Invalid waypoint sequence")]
     InvalidWaypointSequence,
     #[error("Challenge validation failed: {0}")]
     ValidationFailed(String),
+    #[error("Challenge is self-paced")]
+    SelfPaced,
+    #[error("Challenge is not self-paced")]
+    NotSelfPaced,
+    #[error("Challenge is not available at this time")]
+    NotAvailable,
+    #[error("Challenge has not started")]
+    ChallengeNotStarted,
+    #[error("Challenge has ended")]
+    ChallengeEnded,
+    #[error("Challenge is paused")]
+    ChallengePaused,
+    #[error("Challenge is not paused")]
+    ChallengeNotPaused,
+    #[error("Participant is not active")]
+    ParticipantNotActive,
+    #[error("Participant is not checked in at the waypoint")]
+    NotCheckedIn,
+    #[error("Participant is {from} and cannot become {to}")]
+    InvalidStatusChange {
+        from: ParticipantStatus,
+        to: ParticipantStatus,
+    },
 }
 
 // Legacy Challenge implementation removed - now using TemporalChallenge
@@ -193,7 +1034,11 @@ impl ChallengeParticipant {
                    current_waypoint_id, 
                    COALESCE(current_state, 'PRESENTED') as "current_state!: WaypointState",
                    COALESCE(joined_at, NOW()) as "joined_at!",
-                   COALESCE(last_updated, NOW()) as "last_updated!"
+                   COALESCE(last_updated, NOW()) as "last_updated!",
+                   started_at, deadline_at,
+                   participant_status as "participant_status: ParticipantStatus",
+                   status_reason, status_changed_at, completed_waypoints,
+                   checked_in_bonus_waypoints, verified_bonus_waypoints, last_scored_at
             FROM challenge_participants
             WHERE participant_id = $1
             "#,
@@ -218,7 +1063,11 @@ impl ChallengeParticipant {
                    current_waypoint_id, 
                    COALESCE(current_state, 'PRESENTED') as "current_state!: WaypointState",
                    COALESCE(joined_at, NOW()) as "joined_at!",
-                   COALESCE(last_updated, NOW()) as "last_updated!"
+                   COALESCE(last_updated, NOW()) as "last_updated!",
+                   started_at, deadline_at,
+                   participant_status as "participant_status: ParticipantStatus",
+                   status_reason, status_changed_at, completed_waypoints,
+                   checked_in_bonus_waypoints, verified_bonus_waypoints, last_scored_at
             FROM challenge_participants
             WHERE challenge_id = $1 AND user_id = $2
             "#,
@@ -231,14 +1080,188 @@ impl ChallengeParticipant {
         Ok(participant)
     }
 
-    pub async fn create_for_challenge(
+    /// The participant a user plays as: their own for individual players, or the shared
+    /// participant of their group. Only users who accepted their invitation have one.
+    pub async fn get_accepted_for_user(
         pool: &PgPool,
         challenge_id: i32,
         user_id: i32,
+    ) -> Result<Option<ChallengeParticipant>, ChallengeError> {
+        let participant = sqlx::query_as!(
+            ChallengeParticipant,
+            r#"
+            SELECT p.participant_id as "participant_id!", p.challenge_id as "challenge_id!",
+                   p.user_id as "user_id!", p.participant_nickname, p.current_waypoint_id,
+                   COALESCE(p.current_state, 'PRESENTED') as "current_state!: WaypointState",
+                   COALESCE(p.joined_at, NOW()) as "joined_at!",
+                   COALESCE(p.last_updated, NOW()) as "last_updated!",
+                   p.started_at, p.deadline_at,
+                   p.participant_status as "participant_status: ParticipantStatus",
+                   p.status_reason, p.status_changed_at, p.completed_waypoints,
+                   p.checked_in_bonus_waypoints, p.verified_bonus_waypoints, p.last_scored_at
+            FROM challenge_invitations i
+            LEFT JOIN participant_group_members m
+              ON m.challenge_id = i.challenge_id AND m.user_id = i.invited_user_id
+            LEFT JOIN participant_groups g ON g.group_id = m.group_id
+            JOIN challenge_participants p
+              ON p.participant_id = g.participant_id
+              OR (g.participant_id IS NULL AND p.challenge_id = i.challenge_id
+                  AND p.user_id = i.invited_user_id)
+            WHERE i.challenge_id = $1 AND i.invited_user_id = $2 AND i.status = 'ACCEPTED'
+            "#,
+            challenge_id,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(participant)
+    }
+
+    /// Start a self-paced participant's clock and present the first waypoint. Beginning again
+    /// keeps the original start time and deadline, so group members share one clock.
+    pub async fn begin(
+        &mut self,
+        pool: &PgPool,
+        first_waypoint: Option<i32>,
+        duration_minutes: i32,
+    ) -> Result<(), ChallengeError> {
+        let started = sqlx::query!(
+            r#"
+            UPDATE challenge_participants
+            SET started_at = COALESCE(started_at, NOW()),
+                deadline_at = COALESCE(deadline_at, NOW() + make_interval(mins => $2)),
+                current_waypoint_id = COALESCE(current_waypoint_id, $3),
+                last_updated = NOW()
+            WHERE participant_id = $1
+            RETURNING started_at, deadline_at, current_waypoint_id,
+                      COALESCE(last_updated, NOW()) as "last_updated!"
+            "#,
+            self.participant_id,
+            duration_minutes,
+            first_waypoint
+        )
+        .fetch_one(pool)
+        .await?;
+
+        self.started_at = started.started_at;
+        self.deadline_at = started.deadline_at;
+        self.current_waypoint_id = started.current_waypoint_id;
+        self.last_updated = started.last_updated;
+
+        Ok(())
+    }
+
+    /// Forfeit, disqualify or reinstate the participant
+    pub async fn change_status(
+        &mut self,
+        pool: &PgPool,
+        status: ParticipantStatus,
+        reason: Option<String>,
+    ) -> Result<(), ChallengeError> {
+        if !self.participant_status.can_change_to(status) {
+            return Err(ChallengeError::InvalidStatusChange {
+                from: self.participant_status,
+                to: status,
+            });
+        }
+
+        let changed = match sqlx::query!(
+            r#"
+            UPDATE challenge_participants
+            SET participant_status = $2, status_reason = $3, status_changed_at = NOW(),
+                last_updated = NOW()
+            WHERE participant_id = $1 AND participant_status = $4
+            RETURNING status_changed_at as "status_changed_at!",
+                      COALESCE(last_updated, NOW()) as "last_updated!"
+            "#,
+            self.participant_id,
+            status as ParticipantStatus,
+            reason,
+            self.participant_status as ParticipantStatus
+        )
+        .fetch_optional(pool)
+        .await?
+        {
+            Some(changed) => changed,
+            // Changed concurrently, report the status it has now
+            None => {
+                let from = Self::get_status(pool, self.participant_id)
+                    .await?
+                    .ok_or(ChallengeError::ParticipantNotFound)?;
+                return Err(ChallengeError::InvalidStatusChange { from, to: status });
+            }
+        };
+
+        self.participant_status = status;
+        self.status_reason = reason;
+        self.status_changed_at = Some(changed.status_changed_at);
+        self.last_updated = changed.last_updated;
+
+        Ok(())
+    }
+
+    /// Status of a participant, None when the participant no longer exists
+    pub async fn get_status(
+        pool: &PgPool,
+        participant_id: Uuid,
+    ) -> Result<Option<ParticipantStatus>, ChallengeError> {
+        let status = sqlx::query_scalar!(
+            r#"
+            SELECT participant_status as "participant_status: ParticipantStatus"
+            FROM challenge_participants WHERE participant_id = $1
+            "#,
+            participant_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(status)
+    }
+
+    /// Push back the deadline of every self-paced participant still playing at `playing_at`
+    pub async fn extend_deadlines(
+        pool: &PgPool,
+        challenge_id: i32,
+        playing_at: DateTime<Utc>,
+        by: chrono::Duration,
+    ) -> Result<u64, ChallengeError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE challenge_participants
+            SET deadline_at = deadline_at + make_interval(secs => $3),
+                last_updated = NOW()
+            WHERE challenge_id = $1 AND deadline_at > $2
+            "#,
+            challenge_id,
+            playing_at,
+            by.num_milliseconds() as f64 / 1000.0
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn create_for_challenge(
+        conn: &mut PgConnection,
+        challenge_id: i32,
+        user_id: i32,
         nickname: Option<String>,
     ) -> Result<ChallengeParticipant, ChallengeError> {
         // Check if user is already a participant
-        if (Self::get_by_challenge_and_user(pool, challenge_id, user_id).await?).is_some() {
+        let joined = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM challenge_participants WHERE challenge_id = $1 AND user_id = $2
+            ) as "exists!"
+            "#,
+            challenge_id,
+            user_id
+        )
+        .fetch_one(&mut *conn)
+        .await?;
+        if joined {
             return Err(ChallengeError::AlreadyParticipant);
         }
 
@@ -251,13 +1274,17 @@ impl ChallengeParticipant {
                      current_waypoint_id, 
                      COALESCE(current_state, 'PRESENTED') as "current_state!: WaypointState",
                      COALESCE(joined_at, NOW()) as "joined_at!",
-                     COALESCE(last_updated, NOW()) as "last_updated!"
+                     COALESCE(last_updated, NOW()) as "last_updated!",
+                   started_at, deadline_at,
+                   participant_status as "participant_status: ParticipantStatus",
+                   status_reason, status_changed_at, completed_waypoints,
+                   checked_in_bonus_waypoints, verified_bonus_waypoints, last_scored_at
             "#,
             challenge_id,
             user_id,
             nickname
         )
-        .fetch_one(pool)
+        .fetch_one(conn)
         .await?;
 
         Ok(participant)
@@ -289,6 +1316,384 @@ impl ChallengeParticipant {
         Ok(())
     }
 
+    /// Lock the participant's row for the rest of the returned transaction and reload it.
+    /// Group members, the proof worker and moderators all write the same row, so progress
+    /// is read again under the lock before it is changed.
+    pub async fn lock_for_update(
+        &mut self,
+        pool: &PgPool,
+    ) -> Result<Transaction<'static, Postgres>, ChallengeError> {
+        let mut tx = pool.begin().await?;
+        *self = sqlx::query_as!(
+            ChallengeParticipant,
+            r#"
+            SELECT participant_id as "participant_id!", challenge_id as "challenge_id!", user_id as "user_id!", participant_nickname,
+                   current_waypoint_id, 
+                   COALESCE(current_state, 'PRESENTED') as "current_state!: WaypointState",
+                   COALESCE(joined_at, NOW()) as "joined_at!",
+                   COALESCE(last_updated, NOW()) as "last_updated!",
+                   started_at, deadline_at,
+                   participant_status as "participant_status: ParticipantStatus",
+                   status_reason, status_changed_at, completed_waypoints,
+                   checked_in_bonus_waypoints, verified_bonus_waypoints, last_scored_at
+            FROM challenge_participants
+            WHERE participant_id = $1
+            FOR UPDATE
+            "#,
+            self.participant_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(ChallengeError::ParticipantNotFound)?;
+
+        Ok(tx)
+    }
+
+    /// Move the participant to a waypoint in the given state, saving their completed waypoints.
+    /// Run it under [`Self::lock_for_update`] so the saved waypoints are not stale.
+    async fn set_waypoint(
+        &mut self,
+        conn: &mut PgConnection,
+        waypoint_sequence: i32,
+        state: WaypointState,
+    ) -> Result<(), ChallengeError> {
+        let now = Utc::now();
+
+        sqlx::query!(
+            r#"
+            UPDATE challenge_participants
+            SET current_waypoint_id = $1, current_state = $2, completed_waypoints = $3,
+                last_updated = $4
+            WHERE participant_id = $5
+            "#,
+            waypoint_sequence,
+            state as WaypointState,
+            &self.completed_waypoints,
+            now,
+            self.participant_id
+        )
+        .execute(conn)
+        .await?;
+
+        self.current_waypoint_id = Some(waypoint_sequence);
+        self.current_state = state;
+        self.last_updated = now;
+
+        Ok(())
+    }
+
+    /// Move a bonus waypoint to `state`, it is presented again once dropped from both lists.
+    /// Run it under [`Self::lock_for_update`] like [`Self::set_waypoint`].
+    async fn set_bonus_state(
+        &mut self,
+        conn: &mut PgConnection,
+        waypoint_sequence: i32,
+        state: WaypointState,
+    ) -> Result<(), ChallengeError> {
+        self.checked_in_bonus_waypoints
+            .retain(|&sequence| sequence != waypoint_sequence);
+        self.verified_bonus_waypoints
+            .retain(|&sequence| sequence != waypoint_sequence);
+        match state {
+            WaypointState::Presented => {}
+            WaypointState::CheckedIn => self.checked_in_bonus_waypoints.push(waypoint_sequence),
+            WaypointState::Verified => self.verified_bonus_waypoints.push(waypoint_sequence),
+        }
+
+        let now = Utc::now();
+        sqlx::query!(
+            r#"
+            UPDATE challenge_participants
+            SET checked_in_bonus_waypoints = $1, verified_bonus_waypoints = $2, last_updated = $3
+            WHERE participant_id = $4
+            "#,
+            &self.checked_in_bonus_waypoints,
+            &self.verified_bonus_waypoints,
+            now,
+            self.participant_id
+        )
+        .execute(conn)
+        .await?;
+
+        self.last_updated = now;
+
+        Ok(())
+    }
+
+    pub fn bonus_state(&self, waypoint_sequence: i32) -> WaypointState {
+        if self.verified_bonus_waypoints.contains(&waypoint_sequence) {
+            WaypointState::Verified
+        } else if self.checked_in_bonus_waypoints.contains(&waypoint_sequence) {
+            WaypointState::CheckedIn
+        } else {
+            WaypointState::Presented
+        }
+    }
+
+    /// Check in at a waypoint. Bonus check-ins leave the participant's place on the route alone.
+    pub async fn check_in(
+        &mut self,
+        pool: &PgPool,
+        challenge_data: &ChallengeData,
+        waypoint_sequence: i32,
+    ) -> Result<(), ChallengeError> {
+        let mut tx = self.lock_for_update(pool).await?;
+        self.can_check_in(challenge_data, waypoint_sequence, Utc::now())?;
+        if challenge_data.bonus_waypoint(waypoint_sequence).is_some() {
+            self.set_bonus_state(&mut tx, waypoint_sequence, WaypointState::CheckedIn)
+                .await?;
+        } else {
+            self.set_waypoint(&mut tx, waypoint_sequence, WaypointState::CheckedIn)
+                .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Synchronized participants start with the challenge, self-paced ones when they begin
+    pub fn has_started(&self, challenge_data: &ChallengeData) -> bool {
+        self.clock_started_at(challenge_data).is_some()
+    }
+
+    /// When the participant's clock started: the challenge start for synchronized challenges,
+    /// their own start for self-paced ones
+    pub fn clock_started_at(&self, challenge_data: &ChallengeData) -> Option<DateTime<Utc>> {
+        match challenge_data.pacing {
+            ChallengePacing::Synchronized => challenge_data.actual_start_time,
+            ChallengePacing::SelfPaced => self.started_at,
+        }
+    }
+
+    /// Waypoints the participant may work on: the one presented or checked in at, or the
+    /// route's choice of next waypoints once their last one is verified
+    pub fn available_waypoints(&self, challenge_data: &ChallengeData) -> Vec<i32> {
+        match self.current_waypoint_id {
+            Some(current) if self.current_state != WaypointState::Verified => vec![current],
+            _ => challenge_data.next_waypoints(&self.completed_waypoints),
+        }
+    }
+
+    pub fn route_progress(&self, challenge_data: &ChallengeData) -> RouteProgress {
+        RouteProgress {
+            current_waypoint_id: self.current_waypoint_id,
+            state: self.current_state,
+            completed_waypoints: self.completed_waypoints.clone(),
+            available_waypoints: self.available_waypoints(challenge_data),
+            finished: challenge_data.is_route_finished(&self.completed_waypoints),
+            bonus_waypoints: challenge_data
+                .waypoints
+                .iter()
+                .filter_map(|w| {
+                    w.bonus.as_ref().map(|bonus| BonusProgress {
+                        waypoint_id: w.waypoint_sequence,
+                        state: self.bonus_state(w.waypoint_sequence),
+                        points: bonus.points,
+                        available_from: bonus.available_from,
+                        available_until: bonus.available_until,
+                    })
+                })
+                .collect(),
+            bonus_points: challenge_data.bonus_points(&self.verified_bonus_waypoints),
+            score: challenge_data.score(&self.completed_waypoints, &self.verified_bonus_waypoints),
+        }
+    }
+
+    /// Whether the participant may check in at a waypoint at `at`: they must be playing, the
+    /// clock must be running and the route must allow the waypoint next, or for bonus
+    /// waypoints it must be open and not yet verified
+    pub fn can_check_in(
+        &self,
+        challenge_data: &ChallengeData,
+        waypoint_sequence: i32,
+        at: DateTime<Utc>,
+    ) -> Result<(), ChallengeError> {
+        if self.participant_status != ParticipantStatus::Active {
+            return Err(ChallengeError::ParticipantNotActive);
+        }
+        if !self.has_started(challenge_data) {
+            return Err(ChallengeError::ChallengeNotStarted);
+        }
+        if challenge_data.is_paused() {
+            return Err(ChallengeError::ChallengePaused);
+        }
+        let ended = match challenge_data.pacing {
+            ChallengePacing::Synchronized => challenge_data.is_ended_at(at),
+            ChallengePacing::SelfPaced => self
+                .deadline_at
+                .is_some_and(|deadline| at > challenge_data.participant_deadline_at(deadline, at)),
+        };
+        if ended {
+            return Err(ChallengeError::ChallengeEnded);
+        }
+        let available = match challenge_data.bonus_waypoint(waypoint_sequence) {
+            Some(bonus) => {
+                bonus.is_available_at(at)
+                    && self.bonus_state(waypoint_sequence) != WaypointState::Verified
+            }
+            None => self
+                .available_waypoints(challenge_data)
+                .contains(&waypoint_sequence),
+        };
+        if !available {
+            return Err(ChallengeError::InvalidWaypointSequence);
+        }
+
+        Ok(())
+    }
+
+    /// Whether the participant may prove a waypoint at `at`: the same rules as checking in,
+    /// and they must be checked in there
+    pub fn can_submit_proof(
+        &self,
+        challenge_data: &ChallengeData,
+        waypoint_sequence: i32,
+        at: DateTime<Utc>,
+    ) -> Result<(), ChallengeError> {
+        self.can_check_in(challenge_data, waypoint_sequence, at)?;
+        if !self.is_checked_in_at(challenge_data, waypoint_sequence) {
+            return Err(ChallengeError::NotCheckedIn);
+        }
+
+        Ok(())
+    }
+
+    /// Whether the participant is checked in at a waypoint and owes it a proof
+    pub fn is_checked_in_at(&self, challenge_data: &ChallengeData, waypoint_sequence: i32) -> bool {
+        match challenge_data.bonus_waypoint(waypoint_sequence) {
+            Some(_) => self.bonus_state(waypoint_sequence) == WaypointState::CheckedIn,
+            None => {
+                self.current_waypoint_id == Some(waypoint_sequence)
+                    && self.current_state == WaypointState::CheckedIn
+            }
+        }
+    }
+
+    /// Verify a waypoint and present what comes next when the route leaves a single choice.
+    /// Returns the presented waypoint, None when the participant finished, picks their own
+    /// next waypoint or verified a bonus waypoint.
+    pub async fn complete_waypoint(
+        &mut self,
+        pool: &PgPool,
+        challenge_data: &ChallengeData,
+        waypoint_sequence: i32,
+    ) -> Result<Option<i32>, ChallengeError> {
+        let mut tx = self.lock_for_update(pool).await?;
+        let next = self
+            .complete_waypoint_locked(&mut tx, challenge_data, waypoint_sequence)
+            .await?;
+        tx.commit().await?;
+
+        Ok(next)
+    }
+
+    async fn complete_waypoint_locked(
+        &mut self,
+        conn: &mut PgConnection,
+        challenge_data: &ChallengeData,
+        waypoint_sequence: i32,
+    ) -> Result<Option<i32>, ChallengeError> {
+        // Another group member or a moderator may have got there first, leave their progress
+        // alone then
+        if challenge_data.bonus_waypoint(waypoint_sequence).is_some() {
+            if !self.verified_bonus_waypoints.contains(&waypoint_sequence) {
+                self.set_bonus_state(conn, waypoint_sequence, WaypointState::Verified)
+                    .await?;
+                self.mark_scored(conn).await?;
+            }
+            return Ok(None);
+        }
+        if self.completed_waypoints.contains(&waypoint_sequence) {
+            return Ok(None);
+        }
+        self.completed_waypoints.push(waypoint_sequence);
+
+        let next = match challenge_data
+            .next_waypoints(&self.completed_waypoints)
+            .as_slice()
+        {
+            [next] => {
+                self.set_waypoint(conn, *next, WaypointState::Presented)
+                    .await?;
+                Some(*next)
+            }
+            _ => {
+                self.set_waypoint(conn, waypoint_sequence, WaypointState::Verified)
+                    .await?;
+                None
+            }
+        };
+        self.mark_scored(conn).await?;
+
+        Ok(next)
+    }
+
+    /// Remember when the participant's score last went up, the standings break ties on it
+    async fn mark_scored(&mut self, conn: &mut PgConnection) -> Result<(), ChallengeError> {
+        sqlx::query!(
+            "UPDATE challenge_participants SET last_scored_at = $1 WHERE participant_id = $2",
+            self.last_updated,
+            self.participant_id
+        )
+        .execute(conn)
+        .await?;
+
+        self.last_scored_at = Some(self.last_updated);
+
+        Ok(())
+    }
+
+    /// Apply a moderator override. Verifying completes a waypoint like an accepted proof;
+    /// rejecting and resetting send the participant back to a waypoint they reached, checked
+    /// in but needing a new proof, or presented afresh. The participant's row must be locked
+    /// with [`Self::lock_for_update`], the override is recorded in the same transaction.
+    pub async fn override_waypoint(
+        &mut self,
+        conn: &mut PgConnection,
+        challenge_data: &ChallengeData,
+        waypoint_sequence: i32,
+        action: WaypointOverrideAction,
+    ) -> Result<(), ChallengeError> {
+        if !challenge_data
+            .waypoints
+            .iter()
+            .any(|w| w.waypoint_sequence == waypoint_sequence)
+        {
+            return Err(ChallengeError::WaypointNotFound);
+        }
+        if self.participant_status != ParticipantStatus::Active {
+            return Err(ChallengeError::ParticipantNotActive);
+        }
+        if !self.has_started(challenge_data)
+            || !action.applies_to(&self.route_progress(challenge_data), waypoint_sequence)
+        {
+            return Err(ChallengeError::InvalidWaypointSequence);
+        }
+
+        let bonus = challenge_data.bonus_waypoint(waypoint_sequence).is_some();
+        match action {
+            // Bonus waypoints included, so the participant's score is recorded the same way
+            WaypointOverrideAction::Verify => {
+                self.complete_waypoint_locked(conn, challenge_data, waypoint_sequence)
+                    .await?;
+            }
+            WaypointOverrideAction::Reject | WaypointOverrideAction::Reset => {
+                let state = match action {
+                    WaypointOverrideAction::Reject => WaypointState::CheckedIn,
+                    _ => WaypointState::Presented,
+                };
+                if bonus {
+                    self.set_bonus_state(conn, waypoint_sequence, state).await?;
+                } else {
+                    challenge_data.rewind_to(&mut self.completed_waypoints, waypoint_sequence);
+                    self.set_waypoint(conn, waypoint_sequence, state).await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     pub async fn get_participants_for_challenge(
         pool: &PgPool,
         challenge_id: i32,
@@ -300,16 +1705,96 @@ impl ChallengeParticipant {
                    current_waypoint_id, 
                    COALESCE(current_state, 'PRESENTED') as "current_state!: WaypointState",
                    COALESCE(joined_at, NOW()) as "joined_at!",
-                   COALESCE(last_updated, NOW()) as "last_updated!"
+                   COALESCE(last_updated, NOW()) as "last_updated!",
+                   started_at, deadline_at,
+                   participant_status as "participant_status: ParticipantStatus",
+                   status_reason, status_changed_at, completed_waypoints,
+                   checked_in_bonus_waypoints, verified_bonus_waypoints, last_scored_at
             FROM challenge_participants
             WHERE challenge_id = $1
             "#,
             challenge_id
         )
-        .fetch_all(pool)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(participants)
+    }
+
+    /// Every user still playing in a challenge, including all members of participant groups
+    pub async fn get_user_ids_for_challenge(
+        pool: &PgPool,
+        challenge_id: i32,
+    ) -> Result<Vec<i32>, ChallengeError> {
+        let user_ids = sqlx::query_scalar!(
+            r#"
+            SELECT user_id as "user_id!" FROM challenge_participants
+            WHERE challenge_id = $1 AND user_id IS NOT NULL AND participant_status = 'ACTIVE'
+            UNION
+            SELECT m.user_id FROM participant_group_members m
+            JOIN participant_groups g ON g.group_id = m.group_id
+            JOIN challenge_participants p ON p.participant_id = g.participant_id
+            WHERE m.challenge_id = $1 AND p.participant_status = 'ACTIVE'
+            "#,
+            challenge_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(user_ids)
+    }
+
+    /// Participants whose current waypoint is the given waypoint sequence
+    pub async fn get_participants_at_waypoint(
+        pool: &PgPool,
+        challenge_id: i32,
+        waypoint_sequence: i32,
+    ) -> Result<Vec<ChallengeParticipant>, ChallengeError> {
+        let participants = sqlx::query_as!(
+            ChallengeParticipant,
+            r#"
+            SELECT participant_id as "participant_id!", challenge_id as "challenge_id!", user_id as "user_id!", participant_nickname,
+                   current_waypoint_id,
+                   COALESCE(current_state, 'PRESENTED') as "current_state!: WaypointState",
+                   COALESCE(joined_at, NOW()) as "joined_at!",
+                   COALESCE(last_updated, NOW()) as "last_updated!",
+                   started_at, deadline_at,
+                   participant_status as "participant_status: ParticipantStatus",
+                   status_reason, status_changed_at, completed_waypoints,
+                   checked_in_bonus_waypoints, verified_bonus_waypoints, last_scored_at
+            FROM challenge_participants
+            WHERE challenge_id = $1 AND current_waypoint_id = $2
+              AND participant_status = 'ACTIVE'
+            "#,
+            challenge_id,
+            waypoint_sequence
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(participants)
+    }
+
+    /// Present the given waypoint to every participant of a challenge
+    pub async fn present_waypoint_to_all(
+        pool: &PgPool,
+        challenge_id: i32,
+        waypoint_sequence: i32,
+    ) -> Result<(), ChallengeError> {
+        sqlx::query!(
+            r#"
+            UPDATE challenge_participants
+            SET current_waypoint_id = $1, current_state = $2, last_updated = NOW()
+            WHERE challenge_id = $3 AND participant_status = 'ACTIVE'
+            "#,
+            waypoint_sequence,
+            WaypointState::Presented as WaypointState,
+            challenge_id
+        )
+        .execute(pool)
         .await?;
 
-        Ok(participants)
+        Ok(())
     }
 }
 
@@ -322,6 +1807,37 @@ impl TemporalChallenge {
     ) -> Result<TemporalChallenge, ChallengeError> {
         // Validate waypoint sequences
         Self::validate_waypoint_sequences(&request.waypoints)?;
+        Self::validate_availability(&request)?;
+        if request.appeals_per_waypoint < 0 {
+            return Err(ChallengeError::ValidationFailed(
+                "appeals_per_waypoint must not be negative".to_string(),
+            ));
+        }
+        if request.waypoint_points < 0 {
+            return Err(ChallengeError::ValidationFailed(
+                "waypoint_points must not be negative".to_string(),
+            ));
+        }
+        let sequences: Vec<i32> = request
+            .waypoints
+            .iter()
+            .filter(|w| w.bonus.is_none())
+            .map(|w| w.waypoint_sequence)
+            .collect();
+        if sequences.is_empty() {
+            return Err(ChallengeError::ValidationFailed(
+                "Challenge must have at least one waypoint on its route".to_string(),
+            ));
+        }
+        request.route.validate(&sequences)?;
+        for waypoint in &request.waypoints {
+            waypoint
+                .proof
+                .validate(waypoint.waypoint_sequence, &waypoint.image_subject)?;
+            if let Some(bonus) = &waypoint.bonus {
+                bonus.validate(waypoint.waypoint_sequence)?;
+            }
+        }
 
         let mut tx = pool.begin().await?;
 
@@ -344,6 +1860,8 @@ impl TemporalChallenge {
                 hints: wp.hints,
                 waypoint_time_minutes: wp.waypoint_time_minutes,
                 image_subject: wp.image_subject,
+                proof: wp.proof,
+                bonus: wp.bonus,
                 created_at: Some(Utc::now()),
             })
             .collect();
@@ -353,6 +1871,7 @@ impl TemporalChallenge {
             challenge_id,
             challenge_description: request.challenge_description,
             challenge_moderator: moderator_id,
+            co_moderators: Vec::new(),
             actual_start_time: None,
             duration_minutes: request.duration_minutes,
             challenge_type: request.challenge_type,
@@ -364,6 +1883,16 @@ impl TemporalChallenge {
                 migrated_from_relational: None,
                 version_notes: Some("Initial version".to_string()),
             },
+            pacing: request.pacing,
+            available_from: request.available_from,
+            available_until: request.available_until,
+            auto_start: request.auto_start,
+            pauses: Vec::new(),
+            route: request.route,
+            missing_exif: request.missing_exif,
+            duplicate_photos: request.duplicate_photos,
+            appeals_per_waypoint: request.appeals_per_waypoint,
+            waypoint_points: request.waypoint_points,
         };
 
         // Convert to JSON
@@ -496,15 +2025,61 @@ impl TemporalChallenge {
         Ok(new_version)
     }
 
+    /// Synchronized, active, auto-start challenges whose planned start has passed but which
+    /// have not been started yet, overdue ones included so starts missed while no server was
+    /// running are caught up. Challenges whose planned end has passed as well are left alone.
+    pub async fn get_due_for_auto_start(
+        pool: &PgPool,
+    ) -> Result<Vec<TemporalChallenge>, ChallengeError> {
+        let challenges = sqlx::query_as!(
+            TemporalChallenge,
+            r#"
+            SELECT challenge_id as "challenge_id!", challenge_version_id as "challenge_version_id!",
+                   challenge_name as "challenge_name!", planned_start_time as "planned_start_time!",
+                   challenge as "challenge!", start_at as "start_at!", end_at,
+                   created_at as "created_at!", updated_at as "updated_at!"
+            FROM temporal_challenges
+            WHERE end_at IS NULL
+              AND planned_start_time <= NOW()
+              -- Too late to catch up on once the planned end has passed
+              AND planned_start_time
+                  + make_interval(mins => COALESCE((challenge->>'duration_minutes')::int, 0)) > NOW()
+              AND (challenge->>'auto_start')::boolean IS TRUE
+              AND (challenge->>'active')::boolean IS TRUE
+              AND jsonb_typeof(challenge->'actual_start_time') IS DISTINCT FROM 'string'
+              AND COALESCE(challenge->>'pacing', 'SYNCHRONIZED') = 'SYNCHRONIZED'
+            ORDER BY planned_start_time
+            "#
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(challenges)
+    }
+
+    /// Start the challenge for everyone. Starts wait for the challenge's advisory lock, so a
+    /// moderator and the auto-start scheduler, possibly on different replicas, can never both
+    /// start the same challenge; whoever comes second reports `ChallengeAlreadyStarted`
     pub async fn start_challenge(
         &self,
         pool: &PgPool,
         moderator_id: i32,
+    ) -> Result<TemporalChallenge, ChallengeError> {
+        let (lock, current) = self.lock_current(pool).await?;
+        let started = current.start_locked(pool, moderator_id).await;
+        lock.rollback().await?;
+        started
+    }
+
+    async fn start_locked(
+        &self,
+        pool: &PgPool,
+        moderator_id: i32,
     ) -> Result<TemporalChallenge, ChallengeError> {
         let mut challenge_data = self.get_challenge_data()?;
 
-        // Check if user is moderator
-        if challenge_data.challenge_moderator != moderator_id {
+        // Check if user is one of the moderators
+        if !challenge_data.is_moderator(moderator_id) {
             return Err(ChallengeError::NotModerator);
         }
 
@@ -513,6 +2088,11 @@ impl TemporalChallenge {
             return Err(ChallengeError::ChallengeNotActive);
         }
 
+        // Self-paced participants start their own clocks
+        if challenge_data.pacing == ChallengePacing::SelfPaced {
+            return Err(ChallengeError::SelfPaced);
+        }
+
         // Check if already started
         if challenge_data.actual_start_time.is_some() {
             return Err(ChallengeError::ChallengeAlreadyStarted);
@@ -521,8 +2101,45 @@ impl TemporalChallenge {
         // Update challenge with actual start time
         challenge_data.actual_start_time = Some(Utc::now());
 
-        self.create_new_version(pool, challenge_data, Some("Challenge started".to_string()))
-            .await
+        let first_waypoint = challenge_data.starting_waypoint();
+
+        let started = self
+            .create_new_version(pool, challenge_data, Some("Challenge started".to_string()))
+            .await?;
+
+        // Every participant starts with the first clue presented, unless the route lets them
+        // pick their own first waypoint
+        if let Some(waypoint_sequence) = first_waypoint {
+            ChallengeParticipant::present_waypoint_to_all(
+                pool,
+                self.challenge_id,
+                waypoint_sequence,
+            )
+            .await?;
+        }
+
+        Ok(started)
+    }
+
+    /// A copy for participants, with every waypoint's proof in the challenge JSON redacted
+    pub fn redacted(&self) -> Result<TemporalChallenge, ChallengeError> {
+        let waypoints = self.get_waypoints()?;
+
+        let mut redacted = self.clone();
+        if let Some(stored) = redacted
+            .challenge
+            .get_mut("waypoints")
+            .and_then(|w| w.as_array_mut())
+        {
+            for (stored, waypoint) in stored.iter_mut().zip(&waypoints) {
+                stored["proof"] =
+                    serde_json::to_value(ProofView::from(&waypoint.proof)).map_err(|e| {
+                        ChallengeError::ValidationFailed(format!("JSON serialization failed: {e}"))
+                    })?;
+            }
+        }
+
+        Ok(redacted)
     }
 
     pub fn get_waypoints(&self) -> Result<Vec<WaypointData>, ChallengeError> {
@@ -537,20 +2154,293 @@ impl TemporalChallenge {
 
     pub fn is_ended(&self) -> Result<bool, ChallengeError> {
         let challenge_data = self.get_challenge_data()?;
-        if let Some(start_time) = challenge_data.actual_start_time {
-            let end_time =
-                start_time + chrono::Duration::minutes(challenge_data.duration_minutes as i64);
-            Ok(Utc::now() > end_time)
-        } else {
-            Ok(false)
-        }
+        Ok(challenge_data.is_ended_at(Utc::now()))
     }
 
+    /// Effective end time, pushed back by every pause
     pub fn get_end_time(&self) -> Result<Option<DateTime<Utc>>, ChallengeError> {
         let challenge_data = self.get_challenge_data()?;
-        Ok(challenge_data
-            .actual_start_time
-            .map(|start| start + chrono::Duration::minutes(challenge_data.duration_minutes as i64)))
+        Ok(challenge_data.end_time_at(Utc::now()))
+    }
+
+    /// Wait for the challenge's advisory lock and re-read the current version under it, the
+    /// version this was loaded from may be stale by now. The lock is held until the returned
+    /// transaction ends
+    async fn lock_current(
+        &self,
+        pool: &PgPool,
+    ) -> Result<(Transaction<'static, Postgres>, TemporalChallenge), ChallengeError> {
+        let mut lock = pool.begin().await?;
+        sqlx::query("SELECT pg_advisory_xact_lock($1, $2)")
+            .bind(CHALLENGE_VERSION_LOCK_CLASS)
+            .bind(self.challenge_id)
+            .execute(&mut *lock)
+            .await?;
+
+        let current = Self::get_current_by_id(pool, self.challenge_id).await?;
+        Ok((lock, current))
+    }
+
+    /// Freeze the challenge clock for everyone
+    pub async fn pause(
+        &self,
+        pool: &PgPool,
+        reason: Option<String>,
+    ) -> Result<TemporalChallenge, ChallengeError> {
+        let (lock, current) = self.lock_current(pool).await?;
+        let paused = current.pause_locked(pool, reason).await;
+        lock.rollback().await?;
+        paused
+    }
+
+    async fn pause_locked(
+        &self,
+        pool: &PgPool,
+        reason: Option<String>,
+    ) -> Result<TemporalChallenge, ChallengeError> {
+        let mut challenge_data = self.get_challenge_data()?;
+        let now = Utc::now();
+
+        if !challenge_data.active {
+            return Err(ChallengeError::ChallengeNotActive);
+        }
+        if challenge_data.is_paused() {
+            return Err(ChallengeError::ChallengePaused);
+        }
+        if challenge_data.pacing == ChallengePacing::Synchronized {
+            if challenge_data.actual_start_time.is_none() {
+                return Err(ChallengeError::ChallengeNotStarted);
+            }
+            if challenge_data.is_ended_at(now) {
+                return Err(ChallengeError::ChallengeEnded);
+            }
+        }
+
+        challenge_data.pauses.push(ChallengePause {
+            paused_at: now,
+            resumed_at: None,
+            reason,
+        });
+
+        self.create_new_version(pool, challenge_data, Some("Challenge paused".to_string()))
+            .await
+    }
+
+    /// Restart the challenge clock, everyone gets the paused time back
+    pub async fn resume(&self, pool: &PgPool) -> Result<TemporalChallenge, ChallengeError> {
+        let (lock, current) = self.lock_current(pool).await?;
+        let resumed = current.resume_locked(pool).await;
+        lock.rollback().await?;
+        resumed
+    }
+
+    async fn resume_locked(&self, pool: &PgPool) -> Result<TemporalChallenge, ChallengeError> {
+        let mut challenge_data = self.get_challenge_data()?;
+        let now = Utc::now();
+
+        let pause = match challenge_data.pauses.last_mut() {
+            Some(pause) if pause.resumed_at.is_none() => pause,
+            _ => return Err(ChallengeError::ChallengeNotPaused),
+        };
+        pause.resumed_at = Some(now);
+        let paused_at = pause.paused_at;
+        let pacing = challenge_data.pacing;
+
+        let resumed = self
+            .create_new_version(pool, challenge_data, Some("Challenge resumed".to_string()))
+            .await?;
+
+        // Self-paced participants keep their own deadlines, move those that were running
+        if pacing == ChallengePacing::SelfPaced {
+            ChallengeParticipant::extend_deadlines(
+                pool,
+                self.challenge_id,
+                paused_at,
+                now - paused_at,
+            )
+            .await?;
+        }
+
+        Ok(resumed)
+    }
+
+    /// Add time to a challenge that has not ended yet
+    pub async fn extend(
+        &self,
+        pool: &PgPool,
+        minutes: i32,
+    ) -> Result<TemporalChallenge, ChallengeError> {
+        let (lock, current) = self.lock_current(pool).await?;
+        let extended = current.extend_locked(pool, minutes).await;
+        lock.rollback().await?;
+        extended
+    }
+
+    async fn extend_locked(
+        &self,
+        pool: &PgPool,
+        minutes: i32,
+    ) -> Result<TemporalChallenge, ChallengeError> {
+        let mut challenge_data = self.get_challenge_data()?;
+        let now = Utc::now();
+
+        if !(1..=MAX_EXTENSION_MINUTES).contains(&minutes) {
+            return Err(ChallengeError::ValidationFailed(format!(
+                "Extension must be between 1 and {MAX_EXTENSION_MINUTES} minutes"
+            )));
+        }
+        if !challenge_data.active {
+            return Err(ChallengeError::ChallengeNotActive);
+        }
+        if challenge_data.is_ended_at(now) {
+            return Err(ChallengeError::ChallengeEnded);
+        }
+
+        challenge_data.duration_minutes += minutes;
+        let pacing = challenge_data.pacing;
+        // Deadlines that ran out during a pause in progress have not passed yet
+        let playing_at = challenge_data.deadlines_frozen_at(now);
+
+        let extended = self
+            .create_new_version(
+                pool,
+                challenge_data,
+                Some(format!("Challenge extended by {minutes} minutes")),
+            )
+            .await?;
+
+        if pacing == ChallengePacing::SelfPaced {
+            ChallengeParticipant::extend_deadlines(
+                pool,
+                self.challenge_id,
+                playing_at,
+                chrono::Duration::minutes(minutes as i64),
+            )
+            .await?;
+        }
+
+        Ok(extended)
+    }
+
+    /// Let another user moderate the challenge alongside the owner
+    pub async fn add_co_moderator(
+        &self,
+        pool: &PgPool,
+        user_id: i32,
+    ) -> Result<TemporalChallenge, ChallengeError> {
+        let (lock, current) = self.lock_current(pool).await?;
+        let added = current.add_co_moderator_locked(pool, user_id).await;
+        lock.rollback().await?;
+        added
+    }
+
+    async fn add_co_moderator_locked(
+        &self,
+        pool: &PgPool,
+        user_id: i32,
+    ) -> Result<TemporalChallenge, ChallengeError> {
+        let mut challenge_data = self.get_challenge_data()?;
+        if challenge_data.is_moderator(user_id) {
+            return Err(ChallengeError::AlreadyModerator);
+        }
+        // They could override their own waypoints otherwise
+        if ChallengeParticipant::get_accepted_for_user(pool, self.challenge_id, user_id)
+            .await?
+            .is_some()
+        {
+            return Err(ChallengeError::AlreadyParticipant);
+        }
+
+        challenge_data.co_moderators.push(user_id);
+
+        self.create_new_version(
+            pool,
+            challenge_data,
+            Some(format!("Co-moderator {user_id} added")),
+        )
+        .await
+    }
+
+    /// Remove a co-moderator, the owner cannot be removed
+    pub async fn remove_co_moderator(
+        &self,
+        pool: &PgPool,
+        user_id: i32,
+    ) -> Result<TemporalChallenge, ChallengeError> {
+        let (lock, current) = self.lock_current(pool).await?;
+        let removed = current.remove_co_moderator_locked(pool, user_id).await;
+        lock.rollback().await?;
+        removed
+    }
+
+    async fn remove_co_moderator_locked(
+        &self,
+        pool: &PgPool,
+        user_id: i32,
+    ) -> Result<TemporalChallenge, ChallengeError> {
+        let mut challenge_data = self.get_challenge_data()?;
+        if challenge_data.moderator_role(user_id) != Some(ModeratorRole::CoModerator) {
+            return Err(ChallengeError::NotModerator);
+        }
+
+        challenge_data.co_moderators.retain(|&id| id != user_id);
+
+        self.create_new_version(
+            pool,
+            challenge_data,
+            Some(format!("Co-moderator {user_id} removed")),
+        )
+        .await
+    }
+
+    pub fn timing_response(&self) -> Result<ChallengeTimingResponse, ChallengeError> {
+        let challenge_data = self.get_challenge_data()?;
+        Ok(ChallengeTimingResponse {
+            challenge_id: self.challenge_id,
+            paused: challenge_data.is_paused(),
+            duration: challenge_data.duration_minutes,
+            actual_start_time: challenge_data.actual_start_time,
+            end_time: challenge_data.end_time_at(Utc::now()),
+            pauses: challenge_data.pauses,
+        })
+    }
+
+    fn validate_availability(request: &CreateChallengeRequest) -> Result<(), ChallengeError> {
+        match request.pacing {
+            ChallengePacing::Synchronized => {
+                if request.available_from.is_some() || request.available_until.is_some() {
+                    return Err(ChallengeError::ValidationFailed(
+                        "Availability window only applies to self-paced challenges".to_string(),
+                    ));
+                }
+            }
+            ChallengePacing::SelfPaced => {
+                if request.auto_start {
+                    return Err(ChallengeError::ValidationFailed(
+                        "Auto-start only applies to synchronized challenges".to_string(),
+                    ));
+                }
+                let from = request.available_from.unwrap_or(request.planned_start_time);
+                if matches!(request.available_until, Some(until) if until <= from) {
+                    return Err(ChallengeError::ValidationFailed(
+                        "Availability window must end after it opens".to_string(),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether a self-paced participant may begin at `at`
+    pub fn is_available_at(&self, challenge_data: &ChallengeData, at: DateTime<Utc>) -> bool {
+        let from = challenge_data
+            .available_from
+            .unwrap_or(self.planned_start_time);
+        at >= from
+            && challenge_data
+                .available_until
+                .is_none_or(|until| at < until)
     }
 
     fn validate_waypoint_sequences(
@@ -627,6 +2517,8 @@ mod tests {
                 hints: vec![],
                 waypoint_time_minutes: None,
                 image_subject: "Test".to_string(),
+                proof: ProofKind::Image,
+                bonus: None,
             },
             CreateWaypointRequest {
                 waypoint_sequence: 2,
@@ -636,6 +2528,8 @@ mod tests {
                 hints: vec![],
                 waypoint_time_minutes: None,
                 image_subject: "Test".to_string(),
+                proof: ProofKind::Image,
+                bonus: None,
             },
         ];
         assert!(TemporalChallenge::validate_waypoint_sequences(&valid_waypoints).is_ok());
@@ -650,6 +2544,8 @@ mod tests {
                 hints: vec![],
                 waypoint_time_minutes: None,
                 image_subject: "Test".to_string(),
+                proof: ProofKind::Image,
+                bonus: None,
             },
             CreateWaypointRequest {
                 waypoint_sequence: 3, // Should be 2
@@ -659,6 +2555,8 @@ mod tests {
                 hints: vec![],
                 waypoint_time_minutes: None,
                 image_subject: "Test".to_string(),
+                proof: ProofKind::Image,
+                bonus: None,
             },
         ];
         assert!(TemporalChallenge::validate_waypoint_sequences(&invalid_waypoints).is_err());
@@ -683,6 +2581,8 @@ mod tests {
             hints: vec!["Hint 1".to_string()],
             waypoint_time_minutes: Some(15),
             image_subject: "Test subject".to_string(),
+            proof: ProofKind::Image,
+            bonus: None,
             created_at: Some(Utc::now()),
         };
 
@@ -690,6 +2590,7 @@ mod tests {
             challenge_id: 1,
             challenge_description: Some("Test description".to_string()),
             challenge_moderator: 1,
+            co_moderators: vec![],
             actual_start_time: None,
             duration_minutes: 120,
             challenge_type: ChallengeType::Com,
@@ -701,6 +2602,16 @@ mod tests {
                 migrated_from_relational: None,
                 version_notes: Some("Test version".to_string()),
             },
+            pacing: ChallengePacing::Synchronized,
+            available_from: None,
+            available_until: None,
+            auto_start: false,
+            pauses: Vec::new(),
+            route: RouteTopology::Linear,
+            missing_exif: MissingExifPolicy::Allow,
+            duplicate_photos: DuplicatePhotoPolicy::Review,
+            appeals_per_waypoint: 1,
+            waypoint_points: 10,
         };
 
         // Test serialization and deserialization
@@ -751,6 +2662,8 @@ mod tests {
             hints: vec!["Look for red".to_string(), "Used for posting".to_string()],
             waypoint_time_minutes: Some(15),
             image_subject: "Red post box".to_string(),
+            proof: ProofKind::Image,
+            bonus: None,
             created_at: Some(Utc::now()),
         };
 
@@ -762,4 +2675,630 @@ mod tests {
         assert_eq!(waypoint.location.lat, deserialized.location.lat);
         assert_eq!(waypoint.hints.len(), deserialized.hints.len());
     }
+
+    #[test]
+    fn test_self_paced_availability_validation() {
+        let json = r#"{
+            "challenge_name": "Evening Walk",
+            "challenge_description": null,
+            "planned_start_time": "2025-01-01T10:00:00Z",
+            "duration_minutes": 45,
+            "challenge_type": "REC",
+            "waypoints": [],
+            "pacing": "SELF_PACED",
+            "available_until": "2025-01-08T10:00:00Z"
+        }"#;
+
+        let mut request: CreateChallengeRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(request.pacing, ChallengePacing::SelfPaced);
+        assert!(TemporalChallenge::validate_availability(&request).is_ok());
+
+        // Window closing before it opens
+        request.available_until = Some(request.planned_start_time);
+        assert!(TemporalChallenge::validate_availability(&request).is_err());
+        request.available_until = None;
+
+        // Self-paced challenges have no shared start to schedule
+        request.auto_start = true;
+        assert!(TemporalChallenge::validate_availability(&request).is_err());
+        request.auto_start = false;
+
+        // Windows are meaningless for synchronized challenges
+        request.pacing = ChallengePacing::Synchronized;
+        request.available_from = Some(request.planned_start_time);
+        assert!(TemporalChallenge::validate_availability(&request).is_err());
+    }
+
+    #[test]
+    fn test_challenge_data_defaults_to_synchronized() {
+        let json = serde_json::json!({
+            "challenge_id": 1,
+            "challenge_description": null,
+            "challenge_moderator": 1,
+            "actual_start_time": null,
+            "duration_minutes": 60,
+            "challenge_type": "COM",
+            "active": true,
+            "waypoints": [],
+            "metadata": {
+                "created_at": "2025-01-01T10:00:00Z",
+                "updated_at": "2025-01-01T10:00:00Z",
+                "migrated_from_relational": null,
+                "version_notes": null
+            }
+        });
+
+        let data: ChallengeData = serde_json::from_value(json).unwrap();
+        assert_eq!(data.pacing, ChallengePacing::Synchronized);
+        assert!(data.available_from.is_none());
+        assert!(data.co_moderators.is_empty());
+        assert_eq!(data.appeals_per_waypoint, 1);
+    }
+
+    #[test]
+    fn test_moderator_roles() {
+        let mut data: ChallengeData = serde_json::from_value(serde_json::json!({
+            "challenge_id": 1,
+            "challenge_description": null,
+            "challenge_moderator": 1,
+            "co_moderators": [2],
+            "actual_start_time": null,
+            "duration_minutes": 60,
+            "challenge_type": "COM",
+            "active": true,
+            "waypoints": [],
+            "metadata": {
+                "created_at": "2025-01-01T10:00:00Z",
+                "updated_at": "2025-01-01T10:00:00Z",
+                "migrated_from_relational": null,
+                "version_notes": null
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(data.moderator_role(1), Some(ModeratorRole::Owner));
+        assert_eq!(data.moderator_role(2), Some(ModeratorRole::CoModerator));
+        assert!(!data.is_moderator(3));
+
+        data.co_moderators.clear();
+        assert!(!data.is_moderator(2));
+        assert_eq!(
+            serde_json::to_string(&ModeratorRole::CoModerator).unwrap(),
+            "\"CO_MODERATOR\""
+        );
+    }
+
+    #[test]
+    fn test_pauses_push_back_end_time() {
+        let start = DateTime::parse_from_rfc3339("2025-01-01T10:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let minutes = |m: i64| chrono::Duration::minutes(m);
+
+        let mut data: ChallengeData = serde_json::from_value(serde_json::json!({
+            "challenge_id": 1,
+            "challenge_description": null,
+            "challenge_moderator": 1,
+            "actual_start_time": start,
+            "duration_minutes": 60,
+            "challenge_type": "COM",
+            "active": true,
+            "waypoints": [],
+            "metadata": {
+                "created_at": start,
+                "updated_at": start,
+                "migrated_from_relational": null,
+                "version_notes": null
+            }
+        }))
+        .unwrap();
+        assert_eq!(data.end_time_at(start), Some(start + minutes(60)));
+
+        // A completed 15 minute pause
+        data.pauses.push(ChallengePause {
+            paused_at: start + minutes(10),
+            resumed_at: Some(start + minutes(25)),
+            reason: Some("Rain".to_string()),
+        });
+        // An ongoing pause keeps moving the end while it lasts
+        data.pauses.push(ChallengePause {
+            paused_at: start + minutes(40),
+            resumed_at: None,
+            reason: None,
+        });
+
+        assert!(data.is_paused());
+        assert_eq!(
+            data.end_time_at(start + minutes(50)),
+            Some(start + minutes(85))
+        );
+        assert!(!data.is_ended_at(start + minutes(200)));
+
+        // Self-paced deadlines already moved for completed pauses, only the ongoing one counts
+        assert_eq!(
+            data.participant_deadline_at(start + minutes(45), start + minutes(50)),
+            start + minutes(55)
+        );
+        assert_eq!(
+            data.participant_deadline_at(start + minutes(30), start + minutes(50)),
+            start + minutes(30)
+        );
+        // Extensions reach everyone still playing when the pause began
+        assert_eq!(
+            data.deadlines_frozen_at(start + minutes(50)),
+            start + minutes(40)
+        );
+        data.pauses.last_mut().unwrap().resumed_at = Some(start + minutes(60));
+        assert_eq!(
+            data.deadlines_frozen_at(start + minutes(70)),
+            start + minutes(70)
+        );
+    }
+
+    #[test]
+    fn test_participant_status_changes() {
+        use ParticipantStatus::*;
+
+        assert!(Active.can_change_to(Forfeited));
+        assert!(Active.can_change_to(Disqualified));
+        assert!(Forfeited.can_change_to(Disqualified));
+        assert!(Disqualified.can_change_to(Active));
+
+        assert!(!Active.can_change_to(Active));
+        assert!(!Disqualified.can_change_to(Forfeited));
+        assert!(!Disqualified.can_change_to(Disqualified));
+    }
+
+    #[test]
+    fn test_waypoint_override_applicability() {
+        use WaypointOverrideAction::*;
+
+        let progress =
+            |current: Option<i32>, completed: Vec<i32>, available: Vec<i32>| RouteProgress {
+                current_waypoint_id: current,
+                state: WaypointState::CheckedIn,
+                completed_waypoints: completed,
+                available_waypoints: available,
+                finished: false,
+                bonus_waypoints: Vec::new(),
+                bonus_points: 0,
+                score: 0,
+            };
+
+        // Participant working on waypoint 2
+        let working = progress(Some(2), vec![1], vec![2]);
+        assert!(Verify.applies_to(&working, 2));
+        assert!(!Verify.applies_to(&working, 1));
+        assert!(Reject.applies_to(&working, 1));
+        assert!(Reset.applies_to(&working, 2));
+        assert!(!Reset.applies_to(&working, 3));
+
+        // Finished the last waypoint
+        assert!(!Verify.applies_to(&progress(Some(3), vec![1, 2, 3], vec![]), 3));
+
+        // Not started yet
+        assert!(!Reset.applies_to(&progress(None, vec![], vec![1]), 1));
+    }
+
+    fn route_challenge(route: RouteTopology) -> ChallengeData {
+        let waypoints: Vec<serde_json::Value> = (1..=4)
+            .map(|sequence| {
+                serde_json::json!({
+                    "waypoint_id": null,
+                    "waypoint_sequence": sequence,
+                    "location": {"lat": 51.5, "long": -0.12},
+                    "radius_meters": 50.0,
+                    "waypoint_clue": "Clue",
+                    "hints": [],
+                    "waypoint_time_minutes": null,
+                    "image_subject": "Subject",
+                    "created_at": null
+                })
+            })
+            .collect();
+
+        serde_json::from_value(serde_json::json!({
+            "challenge_id": 1,
+            "challenge_description": null,
+            "challenge_moderator": 1,
+            "actual_start_time": null,
+            "duration_minutes": 60,
+            "challenge_type": "COM",
+            "active": true,
+            "waypoints": waypoints,
+            "metadata": {
+                "created_at": "2025-01-01T10:00:00Z",
+                "updated_at": "2025-01-01T10:00:00Z",
+                "migrated_from_relational": null,
+                "version_notes": null
+            },
+            "route": route
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_proof_kind_validation() {
+        assert!(ProofKind::Image.validate(1, "Red post box").is_ok());
+        assert!(ProofKind::Image.validate(1, " ").is_err());
+        assert!(ProofKind::LocationOnly.validate(1, "").is_ok());
+        assert!(ProofKind::QrCode {
+            secret: String::new()
+        }
+        .validate(1, "")
+        .is_err());
+        assert!(ProofKind::TextAnswer {
+            accepted_answers: vec!["".to_string()],
+            max_typos: None,
+        }
+        .validate(1, "")
+        .is_err());
+
+        let options = vec!["Red".to_string(), "Blue".to_string()];
+        let choice = |correct| ProofKind::MultipleChoice {
+            options: options.clone(),
+            correct,
+        };
+        assert!(choice(vec![1]).validate(1, "").is_ok());
+        assert!(choice(vec![2]).validate(1, "").is_err());
+        assert!(choice(vec![]).validate(1, "").is_err());
+
+        // Waypoints stored before proof kinds existed are image proofs
+        let waypoint: CreateWaypointRequest = serde_json::from_value(serde_json::json!({
+            "waypoint_sequence": 1,
+            "location": { "lat": 1.0, "long": 1.0 },
+            "radius_meters": 50.0,
+            "waypoint_clue": "Clue",
+            "hints": [],
+            "waypoint_time_minutes": null,
+            "image_subject": "Subject"
+        }))
+        .unwrap();
+        assert_eq!(waypoint.proof, ProofKind::Image);
+
+        let proof: ProofKind = serde_json::from_value(serde_json::json!({
+            "kind": "TEXT_ANSWER",
+            "accepted_answers": ["Big Ben"]
+        }))
+        .unwrap();
+        assert_eq!(proof.name(), "TEXT_ANSWER");
+        assert_eq!(proof.prompt(""), "Answer the riddle");
+    }
+
+    #[test]
+    fn test_route_topology_validation() {
+        let sequences = [1, 2, 3, 4];
+        let branching = |edges: &[(i32, &[i32])]| RouteTopology::Branching {
+            branches: edges
+                .iter()
+                .map(|(from, to)| RouteBranch {
+                    from: *from,
+                    to: to.to_vec(),
+                })
+                .collect(),
+        };
+
+        assert!(RouteTopology::Linear.validate(&sequences).is_ok());
+        assert!(RouteTopology::Unordered { required: 3 }
+            .validate(&sequences)
+            .is_ok());
+        assert!(RouteTopology::Unordered { required: 5 }
+            .validate(&sequences)
+            .is_err());
+        assert!(RouteTopology::Unordered { required: 0 }
+            .validate(&sequences)
+            .is_err());
+
+        // 1 branches to 2 or 3, both lead to 4
+        assert!(branching(&[(1, &[2, 3]), (2, &[4]), (3, &[4])])
+            .validate(&sequences)
+            .is_ok());
+        // 4 unreachable
+        assert!(branching(&[(1, &[2, 3])]).validate(&sequences).is_err());
+        // 4 leads back to 2
+        assert!(branching(&[(1, &[2, 3]), (2, &[4]), (3, &[4]), (4, &[2])])
+            .validate(&sequences)
+            .is_err());
+        // Unknown waypoint
+        assert!(branching(&[(1, &[2, 3, 9]), (2, &[4])])
+            .validate(&sequences)
+            .is_err());
+        // 1 listed twice
+        assert!(branching(&[(1, &[2, 3]), (1, &[4])])
+            .validate(&sequences)
+            .is_err());
+
+        let json = serde_json::to_value(RouteTopology::Unordered { required: 2 }).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"kind": "UNORDERED", "required": 2})
+        );
+    }
+
+    #[test]
+    fn test_bonus_waypoints() {
+        use chrono::TimeZone;
+
+        let at = |hour: u32| Utc.with_ymd_and_hms(2025, 1, 1, hour, 0, 0).unwrap();
+        let mut challenge = route_challenge(RouteTopology::Linear);
+        challenge.actual_start_time = Some(at(10));
+        challenge.duration_minutes = 240;
+        challenge.waypoints[1].bonus = Some(BonusWaypoint {
+            points: 5,
+            available_from: None,
+            available_until: Some(at(12)),
+        });
+        challenge.waypoints[3].bonus = Some(BonusWaypoint {
+            points: 3,
+            available_from: Some(at(11)),
+            available_until: None,
+        });
+
+        // Bonus waypoints 2 and 4 are off the route
+        assert_eq!(challenge.starting_waypoint(), Some(1));
+        assert_eq!(challenge.next_waypoints(&[1]), vec![3]);
+        assert!(challenge.is_route_finished(&[1, 3]));
+        assert_eq!(challenge.bonus_points(&[2, 4]), 8);
+        assert!(challenge.waypoints[1]
+            .bonus
+            .as_ref()
+            .unwrap()
+            .validate(2)
+            .is_ok());
+        assert!(BonusWaypoint {
+            points: 0,
+            available_from: None,
+            available_until: None
+        }
+        .validate(2)
+        .is_err());
+
+        let mut participant = ChallengeParticipant {
+            participant_id: Uuid::new_v4(),
+            challenge_id: 1,
+            user_id: 1,
+            participant_nickname: None,
+            current_waypoint_id: Some(1),
+            current_state: WaypointState::CheckedIn,
+            joined_at: at(9),
+            last_updated: at(10),
+            started_at: None,
+            deadline_at: None,
+            participant_status: ParticipantStatus::Active,
+            status_reason: None,
+            status_changed_at: None,
+            completed_waypoints: vec![],
+            checked_in_bonus_waypoints: vec![],
+            verified_bonus_waypoints: vec![2],
+            last_scored_at: None,
+        };
+
+        // Checked in on the route, bonus waypoints stay open within their window
+        assert!(participant.can_check_in(&challenge, 4, at(11)).is_ok());
+        assert!(participant.can_check_in(&challenge, 4, at(10)).is_err());
+        assert!(participant.can_check_in(&challenge, 2, at(11)).is_err());
+        assert!(participant.can_check_in(&challenge, 3, at(11)).is_err());
+        assert!(matches!(
+            participant.can_submit_proof(&challenge, 4, at(11)),
+            Err(ChallengeError::NotCheckedIn)
+        ));
+        participant.checked_in_bonus_waypoints.push(4);
+        assert!(participant.can_submit_proof(&challenge, 4, at(11)).is_ok());
+        assert!(participant.can_submit_proof(&challenge, 1, at(11)).is_ok());
+
+        let progress = participant.route_progress(&challenge);
+        assert_eq!(progress.available_waypoints, vec![1]);
+        assert_eq!(progress.bonus_points, 5);
+        let states: Vec<(i32, WaypointState)> = progress
+            .bonus_waypoints
+            .iter()
+            .map(|bonus| (bonus.waypoint_id, bonus.state))
+            .collect();
+        assert_eq!(
+            states,
+            vec![(2, WaypointState::Verified), (4, WaypointState::CheckedIn)]
+        );
+        assert!(!WaypointOverrideAction::Verify.applies_to(&progress, 2));
+        assert!(WaypointOverrideAction::Reset.applies_to(&progress, 4));
+    }
+
+    /// An active participant who last scored at `last_scored_at`
+    fn route_participant(
+        current_waypoint_id: Option<i32>,
+        current_state: WaypointState,
+        last_scored_at: DateTime<Utc>,
+    ) -> ChallengeParticipant {
+        ChallengeParticipant {
+            participant_id: Uuid::new_v4(),
+            challenge_id: 1,
+            user_id: 1,
+            participant_nickname: None,
+            current_waypoint_id,
+            current_state,
+            joined_at: last_scored_at,
+            last_updated: last_scored_at,
+            started_at: None,
+            deadline_at: None,
+            participant_status: ParticipantStatus::Active,
+            status_reason: None,
+            status_changed_at: None,
+            completed_waypoints: vec![],
+            checked_in_bonus_waypoints: vec![],
+            verified_bonus_waypoints: vec![],
+            last_scored_at: Some(last_scored_at),
+        }
+    }
+
+    #[test]
+    fn test_bonus_availability_window() {
+        use chrono::TimeZone;
+
+        let at =
+            |hour: u32, minute: u32| Utc.with_ymd_and_hms(2025, 1, 1, hour, minute, 0).unwrap();
+        let bonus = BonusWaypoint {
+            points: 5,
+            available_from: Some(at(11, 0)),
+            available_until: Some(at(12, 0)),
+        };
+
+        // Opens at available_from, closes at available_until
+        assert!(!bonus.is_available_at(at(10, 59)));
+        assert!(bonus.is_available_at(at(11, 0)));
+        assert!(bonus.is_available_at(at(11, 59)));
+        assert!(!bonus.is_available_at(at(12, 0)));
+        assert!(BonusWaypoint {
+            points: 5,
+            available_from: None,
+            available_until: None
+        }
+        .is_available_at(at(23, 0)));
+        assert!(BonusWaypoint {
+            points: 5,
+            available_from: Some(at(12, 0)),
+            available_until: Some(at(11, 0))
+        }
+        .validate(2)
+        .is_err());
+
+        let mut challenge = route_challenge(RouteTopology::Linear);
+        challenge.actual_start_time = Some(at(10, 0));
+        challenge.duration_minutes = 240;
+        challenge.waypoints[3].bonus = Some(bonus);
+        let participant = route_participant(Some(1), WaypointState::Presented, at(10, 0));
+
+        assert!(matches!(
+            participant.can_check_in(&challenge, 4, at(10, 30)),
+            Err(ChallengeError::InvalidWaypointSequence)
+        ));
+        assert!(participant.can_check_in(&challenge, 4, at(11, 30)).is_ok());
+        assert!(matches!(
+            participant.can_check_in(&challenge, 4, at(12, 30)),
+            Err(ChallengeError::InvalidWaypointSequence)
+        ));
+    }
+
+    #[test]
+    fn test_bonus_waypoints_out_of_route_order() {
+        use chrono::TimeZone;
+
+        let at = |hour: u32| Utc.with_ymd_and_hms(2025, 1, 1, hour, 0, 0).unwrap();
+        let mut challenge = route_challenge(RouteTopology::Linear);
+        challenge.actual_start_time = Some(at(10));
+        challenge.duration_minutes = 240;
+        for sequence in [3, 4] {
+            challenge.waypoints[sequence - 1].bonus = Some(BonusWaypoint {
+                points: sequence as i32,
+                available_from: None,
+                available_until: None,
+            });
+        }
+
+        // Bonus waypoint 4 before the route even started, then 3 while checked in on the route
+        let mut participant = route_participant(Some(1), WaypointState::Presented, at(10));
+        assert!(participant.can_check_in(&challenge, 4, at(11)).is_ok());
+        participant.verified_bonus_waypoints.push(4);
+        participant.current_state = WaypointState::CheckedIn;
+        assert!(participant.can_check_in(&challenge, 3, at(11)).is_ok());
+        participant.checked_in_bonus_waypoints.push(3);
+
+        // Both are checked in at the same time and the route did not move
+        assert!(participant.is_checked_in_at(&challenge, 1));
+        assert!(participant.is_checked_in_at(&challenge, 3));
+        let progress = participant.route_progress(&challenge);
+        assert_eq!(progress.current_waypoint_id, Some(1));
+        assert_eq!(progress.available_waypoints, vec![1]);
+        assert_eq!(progress.bonus_points, 4);
+        assert_eq!(progress.score, 4);
+
+        // A verified bonus waypoint cannot be done twice
+        assert!(participant.can_check_in(&challenge, 4, at(11)).is_err());
+
+        // The route finishes without the remaining bonus waypoint
+        participant.completed_waypoints = vec![1, 2];
+        participant.current_waypoint_id = Some(2);
+        participant.current_state = WaypointState::Verified;
+        let progress = participant.route_progress(&challenge);
+        assert!(progress.finished);
+        assert_eq!(progress.score, 2 * challenge.waypoint_points + 4);
+    }
+
+    #[test]
+    fn test_challenge_standings() {
+        use chrono::TimeZone;
+
+        let at = |hour: u32| Utc.with_ymd_and_hms(2025, 1, 1, hour, 0, 0).unwrap();
+        let mut challenge = route_challenge(RouteTopology::Linear);
+        challenge.waypoints[3].bonus = Some(BonusWaypoint {
+            points: 10,
+            available_from: None,
+            available_until: None,
+        });
+
+        // Two route waypoints
+        let mut leader = route_participant(Some(3), WaypointState::Presented, at(12));
+        leader.completed_waypoints = vec![1, 2];
+        // One route waypoint and the bonus: as many points, but earlier
+        let mut bonus_hunter = route_participant(Some(2), WaypointState::Presented, at(11));
+        bonus_hunter.completed_waypoints = vec![1];
+        bonus_hunter.verified_bonus_waypoints = vec![4];
+        // The same, at the same time, checking in somewhere after does not count
+        let mut tied = bonus_hunter.clone();
+        tied.participant_id = Uuid::new_v4();
+        tied.last_updated = at(13);
+        let starter = route_participant(Some(1), WaypointState::Presented, at(10));
+        // Ahead of everyone, but out of the challenge
+        let mut disqualified = leader.clone();
+        disqualified.participant_id = Uuid::new_v4();
+        disqualified.completed_waypoints = vec![1, 2, 3];
+        disqualified.participant_status = ParticipantStatus::Disqualified;
+
+        let standings = challenge.standings(&[
+            starter.clone(),
+            leader.clone(),
+            bonus_hunter.clone(),
+            tied,
+            disqualified,
+        ]);
+        let ranks: Vec<(usize, i32, i32)> = standings
+            .iter()
+            .map(|standing| (standing.rank, standing.score, standing.bonus_points))
+            .collect();
+        assert_eq!(ranks, vec![(1, 20, 10), (1, 20, 10), (3, 20, 0), (4, 0, 0)]);
+        assert_eq!(standings[2].participant_id, leader.participant_id);
+        assert_eq!(standings[2].completed_waypoints, 2);
+        assert_eq!(standings[3].participant_id, starter.participant_id);
+    }
+
+    #[test]
+    fn test_route_navigation() {
+        let linear = route_challenge(RouteTopology::Linear);
+        assert_eq!(linear.starting_waypoint(), Some(1));
+        assert_eq!(linear.next_waypoints(&[1, 2]), vec![3]);
+        assert!(linear.is_route_finished(&[1, 2, 3, 4]));
+
+        let unordered = route_challenge(RouteTopology::Unordered { required: 2 });
+        assert_eq!(unordered.starting_waypoint(), None);
+        assert_eq!(unordered.next_waypoints(&[3]), vec![1, 2, 4]);
+        assert!(unordered.is_route_finished(&[3, 1]));
+
+        let branch = |from: i32, to: &[i32]| RouteBranch {
+            from,
+            to: to.to_vec(),
+        };
+        let branching = route_challenge(RouteTopology::Branching {
+            branches: vec![branch(1, &[2, 3]), branch(2, &[4]), branch(3, &[4])],
+        });
+        assert_eq!(branching.starting_waypoint(), Some(1));
+        assert_eq!(branching.next_waypoints(&[1]), vec![2, 3]);
+        assert_eq!(branching.next_waypoints(&[1, 3]), vec![4]);
+        assert!(branching.is_route_finished(&[1, 3, 4]));
+
+        let mut completed = vec![1, 3, 4];
+        branching.rewind_to(&mut completed, 3);
+        assert_eq!(completed, vec![1]);
+
+        let mut completed = vec![4, 2];
+        unordered.rewind_to(&mut completed, 4);
+        assert_eq!(completed, vec![2]);
+    }
 }
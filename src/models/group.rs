@@ -0,0 +1,511 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgConnection, PgPool};
+use uuid::Uuid;
+
+/// Group size used when the captain does not set one
+pub const DEFAULT_GROUP_SIZE: i32 = 4;
+/// Largest group a captain may create
+pub const MAX_GROUP_SIZE: i32 = 12;
+
+/// Join codes avoid characters that are easily confused when read out (0/O, 1/I)
+const JOIN_CODE_ALPHABET: &[u8; 32] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const JOIN_CODE_LENGTH: usize = 8;
+
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct ParticipantGroup {
+    #[serde(rename = "group-id")]
+    pub group_id: i32, // SERIAL PRIMARY KEY - never null
+    #[serde(rename = "challenge-id")]
+    pub challenge_id: i32, // NOT NULL
+    #[serde(rename = "participant-id")]
+    pub participant_id: Uuid, // NOT NULL, shared by all members
+    #[serde(rename = "group-name")]
+    pub group_name: String, // NOT NULL
+    #[serde(rename = "join-code")]
+    pub join_code: String, // NOT NULL UNIQUE
+    #[serde(rename = "captain-id")]
+    pub captain_user_id: i32, // NOT NULL FK to users
+    #[serde(rename = "max-size")]
+    pub max_size: i32, // NOT NULL
+    #[serde(rename = "created-at")]
+    pub created_at: DateTime<Utc>, // DEFAULT NOW() - never null
+}
+
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct GroupMember {
+    #[serde(rename = "user-id")]
+    pub user_id: i32,
+    pub nickname: Option<String>,
+    #[serde(rename = "joined-at")]
+    pub joined_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupResponse {
+    #[serde(flatten)]
+    pub group: ParticipantGroup,
+    pub members: Vec<GroupMember>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateGroupRequest {
+    #[serde(rename = "group-name")]
+    pub group_name: String,
+    #[serde(rename = "max-size")]
+    pub max_size: Option<i32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct JoinGroupRequest {
+    #[serde(rename = "join-code")]
+    pub join_code: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GroupError {
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+    #[error("User has not accepted an invitation to this challenge")]
+    NotParticipant,
+    #[error("User is already a member of a group in this challenge")]
+    AlreadyInGroup,
+    #[error("User is not a member of a group in this challenge")]
+    NotInGroup,
+    #[error("Group not found")]
+    GroupNotFound,
+    #[error("Group is full")]
+    GroupFull,
+    #[error("Only the group captain may do this")]
+    NotCaptain,
+    #[error("Group has already begun the challenge")]
+    AlreadyBegun,
+    #[error("Group name must not be empty")]
+    InvalidGroupName,
+    #[error("Group size must be between 1 and {MAX_GROUP_SIZE}")]
+    InvalidGroupSize,
+}
+
+/// What happened to a group when a member was removed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MemberRemoval {
+    /// The user was not in a group
+    NotAMember,
+    /// A regular member left, or the captain left and passed the captaincy on
+    Removed,
+    /// The captain was the last member, the group is gone and the captain keeps the participant
+    Dissolved,
+}
+
+pub fn generate_join_code() -> String {
+    Uuid::new_v4()
+        .as_bytes()
+        .iter()
+        .take(JOIN_CODE_LENGTH)
+        .map(|b| JOIN_CODE_ALPHABET[(*b as usize) % JOIN_CODE_ALPHABET.len()] as char)
+        .collect()
+}
+
+impl ParticipantGroup {
+    /// Create a group around the captain's participant. The captain must have accepted an
+    /// invitation and must not be in another group of the challenge.
+    pub async fn create(
+        pool: &PgPool,
+        challenge_id: i32,
+        captain_user_id: i32,
+        group_name: &str,
+        max_size: Option<i32>,
+    ) -> Result<ParticipantGroup, GroupError> {
+        let group_name = group_name.trim();
+        if group_name.is_empty() {
+            return Err(GroupError::InvalidGroupName);
+        }
+        let max_size = max_size.unwrap_or(DEFAULT_GROUP_SIZE);
+        if !(1..=MAX_GROUP_SIZE).contains(&max_size) {
+            return Err(GroupError::InvalidGroupSize);
+        }
+
+        let mut tx = pool.begin().await?;
+
+        if Self::get_for_user_in(&mut tx, challenge_id, captain_user_id)
+            .await?
+            .is_some()
+        {
+            return Err(GroupError::AlreadyInGroup);
+        }
+
+        let participant_id = Self::own_participant_id(&mut tx, challenge_id, captain_user_id)
+            .await?
+            .ok_or(GroupError::NotParticipant)?;
+        if Self::has_begun(&mut tx, participant_id).await? {
+            return Err(GroupError::AlreadyBegun);
+        }
+
+        let group = sqlx::query_as!(
+            ParticipantGroup,
+            r#"
+            INSERT INTO participant_groups (challenge_id, participant_id, group_name, join_code,
+                                            captain_user_id, max_size)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING group_id, challenge_id, participant_id, group_name, join_code,
+                      captain_user_id, max_size, COALESCE(created_at, NOW()) as "created_at!"
+            "#,
+            challenge_id,
+            participant_id,
+            group_name,
+            generate_join_code(),
+            captain_user_id,
+            max_size
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO participant_group_members (group_id, challenge_id, user_id)
+            VALUES ($1, $2, $3)
+            "#,
+            group.group_id,
+            challenge_id,
+            captain_user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        // The group plays under its own name
+        sqlx::query!(
+            "UPDATE challenge_participants SET participant_nickname = $2 WHERE participant_id = $1",
+            participant_id,
+            group_name
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(group)
+    }
+
+    /// Join a group by its code. The user's own participant is replaced by the group's.
+    pub async fn join(
+        pool: &PgPool,
+        challenge_id: i32,
+        join_code: &str,
+        user_id: i32,
+    ) -> Result<ParticipantGroup, GroupError> {
+        let mut tx = pool.begin().await?;
+
+        let group = sqlx::query_as!(
+            ParticipantGroup,
+            r#"
+            SELECT group_id, challenge_id, participant_id, group_name, join_code,
+                   captain_user_id, max_size, COALESCE(created_at, NOW()) as "created_at!"
+            FROM participant_groups
+            WHERE challenge_id = $1 AND join_code = $2
+            FOR UPDATE
+            "#,
+            challenge_id,
+            join_code.trim().to_uppercase()
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(GroupError::GroupNotFound)?;
+        if Self::has_begun(&mut tx, group.participant_id).await? {
+            return Err(GroupError::AlreadyBegun);
+        }
+
+        if Self::get_for_user_in(&mut tx, challenge_id, user_id)
+            .await?
+            .is_some()
+        {
+            return Err(GroupError::AlreadyInGroup);
+        }
+
+        let own_participant_id = Self::own_participant_id(&mut tx, challenge_id, user_id)
+            .await?
+            .ok_or(GroupError::NotParticipant)?;
+
+        let member_count = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!" FROM participant_group_members WHERE group_id = $1"#,
+            group.group_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if member_count >= group.max_size as i64 {
+            return Err(GroupError::GroupFull);
+        }
+
+        sqlx::query!(
+            "DELETE FROM challenge_participants WHERE participant_id = $1",
+            own_participant_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO participant_group_members (group_id, challenge_id, user_id)
+            VALUES ($1, $2, $3)
+            "#,
+            group.group_id,
+            challenge_id,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(group)
+    }
+
+    /// Leave the group and play individually again
+    pub async fn leave(pool: &PgPool, challenge_id: i32, user_id: i32) -> Result<(), GroupError> {
+        let mut tx = pool.begin().await?;
+
+        let group = Self::get_for_user_in(&mut tx, challenge_id, user_id)
+            .await?
+            .ok_or(GroupError::NotInGroup)?;
+        if Self::has_begun(&mut tx, group.participant_id).await? {
+            return Err(GroupError::AlreadyBegun);
+        }
+
+        Self::remove_member(&mut tx, challenge_id, user_id).await?;
+
+        if Self::own_participant_id(&mut tx, challenge_id, user_id)
+            .await?
+            .is_none()
+        {
+            sqlx::query!(
+                r#"
+                INSERT INTO challenge_participants (challenge_id, user_id, participant_nickname)
+                SELECT $1, user_id, nickname FROM users WHERE user_id = $2
+                "#,
+                challenge_id,
+                user_id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Remove a member from their group in the challenge. A departing captain hands the
+    /// captaincy, and with it the group's participant, to the longest-standing member.
+    pub async fn remove_member(
+        conn: &mut PgConnection,
+        challenge_id: i32,
+        user_id: i32,
+    ) -> Result<MemberRemoval, GroupError> {
+        let Some(group) = Self::get_for_user_in(conn, challenge_id, user_id).await? else {
+            return Ok(MemberRemoval::NotAMember);
+        };
+
+        sqlx::query!(
+            "DELETE FROM participant_group_members WHERE group_id = $1 AND user_id = $2",
+            group.group_id,
+            user_id
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        if group.captain_user_id != user_id {
+            return Ok(MemberRemoval::Removed);
+        }
+
+        let next_captain = sqlx::query_scalar!(
+            r#"
+            SELECT user_id FROM participant_group_members
+            WHERE group_id = $1
+            ORDER BY joined_at, user_id
+            LIMIT 1
+            "#,
+            group.group_id
+        )
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        match next_captain {
+            Some(next_captain) => {
+                sqlx::query!(
+                    "UPDATE participant_groups SET captain_user_id = $2 WHERE group_id = $1",
+                    group.group_id,
+                    next_captain
+                )
+                .execute(&mut *conn)
+                .await?;
+                sqlx::query!(
+                    "UPDATE challenge_participants SET user_id = $2 WHERE participant_id = $1",
+                    group.participant_id,
+                    next_captain
+                )
+                .execute(&mut *conn)
+                .await?;
+                Ok(MemberRemoval::Removed)
+            }
+            None => {
+                sqlx::query!(
+                    "DELETE FROM participant_groups WHERE group_id = $1",
+                    group.group_id
+                )
+                .execute(&mut *conn)
+                .await?;
+                Ok(MemberRemoval::Dissolved)
+            }
+        }
+    }
+
+    /// Remove another member from the captain's group, the member plays individually again
+    pub async fn remove_by_captain(
+        pool: &PgPool,
+        challenge_id: i32,
+        captain_user_id: i32,
+        member_user_id: i32,
+    ) -> Result<(), GroupError> {
+        let group = Self::get_for_user(pool, challenge_id, captain_user_id)
+            .await?
+            .ok_or(GroupError::NotInGroup)?;
+        if group.captain_user_id != captain_user_id {
+            return Err(GroupError::NotCaptain);
+        }
+        if member_user_id == captain_user_id {
+            return Err(GroupError::NotInGroup);
+        }
+        match Self::get_for_user(pool, challenge_id, member_user_id).await? {
+            Some(member_group) if member_group.group_id == group.group_id => {}
+            _ => return Err(GroupError::NotInGroup),
+        }
+
+        Self::leave(pool, challenge_id, member_user_id).await
+    }
+
+    pub async fn get_for_user(
+        pool: &PgPool,
+        challenge_id: i32,
+        user_id: i32,
+    ) -> Result<Option<ParticipantGroup>, GroupError> {
+        let mut conn = pool.acquire().await?;
+        Self::get_for_user_in(&mut conn, challenge_id, user_id).await
+    }
+
+    async fn get_for_user_in(
+        conn: &mut PgConnection,
+        challenge_id: i32,
+        user_id: i32,
+    ) -> Result<Option<ParticipantGroup>, GroupError> {
+        let group = sqlx::query_as!(
+            ParticipantGroup,
+            r#"
+            SELECT g.group_id, g.challenge_id, g.participant_id, g.group_name, g.join_code,
+                   g.captain_user_id, g.max_size, COALESCE(g.created_at, NOW()) as "created_at!"
+            FROM participant_groups g
+            JOIN participant_group_members m ON m.group_id = g.group_id
+            WHERE m.challenge_id = $1 AND m.user_id = $2
+            "#,
+            challenge_id,
+            user_id
+        )
+        .fetch_optional(conn)
+        .await?;
+
+        Ok(group)
+    }
+
+    pub async fn get_for_challenge(
+        pool: &PgPool,
+        challenge_id: i32,
+    ) -> Result<Vec<ParticipantGroup>, GroupError> {
+        let groups = sqlx::query_as!(
+            ParticipantGroup,
+            r#"
+            SELECT group_id, challenge_id, participant_id, group_name, join_code,
+                   captain_user_id, max_size, COALESCE(created_at, NOW()) as "created_at!"
+            FROM participant_groups
+            WHERE challenge_id = $1
+            ORDER BY group_id
+            "#,
+            challenge_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(groups)
+    }
+
+    pub async fn members(&self, pool: &PgPool) -> Result<Vec<GroupMember>, GroupError> {
+        let members = sqlx::query_as!(
+            GroupMember,
+            r#"
+            SELECT m.user_id, u.nickname, COALESCE(m.joined_at, NOW()) as "joined_at!"
+            FROM participant_group_members m
+            JOIN users u ON u.user_id = m.user_id
+            WHERE m.group_id = $1
+            ORDER BY m.joined_at, m.user_id
+            "#,
+            self.group_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(members)
+    }
+
+    /// Whether the participant has begun a self-paced challenge
+    async fn has_begun(conn: &mut PgConnection, participant_id: Uuid) -> Result<bool, GroupError> {
+        let started_at = sqlx::query_scalar!(
+            "SELECT started_at FROM challenge_participants WHERE participant_id = $1",
+            participant_id
+        )
+        .fetch_optional(conn)
+        .await?
+        .flatten();
+
+        Ok(started_at.is_some())
+    }
+
+    /// The participant a user plays as on their own, if they have one
+    async fn own_participant_id(
+        conn: &mut PgConnection,
+        challenge_id: i32,
+        user_id: i32,
+    ) -> Result<Option<Uuid>, GroupError> {
+        let participant_id = sqlx::query_scalar!(
+            r#"
+            SELECT participant_id FROM challenge_participants
+            WHERE challenge_id = $1 AND user_id = $2
+            FOR UPDATE
+            "#,
+            challenge_id,
+            user_id
+        )
+        .fetch_optional(conn)
+        .await?;
+
+        Ok(participant_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_join_code() {
+        let code = generate_join_code();
+        assert_eq!(code.len(), JOIN_CODE_LENGTH);
+        assert!(code.bytes().all(|b| JOIN_CODE_ALPHABET.contains(&b)));
+        assert_ne!(code, generate_join_code());
+    }
+
+    #[test]
+    fn test_create_group_request_deserialization() {
+        let json = r#"{"group-name": "Red Team", "max-size": 3}"#;
+
+        let request: CreateGroupRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(request.group_name, "Red Team");
+        assert_eq!(request.max_size, Some(3));
+    }
+}
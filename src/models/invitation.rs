@@ -0,0 +1,599 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgConnection, PgPool, Type};
+use uuid::Uuid;
+
+use crate::models::challenge::{ChallengeError, ChallengeParticipant};
+use crate::models::group::{GroupError, ParticipantGroup};
+use crate::models::user::{User, UserError, UserRole};
+
+/// Default lifetime of an invitation when the moderator does not set one
+pub const DEFAULT_INVITATION_HOURS: i64 = 72;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq)]
+#[sqlx(type_name = "invitation_status", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum InvitationStatus {
+    Pending,
+    Accepted,
+    Declined,
+    Revoked,
+    Expired,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct ChallengeInvitation {
+    #[serde(rename = "invitation-id")]
+    pub invitation_id: i32, // SERIAL PRIMARY KEY - never null
+    #[serde(rename = "challenge-id")]
+    pub challenge_id: i32, // NOT NULL
+    pub email: String, // NOT NULL, lowercase
+    #[serde(rename = "user-id")]
+    pub invited_user_id: Option<i32>, // Null until the email belongs to a registered user
+    #[serde(skip)]
+    #[allow(dead_code)]
+    pub invited_by: Option<i32>, // Can be null FK to users
+    pub status: InvitationStatus, // NOT NULL
+    #[serde(rename = "expires-at")]
+    pub expires_at: DateTime<Utc>, // NOT NULL
+    #[serde(rename = "responded-at")]
+    pub responded_at: Option<DateTime<Utc>>, // Set on accept/decline/revoke
+    pub nickname: Option<String>, // Suggested by the moderator, can be null
+    #[serde(rename = "created-at")]
+    pub created_at: DateTime<Utc>, // DEFAULT NOW() - never null
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateInvitationsRequest {
+    pub emails: Vec<String>,
+    #[serde(rename = "expires-in-hours")]
+    pub expires_in_hours: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct InvitationTokenRequest {
+    pub token: String,
+    pub nickname: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AcceptInvitationRequest {
+    pub nickname: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InvitationsResponse {
+    #[serde(rename = "challenge-id")]
+    pub challenge_id: i32,
+    pub invitations: Vec<ChallengeInvitation>,
+}
+
+/// Invitation as shown to the invitee when following the invitation link
+#[derive(Debug, Clone, Serialize)]
+pub struct InvitationDetails {
+    #[serde(flatten)]
+    pub invitation: ChallengeInvitation,
+    #[serde(rename = "challenge-name")]
+    pub challenge_name: String,
+    #[serde(rename = "planned-start-time")]
+    pub planned_start_time: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InvitationAcceptedResponse {
+    #[serde(flatten)]
+    pub invitation: ChallengeInvitation,
+    #[serde(rename = "participant-id")]
+    pub participant_id: Uuid,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct InvitationListQuery {
+    pub status: Option<InvitationStatus>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum InvitationError {
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+    #[error("Challenge error: {0}")]
+    ChallengeError(#[from] ChallengeError),
+    #[error("User error: {0}")]
+    UserError(#[from] UserError),
+    #[error("Group error: {0}")]
+    GroupError(#[from] GroupError),
+    #[error("Invalid email address: {0}")]
+    InvalidEmail(String),
+    #[error("Invitation not found")]
+    InvitationNotFound,
+    #[error("Invitation has expired")]
+    InvitationExpired,
+    #[error("Invitation has been revoked")]
+    InvitationRevoked,
+    #[error("Invitation has already been answered")]
+    AlreadyResponded,
+    #[error("Invitation can no longer be revoked")]
+    NotRevocable,
+    #[error("Invitation was sent to a different email address")]
+    WrongRecipient,
+}
+
+/// Normalise an invited email address, rejecting anything that is not a plausible address
+pub fn normalize_email(email: &str) -> Result<String, InvitationError> {
+    let email = email.trim().to_lowercase();
+    if User::is_valid_email(&email) {
+        Ok(email)
+    } else {
+        Err(InvitationError::InvalidEmail(email))
+    }
+}
+
+impl ChallengeInvitation {
+    /// Create a pending invitation, or refresh one that is still pending. Revoked and expired
+    /// invitations are reopened; accepted and declined invitations are returned unchanged.
+    pub async fn create_or_refresh(
+        pool: &PgPool,
+        challenge_id: i32,
+        email: &str,
+        invited_by: i32,
+        expires_at: DateTime<Utc>,
+        nickname: Option<String>,
+    ) -> Result<ChallengeInvitation, InvitationError> {
+        let invitation = sqlx::query_as!(
+            ChallengeInvitation,
+            r#"
+            INSERT INTO challenge_invitations (challenge_id, email, invited_user_id, invited_by,
+                                               expires_at, participant_nickname)
+            VALUES ($1, $2::text, (SELECT user_id FROM users WHERE lower(username) = $2::text), $3, $4,
+                    $5)
+            ON CONFLICT (challenge_id, email) DO UPDATE
+            SET status = 'PENDING',
+                responded_at = NULL,
+                expires_at = EXCLUDED.expires_at,
+                invited_by = EXCLUDED.invited_by,
+                invited_user_id = COALESCE(challenge_invitations.invited_user_id,
+                                           EXCLUDED.invited_user_id),
+                participant_nickname = COALESCE(EXCLUDED.participant_nickname,
+                                                challenge_invitations.participant_nickname)
+            WHERE challenge_invitations.status IN ('PENDING', 'REVOKED', 'EXPIRED')
+            RETURNING invitation_id, challenge_id, email, invited_user_id, invited_by,
+                      status as "status: InvitationStatus", expires_at, responded_at,
+                      participant_nickname as nickname,
+                      COALESCE(created_at, NOW()) as "created_at!"
+            "#,
+            challenge_id,
+            email,
+            invited_by,
+            expires_at,
+            nickname
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        match invitation {
+            Some(invitation) => {
+                if let Some(user_id) = invitation.invited_user_id {
+                    User::grant_role(pool, user_id, UserRole::ChallengeInvitee).await?;
+                }
+                Ok(invitation)
+            }
+            None => Self::get_by_challenge_and_email(pool, challenge_id, email).await,
+        }
+    }
+
+    pub async fn get_by_id(
+        pool: &PgPool,
+        invitation_id: i32,
+    ) -> Result<ChallengeInvitation, InvitationError> {
+        sqlx::query_as!(
+            ChallengeInvitation,
+            r#"
+            SELECT invitation_id, challenge_id, email, invited_user_id, invited_by,
+                   status as "status: InvitationStatus", expires_at, responded_at,
+                   participant_nickname as nickname,
+                   COALESCE(created_at, NOW()) as "created_at!"
+            FROM challenge_invitations
+            WHERE invitation_id = $1
+            "#,
+            invitation_id
+        )
+        .fetch_optional(pool)
+        .await?
+        .ok_or(InvitationError::InvitationNotFound)
+    }
+
+    pub async fn get_by_challenge_and_email(
+        pool: &PgPool,
+        challenge_id: i32,
+        email: &str,
+    ) -> Result<ChallengeInvitation, InvitationError> {
+        sqlx::query_as!(
+            ChallengeInvitation,
+            r#"
+            SELECT invitation_id, challenge_id, email, invited_user_id, invited_by,
+                   status as "status: InvitationStatus", expires_at, responded_at,
+                   participant_nickname as nickname,
+                   COALESCE(created_at, NOW()) as "created_at!"
+            FROM challenge_invitations
+            WHERE challenge_id = $1 AND email = $2
+            "#,
+            challenge_id,
+            email
+        )
+        .fetch_optional(pool)
+        .await?
+        .ok_or(InvitationError::InvitationNotFound)
+    }
+
+    /// All invitations of a challenge, optionally filtered by status
+    pub async fn get_for_challenge(
+        pool: &PgPool,
+        challenge_id: i32,
+        status: Option<InvitationStatus>,
+    ) -> Result<Vec<ChallengeInvitation>, InvitationError> {
+        Self::expire_overdue(pool).await?;
+
+        let invitations = sqlx::query_as!(
+            ChallengeInvitation,
+            r#"
+            SELECT invitation_id, challenge_id, email, invited_user_id, invited_by,
+                   status as "status: InvitationStatus", expires_at, responded_at,
+                   participant_nickname as nickname,
+                   COALESCE(created_at, NOW()) as "created_at!"
+            FROM challenge_invitations
+            WHERE challenge_id = $1 AND ($2::invitation_status IS NULL OR status = $2)
+            ORDER BY created_at, invitation_id
+            "#,
+            challenge_id,
+            status as Option<InvitationStatus>
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(invitations)
+    }
+
+    /// Invitations addressed to a user with the name and start time of their challenge,
+    /// optionally filtered by status
+    pub async fn get_details_for_user(
+        pool: &PgPool,
+        user_id: i32,
+        status: Option<InvitationStatus>,
+    ) -> Result<Vec<InvitationDetails>, InvitationError> {
+        Self::expire_overdue(pool).await?;
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT i.invitation_id, i.challenge_id, i.email, i.invited_user_id, i.invited_by,
+                   i.status as "status: InvitationStatus", i.expires_at, i.responded_at,
+                   i.participant_nickname, COALESCE(i.created_at, NOW()) as "created_at!",
+                   tc.challenge_name, tc.planned_start_time
+            FROM challenge_invitations i
+            JOIN temporal_challenges tc ON tc.challenge_id = i.challenge_id AND tc.end_at IS NULL
+            WHERE i.invited_user_id = $1 AND ($2::invitation_status IS NULL OR i.status = $2)
+            ORDER BY tc.planned_start_time, i.invitation_id
+            "#,
+            user_id,
+            status as Option<InvitationStatus>
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| InvitationDetails {
+                invitation: ChallengeInvitation {
+                    invitation_id: row.invitation_id,
+                    challenge_id: row.challenge_id,
+                    email: row.email,
+                    invited_user_id: row.invited_user_id,
+                    invited_by: row.invited_by,
+                    status: row.status,
+                    expires_at: row.expires_at,
+                    responded_at: row.responded_at,
+                    nickname: row.participant_nickname,
+                    created_at: row.created_at,
+                },
+                challenge_name: row.challenge_name,
+                planned_start_time: row.planned_start_time,
+            })
+            .collect())
+    }
+
+    /// Mark pending invitations past their expiry as expired, returns the number updated
+    pub async fn expire_overdue(pool: &PgPool) -> Result<u64, InvitationError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE challenge_invitations
+            SET status = 'EXPIRED'
+            WHERE status = 'PENDING' AND expires_at < NOW()
+            "#
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Attach pending invitations for an email address to a newly registered user,
+    /// returns the number of invitations linked
+    pub async fn link_pending_to_user(
+        pool: &PgPool,
+        user_id: i32,
+        email: &str,
+    ) -> Result<u64, InvitationError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE challenge_invitations
+            SET invited_user_id = $1
+            WHERE email = $2 AND status = 'PENDING' AND invited_user_id IS NULL
+            "#,
+            user_id,
+            email.trim().to_lowercase()
+        )
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() > 0 {
+            User::grant_role(pool, user_id, UserRole::ChallengeInvitee).await?;
+        }
+
+        Ok(result.rows_affected())
+    }
+
+    /// Check that `user` may answer this invitation
+    fn ensure_answerable(&self, user: &User) -> Result<(), InvitationError> {
+        let addressed_to_user = match self.invited_user_id {
+            Some(user_id) => user_id == user.user_id,
+            None => self.email == user.username.to_lowercase(),
+        };
+        if !addressed_to_user {
+            return Err(InvitationError::WrongRecipient);
+        }
+        match self.status {
+            InvitationStatus::Pending if self.expires_at < Utc::now() => {
+                Err(InvitationError::InvitationExpired)
+            }
+            InvitationStatus::Pending => Ok(()),
+            InvitationStatus::Expired => Err(InvitationError::InvitationExpired),
+            InvitationStatus::Revoked => Err(InvitationError::InvitationRevoked),
+            InvitationStatus::Accepted | InvitationStatus::Declined => {
+                Err(InvitationError::AlreadyResponded)
+            }
+        }
+    }
+
+    async fn respond(
+        &mut self,
+        conn: &mut PgConnection,
+        user: &User,
+        status: InvitationStatus,
+    ) -> Result<(), InvitationError> {
+        let responded = sqlx::query!(
+            r#"
+            UPDATE challenge_invitations
+            SET status = $2, invited_user_id = $3, responded_at = NOW()
+            WHERE invitation_id = $1 AND status = 'PENDING'
+            RETURNING responded_at
+            "#,
+            self.invitation_id,
+            status as InvitationStatus,
+            user.user_id
+        )
+        .fetch_optional(conn)
+        .await?
+        .ok_or(InvitationError::AlreadyResponded)?;
+
+        self.status = status;
+        self.invited_user_id = Some(user.user_id);
+        self.responded_at = responded.responded_at;
+
+        Ok(())
+    }
+
+    /// Accept the invitation and join the challenge as a participant. The nickname chosen by
+    /// the invitee takes precedence over the one suggested by the moderator.
+    pub async fn accept(
+        &mut self,
+        pool: &PgPool,
+        user: &User,
+        nickname: Option<String>,
+    ) -> Result<ChallengeParticipant, InvitationError> {
+        self.ensure_answerable(user)?;
+
+        // Hold the invitation until the participant exists and it is answered, a revoke in
+        // between would leave the participant behind
+        let mut tx = pool.begin().await?;
+        *self = sqlx::query_as!(
+            ChallengeInvitation,
+            r#"
+            SELECT invitation_id, challenge_id, email, invited_user_id, invited_by,
+                   status as "status: InvitationStatus", expires_at, responded_at,
+                   participant_nickname as nickname,
+                   COALESCE(created_at, NOW()) as "created_at!"
+            FROM challenge_invitations
+            WHERE invitation_id = $1
+            FOR UPDATE
+            "#,
+            self.invitation_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(InvitationError::InvitationNotFound)?;
+        self.ensure_answerable(user)?;
+
+        let participant = match ChallengeParticipant::create_for_challenge(
+            &mut tx,
+            self.challenge_id,
+            user.user_id,
+            nickname.or_else(|| self.nickname.clone()),
+        )
+        .await
+        {
+            Ok(participant) => participant,
+            // Joined before invitations were tracked
+            Err(ChallengeError::AlreadyParticipant) => {
+                ChallengeParticipant::get_by_challenge_and_user(
+                    pool,
+                    self.challenge_id,
+                    user.user_id,
+                )
+                .await?
+                .ok_or(ChallengeError::ParticipantNotFound)?
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        self.respond(&mut tx, user, InvitationStatus::Accepted)
+            .await?;
+        tx.commit().await?;
+        User::grant_role(pool, user.user_id, UserRole::ChallengeParticipant).await?;
+
+        Ok(participant)
+    }
+
+    pub async fn decline(&mut self, pool: &PgPool, user: &User) -> Result<(), InvitationError> {
+        self.ensure_answerable(user)?;
+        self.respond(
+            &mut *pool.acquire().await?,
+            user,
+            InvitationStatus::Declined,
+        )
+        .await
+    }
+
+    /// Revoke a pending or accepted invitation. Revoking an accepted invitation removes the
+    /// participant (or the group membership) again, so it is refused once the challenge has
+    /// started.
+    pub async fn revoke(
+        &mut self,
+        pool: &PgPool,
+        challenge_started: bool,
+    ) -> Result<(), InvitationError> {
+        let mut tx = pool.begin().await?;
+
+        // Read what it became under the lock, it may have been accepted meanwhile
+        let current = sqlx::query!(
+            r#"
+            SELECT status as "status: InvitationStatus", invited_user_id
+            FROM challenge_invitations
+            WHERE invitation_id = $1
+            FOR UPDATE
+            "#,
+            self.invitation_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(InvitationError::InvitationNotFound)?;
+        self.status = current.status;
+        self.invited_user_id = current.invited_user_id;
+        if self.status == InvitationStatus::Accepted && challenge_started {
+            return Err(InvitationError::NotRevocable);
+        }
+
+        let revoked = sqlx::query!(
+            r#"
+            UPDATE challenge_invitations
+            SET status = 'REVOKED', responded_at = NOW()
+            WHERE invitation_id = $1 AND status IN ('PENDING', 'ACCEPTED')
+            RETURNING responded_at
+            "#,
+            self.invitation_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(InvitationError::NotRevocable)?;
+
+        if self.status == InvitationStatus::Accepted {
+            if let Some(user_id) = self.invited_user_id {
+                ParticipantGroup::remove_member(&mut tx, self.challenge_id, user_id).await?;
+                sqlx::query!(
+                    "DELETE FROM challenge_participants WHERE challenge_id = $1 AND user_id = $2",
+                    self.challenge_id,
+                    user_id
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        tx.commit().await?;
+
+        self.status = InvitationStatus::Revoked;
+        self.responded_at = revoked.responded_at;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_email() {
+        assert_eq!(
+            normalize_email("  Alice@Example.COM ").unwrap(),
+            "alice@example.com"
+        );
+        assert!(matches!(
+            normalize_email("not-an-email"),
+            Err(InvitationError::InvalidEmail(_))
+        ));
+    }
+
+    #[test]
+    fn test_create_invitations_request_deserialization() {
+        let json = r#"{"emails": ["a@example.com", "b@example.com"], "expires-in-hours": 24}"#;
+
+        let request: CreateInvitationsRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(request.emails.len(), 2);
+        assert_eq!(request.expires_in_hours, Some(24));
+    }
+
+    #[test]
+    fn test_invitation_status_serialization() {
+        assert_eq!(
+            serde_json::to_string(&InvitationStatus::Declined).unwrap(),
+            "\"DECLINED\""
+        );
+    }
+
+    #[test]
+    fn test_ensure_answerable_by_status() {
+        let user = User {
+            user_id: 7,
+            username: "alice@example.com".to_string(),
+            password: String::new(),
+            nickname: None,
+            creation_date: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let mut invitation = ChallengeInvitation {
+            invitation_id: 1,
+            challenge_id: 1,
+            email: "alice@example.com".to_string(),
+            invited_user_id: Some(7),
+            invited_by: None,
+            status: InvitationStatus::Pending,
+            expires_at: Utc::now() + chrono::Duration::hours(1),
+            responded_at: None,
+            nickname: None,
+            created_at: Utc::now(),
+        };
+        assert!(invitation.ensure_answerable(&user).is_ok());
+
+        invitation.status = InvitationStatus::Revoked;
+        assert!(matches!(
+            invitation.ensure_answerable(&user),
+            Err(InvitationError::InvitationRevoked)
+        ));
+
+        invitation.status = InvitationStatus::Pending;
+        invitation.expires_at = Utc::now() - chrono::Duration::hours(1);
+        assert!(matches!(
+            invitation.ensure_answerable(&user),
+            Err(InvitationError::InvitationExpired)
+        ));
+    }
+}
@@ -0,0 +1,342 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool, Type};
+use uuid::Uuid;
+
+use crate::models::challenge::{ChallengeError, ChallengeParticipant};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq)]
+#[sqlx(
+    type_name = "challenge_message_type",
+    rename_all = "SCREAMING_SNAKE_CASE"
+)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MessageType {
+    Hint,
+    Announcement,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct ChallengeMessage {
+    pub message_id: i32,                // SERIAL PRIMARY KEY - never null
+    pub challenge_id: i32,              // NOT NULL
+    pub sender_user_id: Option<i32>,    // Can be null FK to users
+    pub message_type: MessageType,      // NOT NULL
+    pub waypoint_sequence: Option<i32>, // Set for hints, null for announcements
+    pub message_body: String,           // NOT NULL
+    pub created_at: DateTime<Utc>,      // DEFAULT NOW() - never null
+}
+
+/// A message as seen from a participant's inbox
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct InboxMessage {
+    #[serde(rename = "message-id")]
+    pub message_id: i32,
+    #[serde(rename = "message-type")]
+    pub message_type: MessageType,
+    #[serde(rename = "waypoint-id")]
+    pub waypoint_sequence: Option<i32>,
+    pub message: String,
+    #[serde(rename = "sent-at")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "read-at")]
+    pub read_at: Option<DateTime<Utc>>,
+}
+
+/// Who should receive a moderator message
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum MessageAudience {
+    /// A single participant
+    Participant {
+        #[serde(rename = "participant-id")]
+        participant_id: Uuid,
+    },
+    /// An explicit set of participants
+    Group {
+        #[serde(rename = "participant-ids")]
+        participant_ids: Vec<Uuid>,
+    },
+    /// Everyone currently presented with the given waypoint
+    Waypoint {
+        #[serde(rename = "waypoint-id")]
+        waypoint_sequence: i32,
+    },
+    /// Every participant of the challenge
+    All,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReleaseHintRequest {
+    #[serde(rename = "waypoint-id")]
+    pub waypoint_sequence: i32,
+    #[serde(rename = "hint-index")]
+    pub hint_index: Option<usize>,
+    pub text: Option<String>,
+    pub audience: MessageAudience,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AnnouncementRequest {
+    pub message: String,
+    pub audience: Option<MessageAudience>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MarkReadRequest {
+    #[serde(rename = "message-ids")]
+    pub message_ids: Vec<i32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MessageSentResponse {
+    #[serde(rename = "message-id")]
+    pub message_id: i32,
+    #[serde(rename = "challenge-id")]
+    pub challenge_id: i32,
+    #[serde(rename = "message-type")]
+    pub message_type: MessageType,
+    pub message: String,
+    pub recipients: u64,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum MessageError {
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+    #[error("Challenge error: {0}")]
+    ChallengeError(#[from] ChallengeError),
+    #[error("Message has no recipients")]
+    NoRecipients,
+    #[error("Message body is empty")]
+    EmptyMessage,
+}
+
+impl ChallengeMessage {
+    /// Store a message and deliver it to the inbox of every recipient.
+    /// Recipients that are not participants of the challenge are ignored; returns the message
+    /// and the number of inboxes it was delivered to.
+    pub async fn create_and_deliver(
+        pool: &PgPool,
+        challenge_id: i32,
+        sender_user_id: i32,
+        message_type: MessageType,
+        waypoint_sequence: Option<i32>,
+        message_body: &str,
+        recipients: &[Uuid],
+    ) -> Result<(ChallengeMessage, u64), MessageError> {
+        if message_body.trim().is_empty() {
+            return Err(MessageError::EmptyMessage);
+        }
+
+        if recipients.is_empty() {
+            return Err(MessageError::NoRecipients);
+        }
+
+        let mut tx = pool.begin().await?;
+
+        let message = sqlx::query_as!(
+            ChallengeMessage,
+            r#"
+            INSERT INTO challenge_messages (challenge_id, sender_user_id, message_type,
+                                            waypoint_sequence, message_body)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING message_id, challenge_id, sender_user_id,
+                      message_type as "message_type: MessageType",
+                      waypoint_sequence, message_body,
+                      COALESCE(created_at, NOW()) as "created_at!"
+            "#,
+            challenge_id,
+            sender_user_id,
+            message_type as MessageType,
+            waypoint_sequence,
+            message_body
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let delivered = sqlx::query!(
+            r#"
+            INSERT INTO participant_inbox (message_id, participant_id)
+            SELECT $1, participant_id
+            FROM challenge_participants
+            WHERE challenge_id = $2 AND participant_id = ANY($3)
+            ON CONFLICT DO NOTHING
+            "#,
+            message.message_id,
+            challenge_id,
+            recipients
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        // Nothing to deliver, drop the transaction so the message is not stored
+        if delivered == 0 {
+            return Err(MessageError::NoRecipients);
+        }
+
+        tx.commit().await?;
+
+        Ok((message, delivered))
+    }
+}
+
+impl MessageAudience {
+    /// Resolve the audience into the participant ids it currently covers
+    pub async fn resolve(
+        &self,
+        pool: &PgPool,
+        challenge_id: i32,
+    ) -> Result<Vec<Uuid>, MessageError> {
+        let participant_ids = match self {
+            MessageAudience::Participant { participant_id } => vec![*participant_id],
+            MessageAudience::Group { participant_ids } => participant_ids.clone(),
+            MessageAudience::Waypoint { waypoint_sequence } => {
+                ChallengeParticipant::get_participants_at_waypoint(
+                    pool,
+                    challenge_id,
+                    *waypoint_sequence,
+                )
+                .await?
+                .into_iter()
+                .map(|p| p.participant_id)
+                .collect()
+            }
+            MessageAudience::All => {
+                ChallengeParticipant::get_participants_for_challenge(pool, challenge_id)
+                    .await?
+                    .into_iter()
+                    .map(|p| p.participant_id)
+                    .collect()
+            }
+        };
+
+        Ok(participant_ids)
+    }
+}
+
+impl InboxMessage {
+    /// Fetch inbox messages for a participant, newest last, and mark the fetched ones delivered
+    pub async fn get_for_participant(
+        pool: &PgPool,
+        participant_id: Uuid,
+        since_message_id: Option<i32>,
+        unread_only: bool,
+    ) -> Result<Vec<InboxMessage>, MessageError> {
+        let messages = sqlx::query_as!(
+            InboxMessage,
+            r#"
+            SELECT cm.message_id, cm.message_type as "message_type: MessageType",
+                   cm.waypoint_sequence, cm.message_body as message,
+                   COALESCE(cm.created_at, NOW()) as "created_at!",
+                   pi.read_at
+            FROM participant_inbox pi
+            JOIN challenge_messages cm ON cm.message_id = pi.message_id
+            WHERE pi.participant_id = $1
+              AND cm.message_id > $2
+              AND ($3 = false OR pi.read_at IS NULL)
+            ORDER BY cm.message_id
+            "#,
+            participant_id,
+            since_message_id.unwrap_or(0),
+            unread_only
+        )
+        .fetch_all(pool)
+        .await?;
+
+        // Only what was returned counts as delivered, not messages filtered out or arrived since
+        let message_ids: Vec<i32> = messages.iter().map(|m| m.message_id).collect();
+        sqlx::query!(
+            r#"
+            UPDATE participant_inbox
+            SET delivered_at = NOW()
+            WHERE participant_id = $1 AND message_id = ANY($2) AND delivered_at IS NULL
+            "#,
+            participant_id,
+            &message_ids
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(messages)
+    }
+
+    /// Mark inbox messages as read, returns the number of messages updated
+    pub async fn mark_read(
+        pool: &PgPool,
+        participant_id: Uuid,
+        message_ids: &[i32],
+    ) -> Result<u64, MessageError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE participant_inbox
+            SET read_at = NOW()
+            WHERE participant_id = $1 AND message_id = ANY($2) AND read_at IS NULL
+            "#,
+            participant_id,
+            message_ids
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_type_serialization() {
+        assert_eq!(
+            serde_json::to_string(&MessageType::Hint).unwrap(),
+            "\"HINT\""
+        );
+        assert_eq!(
+            serde_json::to_string(&MessageType::Announcement).unwrap(),
+            "\"ANNOUNCEMENT\""
+        );
+    }
+
+    #[test]
+    fn test_message_audience_deserialization() {
+        let participant_id = Uuid::new_v4();
+        let json = format!(r#"{{"type": "participant", "participant-id": "{participant_id}"}}"#);
+        let audience: MessageAudience = serde_json::from_str(&json).unwrap();
+        assert_eq!(audience, MessageAudience::Participant { participant_id });
+
+        let audience: MessageAudience =
+            serde_json::from_str(r#"{"type": "waypoint", "waypoint-id": 3}"#).unwrap();
+        assert_eq!(
+            audience,
+            MessageAudience::Waypoint {
+                waypoint_sequence: 3
+            }
+        );
+
+        let audience: MessageAudience = serde_json::from_str(r#"{"type": "all"}"#).unwrap();
+        assert_eq!(audience, MessageAudience::All);
+    }
+
+    #[test]
+    fn test_release_hint_request_deserialization() {
+        let json = r#"{
+            "waypoint-id": 2,
+            "hint-index": 1,
+            "audience": {"type": "group", "participant-ids": []}
+        }"#;
+
+        let request: ReleaseHintRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(request.waypoint_sequence, 2);
+        assert_eq!(request.hint_index, Some(1));
+        assert!(request.text.is_none());
+        assert_eq!(
+            request.audience,
+            MessageAudience::Group {
+                participant_ids: vec![]
+            }
+        );
+    }
+}
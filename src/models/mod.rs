@@ -1,5 +1,10 @@
 pub mod audit_log;
 pub mod challenge;
+pub mod group;
+pub mod invitation;
+pub mod message;
+pub mod notification;
+pub mod proof_job;
 pub mod user;
 
 pub use audit_log::AuditLog;
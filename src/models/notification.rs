@@ -0,0 +1,304 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool, Type};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq)]
+#[sqlx(type_name = "device_platform", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DevicePlatform {
+    Fcm,
+    Apns,
+    Local,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq)]
+#[sqlx(type_name = "notification_status", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum NotificationStatus {
+    Pending,
+    Sent,
+    Failed,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct DeviceToken {
+    #[serde(rename = "device-id")]
+    pub device_id: i32, // SERIAL PRIMARY KEY - never null
+    #[serde(skip)]
+    #[allow(dead_code)]
+    pub user_id: i32, // NOT NULL FK to users
+    pub platform: DevicePlatform, // NOT NULL
+    #[serde(rename = "device-token")]
+    pub device_token: String, // NOT NULL, unique per platform
+    #[serde(rename = "created-at")]
+    pub created_at: DateTime<Utc>, // DEFAULT NOW() - never null
+    #[serde(rename = "last-seen-at")]
+    pub last_seen_at: DateTime<Utc>, // DEFAULT NOW() - never null
+}
+
+/// An outbox row claimed for delivery, joined with its target device
+#[derive(Debug, Clone, FromRow)]
+pub struct OutboxNotification {
+    pub notification_id: i32,
+    pub device_id: i32,
+    pub platform: DevicePlatform,
+    pub device_token: String,
+    #[allow(dead_code)]
+    pub challenge_id: Option<i32>,
+    pub title: String,
+    pub body: String,
+    pub data: serde_json::Value,
+    pub attempts: i32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RegisterDeviceRequest {
+    pub platform: DevicePlatform,
+    #[serde(rename = "device-token")]
+    pub device_token: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum NotificationError {
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+    #[error("Device token is empty")]
+    EmptyDeviceToken,
+}
+
+/// How long a claimed notification stays invisible to other dispatchers
+const CLAIM_LEASE_SECONDS: i64 = 60;
+/// Retry backoff doubles from this base delay up to the cap
+const RETRY_BASE_SECONDS: i64 = 30;
+const RETRY_MAX_SECONDS: i64 = 3600;
+
+/// Delay before the next delivery attempt after `attempts` failed attempts
+pub fn retry_delay(attempts: i32) -> Duration {
+    let exponent = attempts.clamp(1, 16) - 1;
+    let seconds = RETRY_BASE_SECONDS.saturating_mul(1 << exponent);
+    Duration::seconds(seconds.min(RETRY_MAX_SECONDS))
+}
+
+impl DeviceToken {
+    /// Register a device token for a user; re-registering a known token moves it to this user
+    pub async fn register(
+        pool: &PgPool,
+        user_id: i32,
+        platform: DevicePlatform,
+        device_token: &str,
+    ) -> Result<DeviceToken, NotificationError> {
+        let device_token = device_token.trim();
+        if device_token.is_empty() {
+            return Err(NotificationError::EmptyDeviceToken);
+        }
+
+        let device = sqlx::query_as!(
+            DeviceToken,
+            r#"
+            INSERT INTO device_tokens (user_id, platform, device_token)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (platform, device_token)
+            DO UPDATE SET user_id = EXCLUDED.user_id, last_seen_at = NOW()
+            RETURNING device_id, user_id, platform as "platform: DevicePlatform", device_token,
+                      COALESCE(created_at, NOW()) as "created_at!",
+                      COALESCE(last_seen_at, NOW()) as "last_seen_at!"
+            "#,
+            user_id,
+            platform as DevicePlatform,
+            device_token
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(device)
+    }
+
+    pub async fn get_for_user(
+        pool: &PgPool,
+        user_id: i32,
+    ) -> Result<Vec<DeviceToken>, NotificationError> {
+        let devices = sqlx::query_as!(
+            DeviceToken,
+            r#"
+            SELECT device_id, user_id, platform as "platform: DevicePlatform", device_token,
+                   COALESCE(created_at, NOW()) as "created_at!",
+                   COALESCE(last_seen_at, NOW()) as "last_seen_at!"
+            FROM device_tokens
+            WHERE user_id = $1
+            ORDER BY device_id
+            "#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(devices)
+    }
+
+    /// Remove one of the user's devices, returns false if it does not exist
+    pub async fn delete(
+        pool: &PgPool,
+        user_id: i32,
+        device_id: i32,
+    ) -> Result<bool, NotificationError> {
+        let result = sqlx::query!(
+            "DELETE FROM device_tokens WHERE device_id = $1 AND user_id = $2",
+            device_id,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Forget a device the push gateway no longer knows, along with what was queued for it
+    pub async fn remove_invalid(pool: &PgPool, device_id: i32) -> Result<(), NotificationError> {
+        sqlx::query!("DELETE FROM device_tokens WHERE device_id = $1", device_id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+impl OutboxNotification {
+    /// Queue a notification for every registered device of the given users,
+    /// returns the number of outbox rows created
+    pub async fn enqueue_for_users(
+        pool: &PgPool,
+        user_ids: &[i32],
+        challenge_id: Option<i32>,
+        title: &str,
+        body: &str,
+        data: &serde_json::Value,
+    ) -> Result<u64, NotificationError> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO notification_outbox (device_id, challenge_id, title, body, data)
+            SELECT device_id, $2, $3, $4, $5
+            FROM device_tokens
+            WHERE user_id = ANY($1)
+            "#,
+            user_ids,
+            challenge_id,
+            title,
+            body,
+            data
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Claim up to `limit` due notifications. Claimed rows are leased so concurrent
+    /// dispatchers skip them, and are retried after the lease if the process dies mid-send.
+    pub async fn claim_due(
+        pool: &PgPool,
+        limit: i64,
+    ) -> Result<Vec<OutboxNotification>, NotificationError> {
+        let lease_until = Utc::now() + Duration::seconds(CLAIM_LEASE_SECONDS);
+
+        let notifications = sqlx::query_as!(
+            OutboxNotification,
+            r#"
+            WITH claimed AS (
+                UPDATE notification_outbox
+                SET attempts = attempts + 1, next_attempt_at = $2
+                WHERE notification_id IN (
+                    SELECT notification_id
+                    FROM notification_outbox
+                    WHERE status = 'PENDING' AND next_attempt_at <= NOW()
+                    ORDER BY next_attempt_at
+                    LIMIT $1
+                    FOR UPDATE SKIP LOCKED
+                )
+                RETURNING notification_id, device_id, challenge_id, title, body, data, attempts
+            )
+            SELECT c.notification_id, c.device_id, d.platform as "platform: DevicePlatform",
+                   d.device_token, c.challenge_id, c.title, c.body, c.data, c.attempts
+            FROM claimed c
+            JOIN device_tokens d ON d.device_id = c.device_id
+            ORDER BY c.notification_id
+            "#,
+            limit,
+            lease_until
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(notifications)
+    }
+
+    pub async fn mark_sent(pool: &PgPool, notification_id: i32) -> Result<(), NotificationError> {
+        sqlx::query!(
+            r#"
+            UPDATE notification_outbox
+            SET status = 'SENT', sent_at = NOW(), last_error = NULL
+            WHERE notification_id = $1
+            "#,
+            notification_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Record a failed attempt; the notification is retried with backoff until
+    /// `max_attempts` is reached or the failure is permanent
+    pub async fn mark_failed(
+        &self,
+        pool: &PgPool,
+        error: &str,
+        max_attempts: i32,
+        permanent: bool,
+    ) -> Result<NotificationStatus, NotificationError> {
+        let status = if permanent || self.attempts >= max_attempts {
+            NotificationStatus::Failed
+        } else {
+            NotificationStatus::Pending
+        };
+        let next_attempt_at = Utc::now() + retry_delay(self.attempts);
+
+        sqlx::query!(
+            r#"
+            UPDATE notification_outbox
+            SET status = $2, last_error = $3, next_attempt_at = $4
+            WHERE notification_id = $1
+            "#,
+            self.notification_id,
+            status as NotificationStatus,
+            error,
+            next_attempt_at
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_device_request_deserialization() {
+        let json = r#"{"platform": "APNS", "device-token": "abc123"}"#;
+
+        let request: RegisterDeviceRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(request.platform, DevicePlatform::Apns);
+        assert_eq!(request.device_token, "abc123");
+    }
+
+    #[test]
+    fn test_retry_delay_backs_off_and_caps() {
+        assert_eq!(retry_delay(1), Duration::seconds(30));
+        assert_eq!(retry_delay(2), Duration::seconds(60));
+        assert_eq!(retry_delay(3), Duration::seconds(120));
+        assert_eq!(retry_delay(10), Duration::seconds(RETRY_MAX_SECONDS));
+        assert_eq!(retry_delay(100), Duration::seconds(RETRY_MAX_SECONDS));
+    }
+}
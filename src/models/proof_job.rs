@@ -0,0 +1,815 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use sqlx::{FromRow, PgPool, Type};
+use uuid::Uuid;
+
+use crate::services::exif::PhotoMetadata;
+use crate::services::location_service::{GeoLocation, LocationService};
+use crate::services::perceptual_hash::{PerceptualHash, NEAR_DUPLICATE_MAX_DISTANCE};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq)]
+#[sqlx(type_name = "proof_job_status", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ProofJobStatus {
+    Queued,    // Waiting to be sent to the image checker
+    Submitted, // The image checker is working on it
+    Accepted,
+    Rejected,
+    Failed,       // The image checker could not give a verdict in time
+    ManualReview, // Left to a moderator: the image checker was unavailable or unsure, or it is a duplicate
+}
+
+/// A photo proof being verified in the background
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct ProofJob {
+    #[serde(rename = "processing-id")]
+    pub processing_id: Uuid, // UUID PRIMARY KEY, also sent to the image checker
+    #[serde(rename = "participant-id")]
+    pub participant_id: Uuid, // NOT NULL FK to challenge_participants
+    #[serde(rename = "challenge-id")]
+    pub challenge_id: i32,
+    #[serde(rename = "waypoint-id")]
+    pub waypoint_sequence: i32,
+    #[serde(skip)]
+    pub image_path: String, // Key in the image store
+    pub status: ProofJobStatus,
+    pub reasons: Vec<String>, // Why the photo was rejected or the job failed
+    #[serde(skip)]
+    pub attempts: i32,
+    #[serde(rename = "submitted-at")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "completed-at")]
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// A photo proof as moderators see it in the review queue
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct ProofReview {
+    #[serde(rename = "processing-id")]
+    pub processing_id: Uuid,
+    #[serde(rename = "participant-id")]
+    pub participant_id: Uuid,
+    #[serde(rename = "challenge-id")]
+    pub challenge_id: i32,
+    #[serde(rename = "waypoint-id")]
+    pub waypoint_sequence: i32,
+    #[serde(skip)]
+    pub image_path: String,
+    pub status: ProofJobStatus,
+    pub reasons: Vec<String>, // Why it was held, or the image checker's reasons
+    #[serde(rename = "photo-metadata")]
+    pub photo_metadata: Option<Json<PhotoMetadata>>, // What the photo's EXIF data says
+    #[serde(rename = "appeal-comment")]
+    pub appeal_comment: Option<String>, // Set when the participant appealed a rejection
+    #[serde(rename = "reviewer-id")]
+    pub reviewer_id: Option<i32>, // The moderator who claimed or decided it
+    #[serde(rename = "claimed-at")]
+    pub claimed_at: Option<DateTime<Utc>>,
+    #[serde(rename = "review-comment")]
+    pub review_comment: Option<String>,
+    #[serde(rename = "reviewed-at")]
+    pub reviewed_at: Option<DateTime<Utc>>,
+    #[serde(rename = "submitted-at")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// A participant's appeal of a rejected photo and what the moderator made of it
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct ProofAppeal {
+    #[serde(rename = "processing-id")]
+    pub processing_id: Uuid,
+    #[serde(skip)]
+    pub participant_id: Uuid,
+    #[serde(skip)]
+    pub challenge_id: i32,
+    #[serde(rename = "waypoint-id")]
+    pub waypoint_sequence: i32,
+    pub comment: String,
+    #[serde(rename = "appealed-at")]
+    pub appealed_at: DateTime<Utc>,
+    pub status: ProofJobStatus, // MANUAL_REVIEW until a moderator decides
+    pub reasons: Vec<String>,   // The rejection reasons, or the moderator's
+    #[serde(rename = "review-comment")]
+    pub review_comment: Option<String>,
+    #[serde(rename = "reviewed-at")]
+    pub reviewed_at: Option<DateTime<Utc>>,
+}
+
+/// The perceptual hash of a proof photo and the waypoint it was submitted for
+#[derive(Debug, Clone, FromRow)]
+pub struct PhotoFingerprint {
+    pub processing_id: Uuid, // PK, FK to proof_jobs
+    pub participant_id: Uuid,
+    pub challenge_id: i32,
+    pub waypoint_sequence: i32,
+    pub waypoint_lat: f64,
+    pub waypoint_lon: f64,
+    pub perceptual_hash: i64,
+}
+
+/// An earlier photo a new one is a near duplicate of
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DuplicatePhotoMatch {
+    pub processing_id: Uuid,
+    pub participant_id: Uuid,
+    pub challenge_id: i32,
+    pub waypoint_sequence: i32,
+    pub distance: u32, // Bits the perceptual hashes differ in
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ProofJobError {
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+    #[error("Proof not found")]
+    NotFound,
+    #[error("A proof for this waypoint is already being verified")]
+    AlreadyPending,
+    #[error("This proof is not waiting for review")]
+    NotInReview,
+    #[error("Another moderator is reviewing this proof")]
+    ClaimedByAnother,
+    #[error("There is no rejected photo to appeal for this waypoint")]
+    NothingToAppeal,
+    #[error("This photo was already appealed")]
+    AlreadyAppealed,
+    #[error("No appeals left for this waypoint, {0} allowed")]
+    AppealLimitReached(i32),
+}
+
+/// How long a claimed job stays invisible to other workers
+const CLAIM_LEASE_SECONDS: i64 = 60;
+/// How long a moderator's claim on a review keeps other moderators away
+const REVIEW_CLAIM_MINUTES: i64 = 15;
+
+impl ProofJob {
+    /// Queue a photo for verification, refused while another one for the same waypoint is
+    /// still pending
+    pub async fn create(
+        pool: &PgPool,
+        participant_id: Uuid,
+        challenge_id: i32,
+        waypoint_sequence: i32,
+        image_path: &str,
+        photo_metadata: &PhotoMetadata,
+    ) -> Result<ProofJob, ProofJobError> {
+        sqlx::query_as!(
+            ProofJob,
+            r#"
+            INSERT INTO proof_jobs (processing_id, participant_id, challenge_id, waypoint_sequence,
+                                    image_path, photo_metadata)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT DO NOTHING
+            RETURNING processing_id, participant_id, challenge_id, waypoint_sequence, image_path,
+                      status as "status: ProofJobStatus", reasons, attempts, created_at,
+                      completed_at
+            "#,
+            Uuid::new_v4(),
+            participant_id,
+            challenge_id,
+            waypoint_sequence,
+            image_path,
+            Json(photo_metadata) as _
+        )
+        .fetch_optional(pool)
+        .await?
+        .ok_or(ProofJobError::AlreadyPending)
+    }
+
+    /// Fails with `AlreadyPending` while a photo for the waypoint is still being verified or
+    /// waits for a moderator, the same jobs `create` is refused for
+    pub async fn ensure_none_pending(
+        pool: &PgPool,
+        participant_id: Uuid,
+        waypoint_sequence: i32,
+    ) -> Result<(), ProofJobError> {
+        let pending = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM proof_jobs
+                WHERE participant_id = $1 AND waypoint_sequence = $2
+                  AND status IN ('QUEUED', 'SUBMITTED', 'MANUAL_REVIEW')
+            ) as "pending!"
+            "#,
+            participant_id,
+            waypoint_sequence
+        )
+        .fetch_one(pool)
+        .await?;
+
+        if pending {
+            return Err(ProofJobError::AlreadyPending);
+        }
+        Ok(())
+    }
+
+    /// Record a photo that goes straight to a moderator instead of the image checker, refused
+    /// like `create` while another one for the same waypoint is still pending
+    pub async fn create_for_review(
+        pool: &PgPool,
+        participant_id: Uuid,
+        challenge_id: i32,
+        waypoint_sequence: i32,
+        image_path: &str,
+        photo_metadata: &PhotoMetadata,
+        reason: &str,
+    ) -> Result<ProofJob, ProofJobError> {
+        sqlx::query_as!(
+            ProofJob,
+            r#"
+            INSERT INTO proof_jobs (processing_id, participant_id, challenge_id, waypoint_sequence,
+                                    image_path, photo_metadata, status, reasons)
+            VALUES ($1, $2, $3, $4, $5, $6, 'MANUAL_REVIEW', $7)
+            ON CONFLICT DO NOTHING
+            RETURNING processing_id, participant_id, challenge_id, waypoint_sequence, image_path,
+                      status as "status: ProofJobStatus", reasons, attempts, created_at,
+                      completed_at
+            "#,
+            Uuid::new_v4(),
+            participant_id,
+            challenge_id,
+            waypoint_sequence,
+            image_path,
+            Json(photo_metadata) as _,
+            &[reason.to_string()]
+        )
+        .fetch_optional(pool)
+        .await?
+        .ok_or(ProofJobError::AlreadyPending)
+    }
+
+    /// Record a photo rejected before it reached the image checker, so it can be appealed
+    pub async fn create_rejected(
+        pool: &PgPool,
+        participant_id: Uuid,
+        challenge_id: i32,
+        waypoint_sequence: i32,
+        image_path: &str,
+        photo_metadata: &PhotoMetadata,
+        reasons: &[String],
+    ) -> Result<ProofJob, ProofJobError> {
+        let job = sqlx::query_as!(
+            ProofJob,
+            r#"
+            INSERT INTO proof_jobs (processing_id, participant_id, challenge_id, waypoint_sequence,
+                                    image_path, photo_metadata, status, reasons, completed_at)
+            VALUES ($1, $2, $3, $4, $5, $6, 'REJECTED', $7, NOW())
+            RETURNING processing_id, participant_id, challenge_id, waypoint_sequence, image_path,
+                      status as "status: ProofJobStatus", reasons, attempts, created_at,
+                      completed_at
+            "#,
+            Uuid::new_v4(),
+            participant_id,
+            challenge_id,
+            waypoint_sequence,
+            image_path,
+            Json(photo_metadata) as _,
+            reasons
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(job)
+    }
+
+    pub async fn get_by_id(pool: &PgPool, processing_id: Uuid) -> Result<ProofJob, ProofJobError> {
+        sqlx::query_as!(
+            ProofJob,
+            r#"
+            SELECT processing_id, participant_id, challenge_id, waypoint_sequence, image_path,
+                   status as "status: ProofJobStatus", reasons, attempts, created_at,
+                   completed_at
+            FROM proof_jobs
+            WHERE processing_id = $1
+            "#,
+            processing_id
+        )
+        .fetch_optional(pool)
+        .await?
+        .ok_or(ProofJobError::NotFound)
+    }
+
+    /// Claim up to `limit` pending jobs that are due. Claimed jobs are leased so concurrent
+    /// workers skip them, and picked up again after the lease if the worker dies.
+    pub async fn claim_due(pool: &PgPool, limit: i64) -> Result<Vec<ProofJob>, ProofJobError> {
+        let lease_until = Utc::now() + Duration::seconds(CLAIM_LEASE_SECONDS);
+
+        let jobs = sqlx::query_as!(
+            ProofJob,
+            r#"
+            UPDATE proof_jobs
+            SET attempts = attempts + 1, next_attempt_at = $2
+            WHERE processing_id IN (
+                SELECT processing_id
+                FROM proof_jobs
+                WHERE status IN ('QUEUED', 'SUBMITTED') AND next_attempt_at <= NOW()
+                ORDER BY next_attempt_at
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING processing_id, participant_id, challenge_id, waypoint_sequence, image_path,
+                      status as "status: ProofJobStatus", reasons, attempts, created_at,
+                      completed_at
+            "#,
+            limit,
+            lease_until
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(jobs)
+    }
+
+    /// Come back to a pending job at `next_attempt_at`, as `status`
+    pub async fn reschedule(
+        &self,
+        pool: &PgPool,
+        status: ProofJobStatus,
+        next_attempt_at: DateTime<Utc>,
+        error: Option<&str>,
+    ) -> Result<(), ProofJobError> {
+        sqlx::query!(
+            r#"
+            UPDATE proof_jobs
+            SET status = $2, next_attempt_at = $3, last_error = $4
+            WHERE processing_id = $1 AND status IN ('QUEUED', 'SUBMITTED')
+            "#,
+            self.processing_id,
+            status as ProofJobStatus,
+            next_attempt_at,
+            error
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Take a pending job away from the workers and leave the photo to a moderator
+    pub async fn hold_for_review(
+        &mut self,
+        pool: &PgPool,
+        reasons: &[String],
+    ) -> Result<bool, ProofJobError> {
+        let held = sqlx::query!(
+            r#"
+            UPDATE proof_jobs
+            SET status = 'MANUAL_REVIEW', reasons = $2
+            WHERE processing_id = $1 AND status IN ('QUEUED', 'SUBMITTED')
+            "#,
+            self.processing_id,
+            reasons
+        )
+        .execute(pool)
+        .await?
+        .rows_affected()
+            > 0;
+
+        if held {
+            self.status = ProofJobStatus::ManualReview;
+            self.reasons = reasons.to_vec();
+        }
+        Ok(held)
+    }
+
+    /// Record the outcome of a pending job. Returns false when the job was already finished,
+    /// so an outcome is only ever applied once.
+    pub async fn finish(
+        &mut self,
+        pool: &PgPool,
+        status: ProofJobStatus,
+        reasons: &[String],
+    ) -> Result<bool, ProofJobError> {
+        let finished = sqlx::query!(
+            r#"
+            UPDATE proof_jobs
+            SET status = $2, reasons = $3, completed_at = NOW()
+            WHERE processing_id = $1 AND status IN ('QUEUED', 'SUBMITTED')
+            RETURNING completed_at
+            "#,
+            self.processing_id,
+            status as ProofJobStatus,
+            reasons
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        match finished {
+            Some(row) => {
+                self.status = status;
+                self.reasons = reasons.to_vec();
+                self.completed_at = row.completed_at;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+impl ProofReview {
+    /// Photos of a challenge waiting for a moderator, oldest first
+    pub async fn list_pending(
+        pool: &PgPool,
+        challenge_id: i32,
+    ) -> Result<Vec<ProofReview>, ProofJobError> {
+        let reviews = sqlx::query_as!(
+            ProofReview,
+            r#"
+            SELECT processing_id, participant_id, challenge_id, waypoint_sequence, image_path,
+                   status as "status: ProofJobStatus", reasons,
+                   photo_metadata as "photo_metadata: Json<PhotoMetadata>", appeal_comment,
+                   reviewer_id, claimed_at, review_comment, reviewed_at, created_at
+            FROM proof_jobs
+            WHERE challenge_id = $1 AND status = 'MANUAL_REVIEW'
+            ORDER BY created_at
+            "#,
+            challenge_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(reviews)
+    }
+
+    pub async fn get_by_id(
+        pool: &PgPool,
+        processing_id: Uuid,
+    ) -> Result<ProofReview, ProofJobError> {
+        sqlx::query_as!(
+            ProofReview,
+            r#"
+            SELECT processing_id, participant_id, challenge_id, waypoint_sequence, image_path,
+                   status as "status: ProofJobStatus", reasons,
+                   photo_metadata as "photo_metadata: Json<PhotoMetadata>", appeal_comment,
+                   reviewer_id, claimed_at, review_comment, reviewed_at, created_at
+            FROM proof_jobs
+            WHERE processing_id = $1
+            "#,
+            processing_id
+        )
+        .fetch_optional(pool)
+        .await?
+        .ok_or(ProofJobError::NotFound)
+    }
+
+    /// Claim a held photo for `reviewer_id`. Claims by other moderators lapse after
+    /// REVIEW_CLAIM_MINUTES, so an abandoned review does not block the queue.
+    pub async fn claim(
+        pool: &PgPool,
+        processing_id: Uuid,
+        reviewer_id: i32,
+    ) -> Result<ProofReview, ProofJobError> {
+        let claimed = sqlx::query_as!(
+            ProofReview,
+            r#"
+            UPDATE proof_jobs
+            SET reviewer_id = $2, claimed_at = NOW()
+            WHERE processing_id = $1 AND status = 'MANUAL_REVIEW'
+              AND (reviewer_id IS NULL OR reviewer_id = $2 OR claimed_at < $3)
+            RETURNING processing_id, participant_id, challenge_id, waypoint_sequence, image_path,
+                      status as "status: ProofJobStatus", reasons,
+                      photo_metadata as "photo_metadata: Json<PhotoMetadata>", appeal_comment,
+                      reviewer_id, claimed_at, review_comment, reviewed_at, created_at
+            "#,
+            processing_id,
+            reviewer_id,
+            Utc::now() - Duration::minutes(REVIEW_CLAIM_MINUTES)
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        match claimed {
+            Some(review) => Ok(review),
+            None => Err(Self::get_by_id(pool, processing_id)
+                .await?
+                .unavailable_to(reviewer_id)),
+        }
+    }
+
+    /// Why a review could not be claimed or decided by `reviewer_id`
+    fn unavailable_to(&self, reviewer_id: i32) -> ProofJobError {
+        if self.status == ProofJobStatus::ManualReview && self.reviewer_id != Some(reviewer_id) {
+            ProofJobError::ClaimedByAnother
+        } else {
+            ProofJobError::NotInReview
+        }
+    }
+
+    /// Record a moderator's decision on a held photo, unless another moderator holds a live
+    /// claim on it or it was decided already
+    pub async fn decide(
+        &mut self,
+        pool: &PgPool,
+        reviewer_id: i32,
+        status: ProofJobStatus,
+        reasons: &[String],
+        comment: Option<&str>,
+    ) -> Result<(), ProofJobError> {
+        let decided = sqlx::query!(
+            r#"
+            UPDATE proof_jobs
+            SET status = $3, reasons = $4, review_comment = $5, reviewer_id = $2,
+                reviewed_at = NOW(), completed_at = NOW()
+            WHERE processing_id = $1 AND status = 'MANUAL_REVIEW'
+              AND (reviewer_id IS NULL OR reviewer_id = $2 OR claimed_at < $6)
+            RETURNING reviewed_at
+            "#,
+            self.processing_id,
+            reviewer_id,
+            status as ProofJobStatus,
+            reasons,
+            comment,
+            Utc::now() - Duration::minutes(REVIEW_CLAIM_MINUTES)
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        let Some(row) = decided else {
+            return Err(Self::get_by_id(pool, self.processing_id)
+                .await?
+                .unavailable_to(reviewer_id));
+        };
+        self.status = status;
+        self.reasons = reasons.to_vec();
+        self.reviewer_id = Some(reviewer_id);
+        self.review_comment = comment.map(|c| c.to_string());
+        self.reviewed_at = row.reviewed_at;
+
+        Ok(())
+    }
+
+    /// The proof job this review decides, as the workers know it
+    pub fn to_job(&self) -> ProofJob {
+        ProofJob {
+            processing_id: self.processing_id,
+            participant_id: self.participant_id,
+            challenge_id: self.challenge_id,
+            waypoint_sequence: self.waypoint_sequence,
+            image_path: self.image_path.clone(),
+            status: self.status,
+            reasons: self.reasons.clone(),
+            attempts: 0,
+            created_at: self.created_at,
+            completed_at: self.reviewed_at,
+        }
+    }
+}
+
+impl ProofAppeal {
+    /// Appeal the participant's latest photo for a waypoint, which must have been rejected
+    /// and not appealed before, and send it back to the moderators' review queue. At most
+    /// `limit` photos per waypoint can be appealed.
+    pub async fn file(
+        pool: &PgPool,
+        participant_id: Uuid,
+        waypoint_sequence: i32,
+        comment: &str,
+        limit: i32,
+    ) -> Result<ProofAppeal, ProofJobError> {
+        let mut tx = pool.begin().await?;
+
+        // Locking the latest photo serializes appeals for the waypoint
+        let latest = sqlx::query!(
+            r#"
+            SELECT processing_id, status as "status: ProofJobStatus", appealed_at
+            FROM proof_jobs
+            WHERE participant_id = $1 AND waypoint_sequence = $2
+            ORDER BY created_at DESC
+            LIMIT 1
+            FOR UPDATE
+            "#,
+            participant_id,
+            waypoint_sequence
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(ProofJobError::NothingToAppeal)?;
+
+        if latest.appealed_at.is_some() {
+            return Err(ProofJobError::AlreadyAppealed);
+        }
+        if latest.status != ProofJobStatus::Rejected {
+            return Err(ProofJobError::NothingToAppeal);
+        }
+
+        let appealed = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) as "count!"
+            FROM proof_jobs
+            WHERE participant_id = $1 AND waypoint_sequence = $2 AND appealed_at IS NOT NULL
+            "#,
+            participant_id,
+            waypoint_sequence
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if appealed >= i64::from(limit) {
+            return Err(ProofJobError::AppealLimitReached(limit));
+        }
+
+        // A moderator may have rejected it before, the appeal goes to whoever claims it next
+        let appeal = sqlx::query_as!(
+            ProofAppeal,
+            r#"
+            UPDATE proof_jobs
+            SET status = 'MANUAL_REVIEW', appeal_comment = $2, appealed_at = NOW(),
+                completed_at = NULL, reviewer_id = NULL, claimed_at = NULL,
+                review_comment = NULL, reviewed_at = NULL
+            WHERE processing_id = $1
+            RETURNING processing_id, participant_id, challenge_id, waypoint_sequence,
+                      appeal_comment as "comment!", appealed_at as "appealed_at!",
+                      status as "status: ProofJobStatus", reasons, review_comment, reviewed_at
+            "#,
+            latest.processing_id,
+            comment
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            // Another photo for the waypoint was held for review in the meantime
+            sqlx::Error::Database(ref db) if db.is_unique_violation() => {
+                ProofJobError::AlreadyPending
+            }
+            e => e.into(),
+        })?;
+
+        tx.commit().await?;
+        Ok(appeal)
+    }
+
+    /// Appeals the participant filed, oldest first
+    pub async fn list_for_participant(
+        pool: &PgPool,
+        participant_id: Uuid,
+    ) -> Result<Vec<ProofAppeal>, ProofJobError> {
+        let appeals = sqlx::query_as!(
+            ProofAppeal,
+            r#"
+            SELECT processing_id, participant_id, challenge_id, waypoint_sequence,
+                   appeal_comment as "comment!", appealed_at as "appealed_at!",
+                   status as "status: ProofJobStatus", reasons, review_comment, reviewed_at
+            FROM proof_jobs
+            WHERE participant_id = $1 AND appealed_at IS NOT NULL
+            ORDER BY appealed_at
+            "#,
+            participant_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(appeals)
+    }
+}
+
+impl PhotoFingerprint {
+    pub async fn record(
+        pool: &PgPool,
+        job: &ProofJob,
+        waypoint_location: &GeoLocation,
+        hash: PerceptualHash,
+    ) -> Result<(), ProofJobError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO photo_fingerprints (processing_id, participant_id, challenge_id,
+                                            waypoint_sequence, waypoint_lat, waypoint_lon,
+                                            perceptual_hash)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (processing_id) DO NOTHING
+            "#,
+            job.processing_id,
+            job.participant_id,
+            job.challenge_id,
+            job.waypoint_sequence,
+            waypoint_location.lat,
+            waypoint_location.lon,
+            hash.to_i64()
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Earlier photos `hash` is a near duplicate of: any submitted for the same waypoint of
+    /// this challenge, the participant's own only once accepted or rejected, and other
+    /// participants' photos for a waypoint of another challenge within `radius_meters` of
+    /// this one. Closest first.
+    pub async fn find_near_duplicates(
+        pool: &PgPool,
+        participant_id: Uuid,
+        challenge_id: i32,
+        waypoint_sequence: i32,
+        waypoint_location: &GeoLocation,
+        radius_meters: f64,
+        hash: PerceptualHash,
+    ) -> Result<Vec<DuplicatePhotoMatch>, ProofJobError> {
+        // Box around the waypoint to narrow down by index, refined by distance below
+        let lat_delta = radius_meters / 111_320.0;
+        let lon_delta = lat_delta / waypoint_location.lat.to_radians().cos().max(0.01);
+
+        let candidates = sqlx::query_as!(
+            PhotoFingerprint,
+            r#"
+            SELECT f.processing_id, f.participant_id, f.challenge_id, f.waypoint_sequence,
+                   f.waypoint_lat, f.waypoint_lon, f.perceptual_hash
+            FROM photo_fingerprints f
+            JOIN proof_jobs j ON j.processing_id = f.processing_id
+            WHERE bit_count(int8send(f.perceptual_hash # $2)) <= $3
+              AND ((f.challenge_id = $4 AND f.waypoint_sequence = $5
+                    -- A photo sent again after the checker failed on it is no duplicate
+                    AND (f.participant_id <> $1 OR j.status IN ('ACCEPTED', 'REJECTED')))
+                   OR (f.participant_id <> $1 AND f.challenge_id <> $4
+                       AND f.waypoint_lat BETWEEN $6 AND $7
+                       AND f.waypoint_lon BETWEEN $8 AND $9))
+            "#,
+            participant_id,
+            hash.to_i64(),
+            i64::from(NEAR_DUPLICATE_MAX_DISTANCE),
+            challenge_id,
+            waypoint_sequence,
+            waypoint_location.lat - lat_delta,
+            waypoint_location.lat + lat_delta,
+            waypoint_location.lon - lon_delta,
+            waypoint_location.lon + lon_delta
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let mut matches: Vec<DuplicatePhotoMatch> = candidates
+            .into_iter()
+            .filter(|candidate| {
+                candidate.challenge_id == challenge_id
+                    || LocationService::haversine_distance(
+                        waypoint_location,
+                        &GeoLocation {
+                            lat: candidate.waypoint_lat,
+                            lon: candidate.waypoint_lon,
+                        },
+                    ) <= radius_meters
+            })
+            .map(|candidate| DuplicatePhotoMatch {
+                processing_id: candidate.processing_id,
+                participant_id: candidate.participant_id,
+                challenge_id: candidate.challenge_id,
+                waypoint_sequence: candidate.waypoint_sequence,
+                distance: hash.distance(PerceptualHash::from_i64(candidate.perceptual_hash)),
+            })
+            .collect();
+        matches.sort_by_key(|m| m.distance);
+
+        Ok(matches)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proof_job_serialization() {
+        let job = ProofJob {
+            processing_id: Uuid::nil(),
+            participant_id: Uuid::nil(),
+            challenge_id: 7,
+            waypoint_sequence: 2,
+            image_path: "7/p/abc.jpg".to_string(),
+            status: ProofJobStatus::Submitted,
+            reasons: vec![],
+            attempts: 3,
+            created_at: Utc::now(),
+            completed_at: None,
+        };
+
+        let json = serde_json::to_value(&job).unwrap();
+        assert_eq!(json["status"], "SUBMITTED");
+        assert_eq!(json["waypoint-id"], 2);
+        assert!(json.get("image_path").is_none());
+        assert!(json.get("attempts").is_none());
+        assert_eq!(json["reasons"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn test_proof_appeal_serialization() {
+        let appeal = ProofAppeal {
+            processing_id: Uuid::nil(),
+            participant_id: Uuid::nil(),
+            challenge_id: 7,
+            waypoint_sequence: 2,
+            comment: "The bench is behind the tree".to_string(),
+            appealed_at: Utc::now(),
+            status: ProofJobStatus::ManualReview,
+            reasons: vec!["No bench in the photo".to_string()],
+            review_comment: None,
+            reviewed_at: None,
+        };
+
+        let json = serde_json::to_value(&appeal).unwrap();
+        assert_eq!(json["status"], "MANUAL_REVIEW");
+        assert_eq!(json["waypoint-id"], 2);
+        assert_eq!(json["comment"], "The bench is behind the tree");
+        assert!(json.get("appealed-at").is_some());
+        assert!(json.get("participant_id").is_none());
+        assert!(json.get("challenge_id").is_none());
+    }
+}
@@ -197,6 +197,20 @@ impl User {
         }
     }
 
+    pub async fn get_by_id(pool: &PgPool, user_id: i32) -> Result<User, UserError> {
+        sqlx::query_as!(
+            User,
+            r#"SELECT user_id, username, password, nickname,
+                     COALESCE(creation_date, NOW()) as "creation_date!",
+                     COALESCE(updated_at, NOW()) as "updated_at!"
+               FROM users WHERE user_id = $1"#,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await?
+        .ok_or(UserError::UserNotFound)
+    }
+
     pub async fn get_user_roles(&self, pool: &PgPool) -> Result<Vec<UserRole>, UserError> {
         let roles = sqlx::query!(
             "SELECT role_name FROM user_roles WHERE user_id = $1",
@@ -211,6 +225,22 @@ impl User {
             .collect())
     }
 
+    /// Grant a role to a user, granting a role the user already has is a no-op
+    pub async fn grant_role(pool: &PgPool, user_id: i32, role: UserRole) -> Result<(), UserError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO user_roles (user_id, role_name) VALUES ($1, $2)
+            ON CONFLICT (user_id, role_name) DO NOTHING
+            "#,
+            user_id,
+            role.to_string()
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
     #[allow(dead_code)]
     pub async fn to_response(&self, pool: &PgPool) -> Result<UserResponse, UserError> {
         let roles = self.get_user_roles(pool).await?;
@@ -248,7 +278,7 @@ impl User {
         }
     }
 
-    fn is_valid_email(email: &str) -> bool {
+    pub(crate) fn is_valid_email(email: &str) -> bool {
         // Simple email validation
         if email.len() <= 5 {
             return false;
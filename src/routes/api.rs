@@ -1,6 +1,7 @@
 use axum::{
+    extract::DefaultBodyLimit,
     middleware,
-    routing::{get, post},
+    routing::{delete, get, post},
     Router,
 };
 use tower::ServiceBuilder;
@@ -11,25 +12,40 @@ use tower_http::{
 
 use crate::auth::jwt_middleware;
 use crate::handlers::{
-    create_challenge,
-    create_participant_token,
-    get_challenge,
-    health_check_handler,
-    invite_participant,
-    login_user,
-    register_user,
-    start_challenge,
-    // TODO: Re-enable waypoint handlers after updating for temporal challenge system
-    // check_in_waypoint, submit_waypoint_proof,
+    accept_invitation, accept_my_invitation, add_co_moderator, appeal_waypoint_proof,
+    approve_proof_review, begin_challenge, check_in_waypoint, claim_proof_review, create_challenge,
+    create_group, create_invitations, create_participant_token, decline_invitation,
+    decline_my_invitation, disqualify_participant, extend_challenge, forfeit_challenge,
+    get_challenge, get_challenge_standings, get_invitation, get_my_group, get_participant_inbox,
+    get_participant_report, get_proof_review_image, get_proof_status, health_check_handler,
+    image_checker_callback, invite_participant, join_group, leave_group,
+    list_challenge_invitations, list_devices, list_groups, list_moderators, list_my_invitations,
+    list_proof_reviews, login_user, mark_inbox_read, override_waypoint, pause_challenge,
+    register_device, register_user, reinstate_participant, reject_proof_review, release_hint,
+    remove_co_moderator, remove_group_member, resume_challenge, revoke_invitation,
+    send_announcement, start_challenge, stream_challenge_events, submit_waypoint_proof,
+    unregister_device,
 };
 use crate::routes::AppState;
+use crate::services::image_upload::MULTIPART_OVERHEAD_BYTES;
 
 pub fn create_api_router(state: AppState) -> Router {
     // Public routes (no authentication required)
     let public_routes = Router::new()
         .route("/health", get(health_check_handler))
         .route("/authentication/login", post(login_user))
-        .route("/authentication/register", post(register_user));
+        .route("/authentication/register", post(register_user))
+        .route("/invitations/:token", get(get_invitation))
+        // Signed with the shared callback secret, verified in the handler
+        .route(
+            "/internal/image-checker/callback",
+            post(image_checker_callback),
+        )
+        // Accepts participant or user tokens, authenticated in the handler
+        .route(
+            "/challenges/:challenge_id/events",
+            get(stream_challenge_events),
+        );
 
     // Protected routes (require user authentication)
     let protected_user_routes = Router::new()
@@ -37,37 +53,140 @@ pub fn create_api_router(state: AppState) -> Router {
         .route("/challenges", post(create_challenge))
         .route("/challenges/:challenge_id", get(get_challenge))
         .route("/challenges/start", post(start_challenge))
+        .route("/challenges/:challenge_id/begin", post(begin_challenge))
+        .route("/challenges/:challenge_id/pause", post(pause_challenge))
+        .route("/challenges/:challenge_id/resume", post(resume_challenge))
+        .route("/challenges/:challenge_id/extend", post(extend_challenge))
+        .route(
+            "/challenges/:challenge_id/moderators",
+            get(list_moderators),
+        )
+        .route(
+            "/challenges/:challenge_id/moderators/:user_id",
+            post(add_co_moderator).delete(remove_co_moderator),
+        )
         .route(
             "/challenges/:challenge_id/invite/:user_id",
             post(invite_participant),
         )
+        .route(
+            "/challenges/:challenge_id/invitations",
+            post(create_invitations).get(list_challenge_invitations),
+        )
+        .route(
+            "/challenges/:challenge_id/invitations/:invitation_id",
+            delete(revoke_invitation),
+        )
+        .route(
+            "/challenges/:challenge_id/groups",
+            post(create_group).get(list_groups),
+        )
+        .route("/challenges/:challenge_id/groups/join", post(join_group))
+        .route("/challenges/:challenge_id/groups/leave", post(leave_group))
+        .route("/challenges/:challenge_id/groups/mine", get(get_my_group))
+        .route(
+            "/challenges/:challenge_id/groups/members/:user_id",
+            delete(remove_group_member),
+        )
+        .route("/invitations/accept", post(accept_invitation))
+        .route("/invitations/decline", post(decline_invitation))
+        .route("/users/invitations", get(list_my_invitations))
+        .route(
+            "/users/invitations/:invitation_id/accept",
+            post(accept_my_invitation),
+        )
+        .route(
+            "/users/invitations/:invitation_id/decline",
+            post(decline_my_invitation),
+        )
+        .route(
+            "/challenges/:challenge_id/standings",
+            get(get_challenge_standings),
+        )
+        .route(
+            "/challenges/:challenge_id/participants/:participant_id/disqualify",
+            post(disqualify_participant),
+        )
+        .route(
+            "/challenges/:challenge_id/participants/:participant_id/reinstate",
+            post(reinstate_participant),
+        )
+        .route(
+            "/challenges/:challenge_id/participants/:participant_id/waypoints/:waypoint_id/override",
+            post(override_waypoint),
+        )
+        .route(
+            "/challenges/:challenge_id/reviews",
+            get(list_proof_reviews),
+        )
+        .route(
+            "/challenges/:challenge_id/reviews/:processing_id/image",
+            get(get_proof_review_image),
+        )
+        .route(
+            "/challenges/:challenge_id/reviews/:processing_id/claim",
+            post(claim_proof_review),
+        )
+        .route(
+            "/challenges/:challenge_id/reviews/:processing_id/approve",
+            post(approve_proof_review),
+        )
+        .route(
+            "/challenges/:challenge_id/reviews/:processing_id/reject",
+            post(reject_proof_review),
+        )
+        .route("/challenges/:challenge_id/hints", post(release_hint))
+        .route(
+            "/challenges/:challenge_id/announcements",
+            post(send_announcement),
+        )
+        .route("/users/devices", post(register_device).get(list_devices))
+        .route("/users/devices/:device_id", delete(unregister_device))
         .layer(middleware::from_fn_with_state(
             state.auth_state.clone(),
             jwt_middleware,
         ));
 
-    // TODO: Re-enable waypoint routes after updating for temporal challenge system
     // Protected participant routes (require participant authentication)
-    // let protected_participant_routes = Router::new()
-    //     .route(
-    //         "/challenges/waypoints/:waypoint_id/checkin",
-    //         post(check_in_waypoint),
-    //     )
-    //     .route(
-    //         "/challenges/waypoints/:waypoint_id/proof",
-    //         post(submit_waypoint_proof),
-    //     )
-    //     .layer(middleware::from_fn_with_state(
-    //         state.auth_state.clone(),
-    //         jwt_middleware,
-    //     ));
+    let protected_participant_routes = Router::new()
+        .route("/challenges/participant/inbox", get(get_participant_inbox))
+        .route("/challenges/participant/inbox/read", post(mark_inbox_read))
+        .route("/challenges/participant/forfeit", post(forfeit_challenge))
+        .route("/challenges/participant/full", get(get_participant_report))
+        .layer(middleware::from_fn_with_state(
+            state.auth_state.clone(),
+            jwt_middleware,
+        ));
+
+    // Proof uploads are held to the image size limit while streaming, the body limit only
+    // has to let them through
+    let proof_body_limit = state.image_uploader.max_bytes() + MULTIPART_OVERHEAD_BYTES;
+
+    let protected_waypoint_routes = Router::new()
+        .route(
+            "/challenges/waypoints/:waypoint_id/checkin",
+            post(check_in_waypoint),
+        )
+        .route(
+            "/challenges/waypoints/:waypoint_id/proof",
+            post(submit_waypoint_proof).layer(DefaultBodyLimit::max(proof_body_limit)),
+        )
+        .route(
+            "/challenges/waypoints/:waypoint_id/appeal",
+            post(appeal_waypoint_proof),
+        )
+        .route("/proofs/:processing_id", get(get_proof_status))
+        .layer(middleware::from_fn_with_state(
+            state.auth_state.clone(),
+            jwt_middleware,
+        ));
 
     // Combine all routes
     let api_routes = Router::new()
         .merge(public_routes)
         .merge(protected_user_routes)
-        // TODO: Re-enable after updating waypoint handlers
-        // .merge(protected_participant_routes)
+        .merge(protected_participant_routes)
+        .merge(protected_waypoint_routes)
         .with_state(state);
 
     // Apply middleware
@@ -2,7 +2,10 @@ pub mod api;
 
 use crate::auth::AuthState;
 use crate::db::DatabasePool;
-use crate::services::{AuthService, ImageService, LocationService};
+use crate::services::{
+    AuthService, ChallengeEventHub, ImageUploader, LocationService, Mailer, NotificationService,
+    ProofJobService,
+};
 use std::sync::Arc;
 
 #[derive(Clone)]
@@ -10,7 +13,13 @@ pub struct AppState {
     pub pool: DatabasePool,
     pub auth_service: Arc<AuthService>,
     pub location_service: Arc<LocationService>,
-    pub image_service: Arc<ImageService>,
+    pub proof_jobs: Arc<ProofJobService>,
+    pub image_uploader: Arc<ImageUploader>,
+    pub event_hub: Arc<ChallengeEventHub>,
+    pub notification_service: Arc<NotificationService>,
+    pub mailer: Arc<dyn Mailer>,
+    pub app_base_url: String,
+    pub image_checker_callback_secret: Option<String>,
     pub auth_state: AuthState,
 }
 
@@ -4,6 +4,10 @@ use sqlx::PgPool;
 use std::sync::Arc;
 
 use crate::auth::{AuthError, JwtService};
+use crate::models::challenge::{
+    ChallengeError, ChallengePacing, ChallengeParticipant, ParticipantStatus, TemporalChallenge,
+};
+use crate::models::invitation::ChallengeInvitation;
 use crate::models::user::{CreateUserRequest, LoginRequest, User, UserError};
 
 #[derive(Debug, Clone, Serialize)]
@@ -40,6 +44,12 @@ pub enum AuthServiceError {
     UserNotInvited,
     #[error("Challenge not active")]
     ChallengeNotActive,
+    #[error("Participant has not started the self-paced challenge")]
+    ParticipantNotStarted,
+    #[error("Participant is {0}")]
+    ParticipantNotActive(ParticipantStatus),
+    #[error("Challenge error: {0}")]
+    ChallengeError(#[from] ChallengeError),
     #[error("Invalid request: {0}")]
     #[allow(dead_code)]
     InvalidRequest(String),
@@ -71,6 +81,19 @@ impl AuthService {
         )
         .await?;
 
+        // Link invitations sent to this address before the user registered
+        match ChallengeInvitation::link_pending_to_user(&self.pool, user.user_id, &user.username)
+            .await
+        {
+            Ok(0) => {}
+            Ok(linked) => tracing::info!(
+                "Linked {} pending invitations to new user {}",
+                linked,
+                user.user_id
+            ),
+            Err(e) => tracing::warn!("Failed to link pending invitations: {}", e),
+        }
+
         // Get user roles
         let roles = user.get_user_roles(&self.pool).await?;
         let role_strings: Vec<String> = roles.into_iter().map(|r| r.to_string()).collect();
@@ -115,43 +138,50 @@ impl AuthService {
         user_id: i32,
         challenge_id: i32, // Now integer for temporal challenges
     ) -> Result<ParticipantAuthResponse, AuthServiceError> {
-        // Check if user is invited to the challenge
-        let participant = sqlx::query!(
-            r#"
-            SELECT participant_id, participant_nickname 
-            FROM challenge_participants 
-            WHERE challenge_id = $1 AND user_id = $2
-            "#,
-            challenge_id,
-            user_id
-        )
-        .fetch_optional(&self.pool)
-        .await?
-        .ok_or(AuthServiceError::UserNotInvited)?;
-
-        // Get challenge details from temporal_challenges
-        let challenge = sqlx::query!(
-            r#"
-            SELECT challenge_id, planned_start_time, 
-                   (challenge->>'duration_minutes')::integer as duration_minutes,
-                   (challenge->>'active')::boolean as active
-            FROM temporal_challenges 
-            WHERE challenge_id = $1 AND end_at IS NULL
-            "#,
-            challenge_id
-        )
-        .fetch_optional(&self.pool)
-        .await?
-        .ok_or(AuthServiceError::ChallengeNotFound)?;
+        // Only users who accepted their invitation have a participant in the challenge;
+        // group members share the participant of their group
+        let participant =
+            ChallengeParticipant::get_accepted_for_user(&self.pool, challenge_id, user_id)
+                .await?
+                .ok_or(AuthServiceError::UserNotInvited)?;
+
+        if participant.participant_status != ParticipantStatus::Active {
+            return Err(AuthServiceError::ParticipantNotActive(
+                participant.participant_status,
+            ));
+        }
 
-        if !challenge.active.unwrap_or(false) {
+        let (challenge, challenge_data) =
+            TemporalChallenge::get_current_by_id(&self.pool, challenge_id)
+                .await
+                .and_then(|challenge| {
+                    let challenge_data = challenge.get_challenge_data()?;
+                    Ok((challenge, challenge_data))
+                })
+                .map_err(|e| match e {
+                    ChallengeError::ChallengeNotFound => AuthServiceError::ChallengeNotFound,
+                    e => e.into(),
+                })?;
+
+        if !challenge_data.active {
             return Err(AuthServiceError::ChallengeNotActive);
         }
 
-        // Calculate challenge end time
-        let planned_start = challenge.planned_start_time;
-        let duration = challenge.duration_minutes.unwrap_or(60); // Default 60 minutes if null
-        let challenge_end_time = planned_start + chrono::Duration::minutes(duration as i64);
+        // Calculate challenge end time, pushed back by pauses; self-paced participants run
+        // on their own deadline
+        let now = Utc::now();
+        let challenge_end_time = match challenge_data.pacing {
+            ChallengePacing::Synchronized => challenge_data.end_time_at(now).unwrap_or_else(|| {
+                challenge.planned_start_time
+                    + chrono::Duration::minutes(challenge_data.duration_minutes as i64)
+            }),
+            ChallengePacing::SelfPaced => challenge_data.participant_deadline_at(
+                participant
+                    .deadline_at
+                    .ok_or(AuthServiceError::ParticipantNotStarted)?,
+                now,
+            ),
+        };
 
         // Get user roles
         let user = sqlx::query_as!(
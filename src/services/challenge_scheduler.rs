@@ -0,0 +1,195 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::models::challenge::{
+    ChallengeData, ChallengeError, ChallengeParticipant, TemporalChallenge,
+};
+use crate::services::event_hub::{ChallengeEvent, ChallengeEventHub, ChallengeEventType};
+use crate::services::notification_service::NotificationService;
+
+/// Starts auto-start challenges once their planned start time has passed, unless their
+/// planned end has passed too
+pub struct ChallengeScheduler {
+    pool: PgPool,
+    event_hub: Arc<ChallengeEventHub>,
+    notification_service: Arc<NotificationService>,
+}
+
+impl ChallengeScheduler {
+    pub fn new(
+        pool: PgPool,
+        event_hub: Arc<ChallengeEventHub>,
+        notification_service: Arc<NotificationService>,
+    ) -> Self {
+        Self {
+            pool,
+            event_hub,
+            notification_service,
+        }
+    }
+
+    /// Start every due challenge, returning how many this server started. Challenges
+    /// started concurrently by a moderator or another replica are skipped
+    pub async fn start_due(&self) -> Result<usize, ChallengeError> {
+        let due = TemporalChallenge::get_due_for_auto_start(&self.pool).await?;
+        let mut started = 0;
+
+        for challenge in due {
+            let challenge_data = match challenge.get_challenge_data() {
+                Ok(data) => data,
+                Err(e) => {
+                    tracing::error!(
+                        "Skipping auto-start of challenge {}: {}",
+                        challenge.challenge_id,
+                        e
+                    );
+                    continue;
+                }
+            };
+
+            match challenge
+                .start_challenge(&self.pool, challenge_data.challenge_moderator)
+                .await
+            {
+                Ok(started_challenge) => {
+                    let started_data = started_challenge.get_challenge_data()?;
+                    if let Some(lateness) = start_lateness(
+                        started_challenge.planned_start_time,
+                        started_data.actual_start_time,
+                    ) {
+                        tracing::warn!(
+                            "Challenge {} auto-started {} minutes after its planned start",
+                            started_challenge.challenge_id,
+                            lateness.num_minutes()
+                        );
+                    }
+
+                    announce_challenge_start(
+                        &self.pool,
+                        &self.event_hub,
+                        &self.notification_service,
+                        &started_challenge,
+                        &started_data,
+                    )
+                    .await;
+
+                    tracing::info!("Challenge auto-started: {}", started_challenge.challenge_id);
+                    started += 1;
+                }
+                Err(ChallengeError::ChallengeAlreadyStarted) => {
+                    tracing::debug!(
+                        "Challenge {} was started elsewhere, skipping",
+                        challenge.challenge_id
+                    );
+                }
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to auto-start challenge {}: {}",
+                        challenge.challenge_id,
+                        e
+                    );
+                }
+            }
+        }
+
+        Ok(started)
+    }
+
+    /// Scheduler loop, the first pass runs immediately so starts missed while the server
+    /// was down are caught up on boot
+    pub async fn run(self: Arc<Self>, poll_interval: Duration) {
+        tracing::info!(
+            "Challenge auto-start scheduler started, polling every {}s",
+            poll_interval.as_secs()
+        );
+
+        loop {
+            if let Err(e) = self.start_due().await {
+                tracing::error!("Challenge auto-start pass failed with error: {}", e);
+            }
+
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+}
+
+/// Tell everyone playing that a challenge has started: an event on the challenge stream
+/// and a push notification with the first clue
+pub async fn announce_challenge_start(
+    pool: &PgPool,
+    event_hub: &ChallengeEventHub,
+    notification_service: &NotificationService,
+    started_challenge: &TemporalChallenge,
+    challenge_data: &ChallengeData,
+) {
+    let first_waypoint = challenge_data.starting_waypoint().and_then(|sequence| {
+        challenge_data
+            .waypoints
+            .iter()
+            .find(|w| w.waypoint_sequence == sequence)
+    });
+    event_hub.publish(
+        ChallengeEvent::new(
+            ChallengeEventType::ChallengeStarted,
+            started_challenge.challenge_id,
+            serde_json::json!({
+                "actual-start-time": challenge_data.actual_start_time,
+                "duration": challenge_data.duration_minutes,
+            }),
+        )
+        .with_waypoint(first_waypoint.map(|w| w.waypoint_sequence)),
+    );
+
+    // Push the first clue to the registered devices of everyone playing, group members
+    // included. Routes without a fixed first waypoint leave the choice to participants.
+    let body = first_waypoint.map_or("Choose your first waypoint", |w| &w.waypoint_clue);
+    let user_ids =
+        ChallengeParticipant::get_user_ids_for_challenge(pool, started_challenge.challenge_id)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to get participant users: {}", e);
+                Vec::new()
+            });
+    if let Err(e) = notification_service
+        .notify_users(
+            &user_ids,
+            Some(started_challenge.challenge_id),
+            &format!("{} has started", started_challenge.challenge_name),
+            body,
+            serde_json::json!({
+                "challenge-id": started_challenge.challenge_id,
+                "waypoint-id": first_waypoint.map(|w| w.waypoint_sequence),
+            }),
+        )
+        .await
+    {
+        tracing::warn!("Failed to queue challenge start notifications: {}", e);
+    }
+}
+
+/// How late a start was, when it was noticeably later than planned (a catch-up start)
+fn start_lateness(
+    planned_start_time: DateTime<Utc>,
+    actual_start_time: Option<DateTime<Utc>>,
+) -> Option<chrono::Duration> {
+    let lateness = actual_start_time? - planned_start_time;
+    (lateness > chrono::Duration::minutes(5)).then_some(lateness)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_start_lateness() {
+        let planned = Utc::now();
+
+        assert!(start_lateness(planned, None).is_none());
+        assert!(start_lateness(planned, Some(planned + chrono::Duration::seconds(20))).is_none());
+
+        let late = start_lateness(planned, Some(planned + chrono::Duration::hours(2))).unwrap();
+        assert_eq!(late.num_minutes(), 120);
+    }
+}
@@ -0,0 +1,189 @@
+use axum::async_trait;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+use crate::config::SmtpSecurity;
+
+/// A plain-text email ready to be delivered
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutgoingEmail {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum MailerError {
+    #[error("Invalid address: {0}")]
+    InvalidAddress(String),
+    #[error("Invalid SMTP relay: {0}")]
+    InvalidRelay(lettre::transport::smtp::Error),
+    #[error("SMTP credentials are only sent over TLS")]
+    CleartextCredentials,
+    #[error("Failed to build email: {0}")]
+    BuildFailed(#[from] lettre::error::Error),
+    #[error("SMTP delivery failed: {0}")]
+    DeliveryFailed(#[from] lettre::transport::smtp::Error),
+}
+
+/// An outgoing email transport
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, email: &OutgoingEmail) -> Result<(), MailerError>;
+}
+
+/// Delivers email through an SMTP relay over TLS, or unencrypted to a local catch-all server
+/// in development
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    pub fn new(
+        host: &str,
+        port: u16,
+        security: SmtpSecurity,
+        from: &str,
+        credentials: Option<(String, String)>,
+    ) -> Result<Self, MailerError> {
+        let from = from
+            .parse::<Mailbox>()
+            .map_err(|_| MailerError::InvalidAddress(from.to_string()))?;
+
+        let builder = match security {
+            SmtpSecurity::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+                .map_err(MailerError::InvalidRelay)?,
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host)
+                .map_err(MailerError::InvalidRelay)?,
+            SmtpSecurity::None if credentials.is_some() => {
+                return Err(MailerError::CleartextCredentials);
+            }
+            SmtpSecurity::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+        };
+        let mut builder = builder.port(port);
+        if let Some((username, password)) = credentials {
+            builder = builder.credentials(Credentials::new(username, password));
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            from,
+        })
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, email: &OutgoingEmail) -> Result<(), MailerError> {
+        let to = email
+            .to
+            .parse::<Mailbox>()
+            .map_err(|_| MailerError::InvalidAddress(email.to.clone()))?;
+
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(email.subject.clone())
+            .body(email.body.clone())?;
+
+        self.transport.send(message).await?;
+
+        Ok(())
+    }
+}
+
+/// Compose the invitation email. Registered users get a link to accept the invitation,
+/// unknown addresses are asked to register first.
+pub fn invitation_email(
+    to: &str,
+    challenge_name: &str,
+    app_base_url: &str,
+    token: &str,
+    registered: bool,
+) -> OutgoingEmail {
+    let base_url = app_base_url.trim_end_matches('/');
+
+    let body = if registered {
+        format!(
+            "You have been invited to take part in the scavenger hunt \"{challenge_name}\".\n\n\
+             Open the invitation in the app to accept or decline it:\n\
+             {base_url}/invitations/{token}\n"
+        )
+    } else {
+        format!(
+            "You have been invited to take part in the scavenger hunt \"{challenge_name}\".\n\n\
+             To join, download the app and register with this email address:\n\
+             {base_url}/register?invitation={token}\n\n\
+             Your invitation will be waiting for you once you have registered.\n"
+        )
+    };
+
+    OutgoingEmail {
+        to: to.to_string(),
+        subject: format!("Invitation to {challenge_name}"),
+        body,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invitation_email_links() {
+        let email = invitation_email(
+            "a@example.com",
+            "Park Hunt",
+            "https://hunt.example.com/",
+            "tok",
+            true,
+        );
+        assert_eq!(email.subject, "Invitation to Park Hunt");
+        assert!(email
+            .body
+            .contains("https://hunt.example.com/invitations/tok"));
+
+        let email = invitation_email(
+            "a@example.com",
+            "Park Hunt",
+            "https://hunt.example.com",
+            "tok",
+            false,
+        );
+        assert!(email
+            .body
+            .contains("https://hunt.example.com/register?invitation=tok"));
+    }
+
+    #[test]
+    fn test_smtp_mailer_rejects_invalid_sender() {
+        assert!(matches!(
+            SmtpMailer::new(
+                "localhost",
+                1025,
+                SmtpSecurity::None,
+                "not an address",
+                None
+            ),
+            Err(MailerError::InvalidAddress(_))
+        ));
+    }
+
+    #[test]
+    fn test_smtp_credentials_only_sent_over_tls() {
+        let mailer = |security, credentials| {
+            SmtpMailer::new("localhost", 587, security, "noreply@localhost", credentials)
+        };
+        let credentials = || Some(("user".to_string(), "secret".to_string()));
+
+        assert!(matches!(
+            mailer(SmtpSecurity::None, credentials()),
+            Err(MailerError::CleartextCredentials)
+        ));
+        assert!(mailer(SmtpSecurity::None, None).is_ok());
+        assert!(mailer(SmtpSecurity::StartTls, credentials()).is_ok());
+        assert!(mailer(SmtpSecurity::Tls, credentials()).is_ok());
+    }
+}
@@ -0,0 +1,300 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::models::challenge::{ParticipantStatus, WaypointState};
+use crate::models::proof_job::{ProofAppeal, ProofJob};
+
+/// Events buffered per challenge before slow subscribers start lagging
+const DEFAULT_CHANNEL_CAPACITY: usize = 64;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChallengeEventType {
+    ChallengeStarted,
+    ChallengePaused,
+    ChallengeResumed,
+    ChallengeExtended,
+    ParticipantStatusChanged,
+    WaypointStateChanged,
+    ProofVerified,
+    ProofAppealed,
+    Hint,
+    Announcement,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChallengeEvent {
+    #[serde(rename = "event-type")]
+    pub event_type: ChallengeEventType,
+    #[serde(rename = "challenge-id")]
+    pub challenge_id: i32,
+    #[serde(rename = "waypoint-id", skip_serializing_if = "Option::is_none")]
+    pub waypoint_sequence: Option<i32>,
+    pub data: serde_json::Value,
+    #[serde(rename = "occurred-at")]
+    pub occurred_at: DateTime<Utc>,
+    /// Participants the event is addressed to, None for a broadcast
+    #[serde(skip)]
+    pub recipients: Option<Vec<Uuid>>,
+}
+
+impl ChallengeEvent {
+    pub fn new(event_type: ChallengeEventType, challenge_id: i32, data: serde_json::Value) -> Self {
+        Self {
+            event_type,
+            challenge_id,
+            waypoint_sequence: None,
+            data,
+            occurred_at: Utc::now(),
+            recipients: None,
+        }
+    }
+
+    pub fn with_waypoint(mut self, waypoint_sequence: Option<i32>) -> Self {
+        self.waypoint_sequence = waypoint_sequence;
+        self
+    }
+
+    pub fn with_recipients(mut self, recipients: Vec<Uuid>) -> Self {
+        self.recipients = Some(recipients);
+        self
+    }
+
+    /// A participant moved to a new waypoint state (check-in, verification, override)
+    pub fn waypoint_state_changed(
+        challenge_id: i32,
+        participant_id: Uuid,
+        waypoint_sequence: i32,
+        state: WaypointState,
+    ) -> Self {
+        Self::new(
+            ChallengeEventType::WaypointStateChanged,
+            challenge_id,
+            serde_json::json!({
+                "participant-id": participant_id,
+                "state": state,
+            }),
+        )
+        .with_waypoint(Some(waypoint_sequence))
+        .with_recipients(vec![participant_id])
+    }
+
+    /// A participant forfeited, was disqualified or reinstated
+    pub fn participant_status_changed(
+        challenge_id: i32,
+        participant_id: Uuid,
+        status: ParticipantStatus,
+        reason: Option<&str>,
+    ) -> Self {
+        Self::new(
+            ChallengeEventType::ParticipantStatusChanged,
+            challenge_id,
+            serde_json::json!({
+                "participant-id": participant_id,
+                "status": status,
+                "reason": reason,
+            }),
+        )
+        .with_recipients(vec![participant_id])
+    }
+
+    /// A photo proof verified in the background reached its outcome
+    pub fn proof_verified(job: &ProofJob) -> Self {
+        Self::new(
+            ChallengeEventType::ProofVerified,
+            job.challenge_id,
+            serde_json::json!({
+                "participant-id": job.participant_id,
+                "processing-id": job.processing_id,
+                "status": job.status,
+                "reasons": job.reasons,
+            }),
+        )
+        .with_waypoint(Some(job.waypoint_sequence))
+        .with_recipients(vec![job.participant_id])
+    }
+
+    /// A participant appealed a rejected photo, which went back to the moderators
+    pub fn proof_appealed(appeal: &ProofAppeal) -> Self {
+        Self::new(
+            ChallengeEventType::ProofAppealed,
+            appeal.challenge_id,
+            serde_json::json!({
+                "participant-id": appeal.participant_id,
+                "processing-id": appeal.processing_id,
+                "status": appeal.status,
+                "comment": appeal.comment,
+            }),
+        )
+        .with_waypoint(Some(appeal.waypoint_sequence))
+        .with_recipients(vec![appeal.participant_id])
+    }
+
+    /// Whether a participant subscriber should receive this event
+    pub fn is_visible_to(&self, participant_id: Uuid) -> bool {
+        match &self.recipients {
+            Some(recipients) => recipients.contains(&participant_id),
+            None => true,
+        }
+    }
+
+    /// Whether this event takes the participant out of the game, ending their event stream
+    pub fn removes_participant(&self, participant_id: Uuid) -> bool {
+        self.event_type == ChallengeEventType::ParticipantStatusChanged
+            && self.is_visible_to(participant_id)
+            && self.data["status"] != serde_json::json!(ParticipantStatus::Active)
+    }
+}
+
+/// In-process fan-out of challenge events, one broadcast channel per challenge
+pub struct ChallengeEventHub {
+    channels: Mutex<HashMap<i32, broadcast::Sender<ChallengeEvent>>>,
+    capacity: usize,
+}
+
+impl Default for ChallengeEventHub {
+    fn default() -> Self {
+        Self::new(DEFAULT_CHANNEL_CAPACITY)
+    }
+}
+
+impl ChallengeEventHub {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            channels: Mutex::new(HashMap::new()),
+            capacity,
+        }
+    }
+
+    /// Subscribe to the events of a challenge, creating its channel on first use
+    pub fn subscribe(&self, challenge_id: i32) -> broadcast::Receiver<ChallengeEvent> {
+        let mut channels = self.channels.lock().unwrap_or_else(|e| e.into_inner());
+        channels
+            .entry(challenge_id)
+            .or_insert_with(|| broadcast::channel(self.capacity).0)
+            .subscribe()
+    }
+
+    /// Publish an event to the subscribers of its challenge.
+    /// Returns the number of subscribers reached; channels nobody listens to are dropped.
+    pub fn publish(&self, event: ChallengeEvent) -> usize {
+        let mut channels = self.channels.lock().unwrap_or_else(|e| e.into_inner());
+        let challenge_id = event.challenge_id;
+
+        let Some(sender) = channels.get(&challenge_id) else {
+            return 0;
+        };
+
+        match sender.send(event) {
+            Ok(receivers) => receivers,
+            Err(_) => {
+                channels.remove(&challenge_id);
+                0
+            }
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn subscriber_count(&self, challenge_id: i32) -> usize {
+        let channels = self.channels.lock().unwrap_or_else(|e| e.into_inner());
+        channels
+            .get(&challenge_id)
+            .map(|sender| sender.receiver_count())
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_publish_reaches_subscribers_of_same_challenge_only() {
+        let hub = ChallengeEventHub::default();
+        let mut first = hub.subscribe(1);
+        let mut other = hub.subscribe(2);
+
+        let reached = hub.publish(ChallengeEvent::new(
+            ChallengeEventType::Announcement,
+            1,
+            serde_json::json!({"message": "hello"}),
+        ));
+        assert_eq!(reached, 1);
+
+        let event = first.recv().await.unwrap();
+        assert_eq!(event.challenge_id, 1);
+        assert_eq!(event.event_type, ChallengeEventType::Announcement);
+        assert!(other.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_publish_without_subscribers_drops_channel() {
+        let hub = ChallengeEventHub::default();
+        assert_eq!(
+            hub.publish(ChallengeEvent::new(
+                ChallengeEventType::ChallengeStarted,
+                5,
+                serde_json::Value::Null,
+            )),
+            0
+        );
+
+        let receiver = hub.subscribe(5);
+        assert_eq!(hub.subscriber_count(5), 1);
+        drop(receiver);
+
+        hub.publish(ChallengeEvent::new(
+            ChallengeEventType::ChallengeStarted,
+            5,
+            serde_json::Value::Null,
+        ));
+        assert_eq!(hub.subscriber_count(5), 0);
+    }
+
+    #[test]
+    fn test_event_visibility_and_serialization() {
+        let participant_id = Uuid::new_v4();
+        let event =
+            ChallengeEvent::waypoint_state_changed(3, participant_id, 2, WaypointState::Verified);
+
+        assert!(event.is_visible_to(participant_id));
+        assert!(!event.is_visible_to(Uuid::new_v4()));
+
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(json.contains("\"event-type\":\"waypoint-state-changed\""));
+        assert!(json.contains("\"waypoint-id\":2"));
+        assert!(!json.contains("recipients"));
+    }
+
+    #[test]
+    fn test_status_change_removes_participant() {
+        let participant_id = Uuid::new_v4();
+        let disqualified = ChallengeEvent::participant_status_changed(
+            3,
+            participant_id,
+            ParticipantStatus::Disqualified,
+            Some("Cheating"),
+        );
+        assert!(disqualified.removes_participant(participant_id));
+        assert!(!disqualified.removes_participant(Uuid::new_v4()));
+
+        let reinstated = ChallengeEvent::participant_status_changed(
+            3,
+            participant_id,
+            ParticipantStatus::Active,
+            None,
+        );
+        assert!(!reinstated.removes_participant(participant_id));
+        assert!(!ChallengeEvent::waypoint_state_changed(
+            3,
+            participant_id,
+            2,
+            WaypointState::Verified
+        )
+        .removes_participant(participant_id));
+    }
+}
@@ -0,0 +1,514 @@
+use chrono::{DateTime, Duration, FixedOffset, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::challenge::{MissingExifPolicy, WaypointData};
+use crate::services::image_upload::ImageFormat;
+use crate::services::location_service::{GeoLocation, LocationService};
+
+/// Camera clocks drift, capture times this far outside the window still count
+const CLOCK_SKEW_MINUTES: i64 = 5;
+/// Furthest any local time is from UTC, for capture times recorded without an offset
+const MAX_UTC_OFFSET_HOURS: i64 = 14;
+
+const TAG_EXIF_IFD: u16 = 0x8769;
+const TAG_GPS_IFD: u16 = 0x8825;
+const TAG_DATE_TIME_ORIGINAL: u16 = 0x9003;
+const TAG_OFFSET_TIME_ORIGINAL: u16 = 0x9011;
+const TAG_GPS_LATITUDE_REF: u16 = 0x0001;
+const TAG_GPS_LATITUDE: u16 = 0x0002;
+const TAG_GPS_LONGITUDE_REF: u16 = 0x0003;
+const TAG_GPS_LONGITUDE: u16 = 0x0004;
+
+/// Where and when a photo was taken according to its EXIF data
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PhotoMetadata {
+    pub location: Option<GeoLocation>,
+    pub taken_at: Option<NaiveDateTime>, // DateTimeOriginal, in the camera's local time
+    pub utc_offset: Option<String>,      // OffsetTimeOriginal such as "+02:00", when recorded
+}
+
+impl PhotoMetadata {
+    /// Read the EXIF block of a JPEG, PNG or WebP image. Anything missing or malformed is
+    /// left out rather than failing the upload.
+    pub fn read(data: &[u8], format: ImageFormat) -> Self {
+        let block = match format {
+            ImageFormat::Jpeg => jpeg_exif(data),
+            ImageFormat::Png => png_exif(data),
+            ImageFormat::Webp => webp_exif(data),
+            ImageFormat::Gif | ImageFormat::Bmp => None,
+        };
+
+        block
+            .and_then(Tiff::parse)
+            .map(|tiff| tiff.photo_metadata())
+            .unwrap_or_default()
+    }
+
+    /// The earliest and latest instants the capture time can stand for. Without a recorded
+    /// offset the camera could have been in any time zone.
+    fn taken_between(&self) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        let taken_at = self.taken_at?;
+        match self
+            .utc_offset
+            .as_deref()
+            .and_then(|offset| offset.parse::<FixedOffset>().ok())
+        {
+            Some(offset) => {
+                let taken_at = offset
+                    .from_local_datetime(&taken_at)
+                    .single()?
+                    .with_timezone(&Utc);
+                Some((taken_at, taken_at))
+            }
+            None => {
+                let taken_at = Utc.from_utc_datetime(&taken_at);
+                let offset = Duration::hours(MAX_UTC_OFFSET_HOURS);
+                Some((taken_at - offset, taken_at + offset))
+            }
+        }
+    }
+}
+
+/// What a proof photo's metadata must satisfy before it goes to the image checker
+pub struct PhotoRequirements<'a> {
+    pub waypoint: &'a WaypointData,
+    pub window: Option<(DateTime<Utc>, DateTime<Utc>)>, // From the participant's start until now
+    pub missing_exif: MissingExifPolicy,
+}
+
+impl PhotoRequirements<'_> {
+    /// Reasons the photo is ruled out by where or when it was taken, empty when it may go on
+    pub fn check(&self, metadata: &PhotoMetadata) -> Vec<String> {
+        let mut reasons = Vec::new();
+        let reject_missing = self.missing_exif == MissingExifPolicy::Reject;
+
+        match &metadata.location {
+            Some(location) => {
+                let distance =
+                    LocationService::haversine_distance(&self.waypoint.location, location);
+                if distance > self.waypoint.radius_meters {
+                    reasons.push(format!(
+                        "Image was taken away from the target, {:.0}m from the waypoint where {:.0}m is allowed",
+                        distance, self.waypoint.radius_meters
+                    ));
+                }
+            }
+            None if reject_missing => {
+                reasons.push("Image has no GPS location in its EXIF data".to_string())
+            }
+            None => {}
+        }
+
+        match (metadata.taken_between(), self.window) {
+            (Some((earliest, latest)), Some((start, end))) => {
+                let skew = Duration::minutes(CLOCK_SKEW_MINUTES);
+                if latest < start - skew || earliest > end + skew {
+                    reasons.push("Image was taken outside the challenge window".to_string());
+                }
+            }
+            (None, _) if reject_missing => {
+                reasons.push("Image has no capture time in its EXIF data".to_string())
+            }
+            _ => {}
+        }
+
+        reasons
+    }
+}
+
+/// The TIFF structure inside an APP1 segment, after the `Exif\0\0` marker
+fn jpeg_exif(data: &[u8]) -> Option<&[u8]> {
+    let mut at = 2;
+    while at + 4 <= data.len() {
+        if data[at] != 0xFF {
+            return None;
+        }
+        match data[at + 1] {
+            0xFF => {
+                at += 1; // Fill byte
+                continue;
+            }
+            0xDA | 0xD9 => return None, // Metadata comes before the scan data
+            _ => {}
+        }
+
+        let length = u16::from_be_bytes([data[at + 2], data[at + 3]]) as usize;
+        let segment = data.get(at + 4..at + 2 + length)?;
+        if data[at + 1] == 0xE1 {
+            if let Some(tiff) = segment.strip_prefix(b"Exif\0\0") {
+                return Some(tiff);
+            }
+        }
+        at += 2 + length;
+    }
+    None
+}
+
+fn png_exif(data: &[u8]) -> Option<&[u8]> {
+    let mut at = 8;
+    while at + 8 <= data.len() {
+        let length = u32::from_be_bytes(data[at..at + 4].try_into().ok()?) as usize;
+        let chunk = data.get(at + 8..(at + 8).checked_add(length)?)?;
+        match &data[at + 4..at + 8] {
+            b"eXIf" => return Some(chunk),
+            b"IEND" => return None,
+            _ => at += 12 + length,
+        }
+    }
+    None
+}
+
+fn webp_exif(data: &[u8]) -> Option<&[u8]> {
+    let mut at = 12;
+    while at + 8 <= data.len() {
+        let length = u32::from_le_bytes(data[at + 4..at + 8].try_into().ok()?) as usize;
+        let chunk = data.get(at + 8..(at + 8).checked_add(length)?)?;
+        if &data[at..at + 4] == b"EXIF" {
+            return Some(chunk.strip_prefix(b"Exif\0\0").unwrap_or(chunk));
+        }
+        at += 8 + length + (length & 1); // Chunks are padded to an even size
+    }
+    None
+}
+
+/// Just enough of a TIFF reader to follow the EXIF and GPS IFD pointers
+struct Tiff<'a> {
+    data: &'a [u8],
+    little_endian: bool,
+}
+
+struct IfdEntry {
+    kind: u16,
+    count: usize,
+    value_at: usize,
+}
+
+impl<'a> Tiff<'a> {
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        let little_endian = match data.get(0..4)? {
+            b"II\x2A\0" => true,
+            b"MM\0\x2A" => false,
+            _ => return None,
+        };
+        Some(Self {
+            data,
+            little_endian,
+        })
+    }
+
+    fn u16(&self, at: usize) -> Option<u16> {
+        let bytes = self.data.get(at..at + 2)?.try_into().ok()?;
+        Some(if self.little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    }
+
+    fn u32(&self, at: usize) -> Option<u32> {
+        let bytes = self.data.get(at..at + 4)?.try_into().ok()?;
+        Some(if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn entry(&self, ifd: usize, tag: u16) -> Option<IfdEntry> {
+        let count = self.u16(ifd)? as usize;
+        (0..count).map(|i| ifd + 2 + i * 12).find_map(|at| {
+            if self.u16(at)? != tag {
+                return None;
+            }
+            let kind = self.u16(at + 2)?;
+            let count = self.u32(at + 4)? as usize;
+            let size = match kind {
+                1 | 2 | 6 | 7 => 1,
+                3 | 8 => 2,
+                4 | 9 | 11 => 4,
+                _ => 8,
+            } * count;
+            let value_at = if size <= 4 {
+                at + 8
+            } else {
+                self.u32(at + 8)? as usize
+            };
+            Some(IfdEntry {
+                kind,
+                count,
+                value_at,
+            })
+        })
+    }
+
+    fn ascii(&self, ifd: usize, tag: u16) -> Option<&'a str> {
+        let entry = self.entry(ifd, tag).filter(|entry| entry.kind == 2)?;
+        let bytes = self
+            .data
+            .get(entry.value_at..entry.value_at + entry.count)?;
+        let text = std::str::from_utf8(bytes)
+            .ok()?
+            .trim_end_matches('\0')
+            .trim();
+        (!text.is_empty()).then_some(text)
+    }
+
+    /// Degrees, minutes and seconds as unsigned rationals
+    fn degrees(&self, ifd: usize, tag: u16) -> Option<f64> {
+        let entry = self
+            .entry(ifd, tag)
+            .filter(|entry| entry.kind == 5 && entry.count == 3)?;
+        let mut degrees = 0.0;
+        for (i, unit) in [1.0, 60.0, 3600.0].iter().enumerate() {
+            let at = entry.value_at + i * 8;
+            let denominator = self.u32(at + 4)?;
+            if denominator == 0 {
+                return None;
+            }
+            degrees += self.u32(at)? as f64 / denominator as f64 / unit;
+        }
+        Some(degrees)
+    }
+
+    fn photo_metadata(&self) -> PhotoMetadata {
+        let ifd0 = self.u32(4).map(|offset| offset as usize);
+        let pointer = |tag| {
+            ifd0.and_then(|ifd| self.entry(ifd, tag))
+                .and_then(|entry| self.u32(entry.value_at))
+        };
+        let exif_ifd = pointer(TAG_EXIF_IFD).map(|offset| offset as usize);
+        let gps_ifd = pointer(TAG_GPS_IFD).map(|offset| offset as usize);
+
+        let taken_at = exif_ifd
+            .and_then(|ifd| self.ascii(ifd, TAG_DATE_TIME_ORIGINAL))
+            .and_then(|text| NaiveDateTime::parse_from_str(text, "%Y:%m:%d %H:%M:%S").ok());
+        let utc_offset = exif_ifd
+            .and_then(|ifd| self.ascii(ifd, TAG_OFFSET_TIME_ORIGINAL))
+            .map(str::to_string);
+
+        let location = gps_ifd.and_then(|ifd| {
+            let sign = |tag, negative| match self.ascii(ifd, tag)? {
+                reference if reference == negative => Some(-1.0),
+                _ => Some(1.0),
+            };
+            let location = GeoLocation {
+                lat: sign(TAG_GPS_LATITUDE_REF, "S")? * self.degrees(ifd, TAG_GPS_LATITUDE)?,
+                lon: sign(TAG_GPS_LONGITUDE_REF, "W")? * self.degrees(ifd, TAG_GPS_LONGITUDE)?,
+            };
+            (location.lat.abs() <= 90.0 && location.lon.abs() <= 180.0).then_some(location)
+        });
+
+        PhotoMetadata {
+            location,
+            taken_at,
+            utc_offset,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::challenge::ProofKind;
+
+    /// A big-endian TIFF block with DateTimeOriginal, an optional offset and a GPS position
+    fn tiff_block(taken_at: &str, offset: Option<&str>, lat: [u32; 3], lon: [u32; 3]) -> Vec<u8> {
+        fn entry(tag: u16, kind: u16, count: u32, value: u32) -> Vec<u8> {
+            [
+                &tag.to_be_bytes()[..],
+                &kind.to_be_bytes(),
+                &count.to_be_bytes(),
+                &value.to_be_bytes(),
+            ]
+            .concat()
+        }
+        fn ascii(text: &str) -> Vec<u8> {
+            [text.as_bytes(), b"\0"].concat()
+        }
+        /// Whole degrees and minutes, seconds in hundredths
+        fn rationals([degrees, minutes, seconds]: [u32; 3]) -> Vec<u8> {
+            [(degrees, 1u32), (minutes, 1), (seconds, 100)]
+                .iter()
+                .flat_map(|(n, d)| [n.to_be_bytes(), d.to_be_bytes()].concat())
+                .collect()
+        }
+        let inline = |text: &[u8]| u32::from_be_bytes([text[0], text[1], 0, 0]);
+
+        // Header, then IFD0 at 8, the EXIF IFD at 38, the GPS IFD at 80 and values from 140
+        let taken_at = ascii(taken_at);
+        let offset = offset.map(ascii);
+        let mut values = Vec::new();
+        let mut value = |bytes: Vec<u8>| {
+            let at = 140 + values.len() as u32;
+            values.extend(bytes);
+            at
+        };
+        let taken_at_at = value(taken_at.clone());
+        let offset_at = offset.clone().map(&mut value);
+        let lat_at = value(rationals(lat));
+        let lon_at = value(rationals(lon));
+
+        let mut tiff = b"MM\0\x2A\0\0\0\x08".to_vec();
+        tiff.extend(2u16.to_be_bytes());
+        tiff.extend(entry(TAG_EXIF_IFD, 4, 1, 38));
+        tiff.extend(entry(TAG_GPS_IFD, 4, 1, 80));
+        tiff.extend(0u32.to_be_bytes());
+
+        tiff.extend((1 + offset.is_some() as u16).to_be_bytes());
+        tiff.extend(entry(
+            TAG_DATE_TIME_ORIGINAL,
+            2,
+            taken_at.len() as u32,
+            taken_at_at,
+        ));
+        if let (Some(offset), Some(offset_at)) = (&offset, offset_at) {
+            tiff.extend(entry(
+                TAG_OFFSET_TIME_ORIGINAL,
+                2,
+                offset.len() as u32,
+                offset_at,
+            ));
+        }
+        tiff.extend(0u32.to_be_bytes());
+        tiff.resize(80, 0);
+
+        tiff.extend(4u16.to_be_bytes());
+        tiff.extend(entry(TAG_GPS_LATITUDE_REF, 2, 2, inline(b"S\0")));
+        tiff.extend(entry(TAG_GPS_LATITUDE, 5, 3, lat_at));
+        tiff.extend(entry(TAG_GPS_LONGITUDE_REF, 2, 2, inline(b"E\0")));
+        tiff.extend(entry(TAG_GPS_LONGITUDE, 5, 3, lon_at));
+        tiff.extend(0u32.to_be_bytes());
+        tiff.resize(140, 0);
+
+        tiff.extend(values);
+        tiff
+    }
+
+    /// A minimal JPEG carrying the given TIFF block in its APP1 segment
+    fn jpeg_with_exif(tiff: &[u8]) -> Vec<u8> {
+        let segment = [b"Exif\0\0", tiff].concat();
+        [
+            &[0xFF, 0xD8, 0xFF, 0xE1][..],
+            &((segment.len() + 2) as u16).to_be_bytes(),
+            &segment,
+            &[0xFF, 0xDA, 0x00, 0x02, 0xFF, 0xD9],
+        ]
+        .concat()
+    }
+
+    /// The happy path photo from the waypoint proof docs, taken at (-22.3321, 32.0021) on
+    /// 2025-01-02 two hours ahead of UTC
+    fn happy_path_jpeg() -> Vec<u8> {
+        jpeg_with_exif(&tiff_block(
+            "2025:01:02 02:00:00",
+            Some("+02:00"),
+            [22, 19, 5556], // 22°19'55.56" S
+            [32, 0, 756],   // 32°0'7.56" E
+        ))
+    }
+
+    #[test]
+    fn test_read_photo_metadata() {
+        let metadata = PhotoMetadata::read(&happy_path_jpeg(), ImageFormat::Jpeg);
+        let location = metadata.location.clone().unwrap();
+        assert!((location.lat + 22.3321).abs() < 0.0001);
+        assert!((location.lon - 32.0021).abs() < 0.0001);
+        assert_eq!(
+            metadata.taken_at.unwrap().to_string(),
+            "2025-01-02 02:00:00"
+        );
+        assert_eq!(metadata.utc_offset.as_deref(), Some("+02:00"));
+        let taken_at = Utc.with_ymd_and_hms(2025, 1, 2, 0, 0, 0).unwrap();
+        assert_eq!(metadata.taken_between(), Some((taken_at, taken_at)));
+
+        // The same block in a PNG eXIf chunk
+        let tiff = tiff_block("2025:01:02 02:00:00", None, [22, 19, 5556], [32, 0, 756]);
+        let png = [
+            &b"\x89PNG\r\n\x1a\n\0\0\0\0IHDR\0\0\0\0"[..],
+            &(tiff.len() as u32).to_be_bytes(),
+            b"eXIf",
+            &tiff,
+            b"\0\0\0\0",
+        ]
+        .concat();
+        let metadata = PhotoMetadata::read(&png, ImageFormat::Png);
+        assert!(metadata.location.is_some());
+        assert!(metadata.utc_offset.is_none());
+
+        // Missing or broken EXIF leaves everything out
+        let bare = PhotoMetadata::read(&[0xFF, 0xD8, 0xFF, 0xDA, 0, 2], ImageFormat::Jpeg);
+        assert!(bare.location.is_none() && bare.taken_at.is_none());
+        let truncated = happy_path_jpeg()[..60].to_vec();
+        assert!(PhotoMetadata::read(&truncated, ImageFormat::Jpeg)
+            .location
+            .is_none());
+    }
+
+    #[test]
+    fn test_photo_requirements() {
+        let waypoint = WaypointData {
+            waypoint_id: None,
+            waypoint_sequence: 1,
+            location: GeoLocation {
+                lat: -22.3321,
+                lon: 32.0023,
+            },
+            radius_meters: 50.0,
+            waypoint_clue: "Near the river".to_string(),
+            hints: vec![],
+            waypoint_time_minutes: None,
+            image_subject: "chopped tree trunk".to_string(),
+            proof: ProofKind::Image,
+            bonus: None,
+            created_at: None,
+        };
+        let window = (
+            Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap(),
+        );
+        let requirements = |missing_exif| PhotoRequirements {
+            waypoint: &waypoint,
+            window: Some(window),
+            missing_exif,
+        };
+        let allow = requirements(MissingExifPolicy::Allow);
+        let reject = requirements(MissingExifPolicy::Reject);
+
+        let happy_path = PhotoMetadata::read(&happy_path_jpeg(), ImageFormat::Jpeg);
+        assert!(allow.check(&happy_path).is_empty());
+        assert!(reject.check(&happy_path).is_empty());
+
+        // A degree north and a year early
+        let elsewhere = PhotoMetadata {
+            location: Some(GeoLocation {
+                lat: -21.3321,
+                lon: 32.0021,
+            }),
+            taken_at: Some("2024-01-02T00:00:00".parse().unwrap()),
+            utc_offset: Some("+00:00".to_string()),
+        };
+        let reasons = allow.check(&elsewhere);
+        assert_eq!(reasons.len(), 2);
+        assert!(reasons[0].starts_with("Image was taken away from the target, 1111"));
+        assert_eq!(reasons[1], "Image was taken outside the challenge window");
+
+        // Without an offset the capture time could be up to fourteen hours either way
+        let no_offset = |taken_at: &str| PhotoMetadata {
+            taken_at: Some(taken_at.parse().unwrap()),
+            utc_offset: None,
+            ..happy_path.clone()
+        };
+        assert!(allow.check(&no_offset("2025-01-01T10:00:00")).is_empty());
+        assert_eq!(allow.check(&no_offset("2024-12-31T09:00:00")).len(), 1);
+
+        let missing = PhotoMetadata::default();
+        assert!(allow.check(&missing).is_empty());
+        assert_eq!(
+            reject.check(&missing),
+            vec![
+                "Image has no GPS location in its EXIF data",
+                "Image has no capture time in its EXIF data"
+            ]
+        );
+    }
+}
@@ -1,12 +1,19 @@
 use chrono::{DateTime, Utc};
-use reqwest::Client;
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use reqwest::{Client, RequestBuilder, Response};
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
-use tokio::time::sleep;
+use sha2::Sha256;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
+use crate::services::image_store::{ImageStore, ImageStoreError};
 use crate::services::location_service::GeoLocation;
 
+/// How long the image checker can fetch a submitted image for
+const IMAGE_URL_EXPIRY: Duration = Duration::from_secs(15 * 60);
+
 #[derive(Debug, Clone, Serialize)]
 pub struct ImageValidationRequest {
     #[serde(rename = "processing-id")]
@@ -40,63 +47,229 @@ pub struct DateTimeConstraint {
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct StatusResponse {
-    pub status: String,
+    pub status: String, // "accepted", "in_progress", "completed" or "failed"
+}
+
+/// The verdict the image checker calls back with, signed with the shared callback secret
+#[derive(Debug, Clone, Deserialize)]
+pub struct ValidationCallback {
+    #[serde(rename = "processing-id")]
+    pub processing_id: Uuid,
+    #[serde(flatten)]
+    pub result: ValidationResult,
+}
+
+impl ValidationCallback {
+    /// Whether `signature` is `sha256=` followed by the hex HMAC-SHA256 of the raw callback
+    /// body under the shared secret, compared in constant time
+    pub fn verify_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+        let Some(signature) = signature
+            .strip_prefix("sha256=")
+            .and_then(|hex_digest| hex::decode(hex_digest.trim()).ok())
+        else {
+            return false;
+        };
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(body);
+        mac.verify_slice(&signature).is_ok()
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct ValidationResult {
-    pub resolution: String, // "accepted" or "rejected"
+    pub resolution: String, // "accepted", "rejected" or "uncertain"
     pub reasons: Option<Vec<String>>,
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum ImageError {
-    #[error("HTTP request failed: {0}")]
-    RequestFailed(#[from] reqwest::Error),
-    #[error("Image validation failed")]
-    ValidationFailed,
-    #[error("Processing timeout")]
+    #[error("Image checker refused the request with status {0}")]
+    ClientError(u16),
+    #[error("Image checker failed with status {0}")]
+    ServerError(u16),
+    #[error("Image checker did not answer in time")]
     Timeout,
+    #[error("Could not reach the image checker: {0}")]
+    Connection(reqwest::Error),
+    #[error("Image checker is unavailable, not sending requests for now")]
+    CircuitOpen,
     #[error("Invalid image path: {0}")]
-    InvalidImagePath(String),
-    #[error("Service unavailable")]
-    ServiceUnavailable,
+    InvalidImagePath(#[from] ImageStoreError),
     #[error("Unexpected response: {0}")]
     UnexpectedResponse(String),
 }
 
+impl From<reqwest::Error> for ImageError {
+    fn from(error: reqwest::Error) -> Self {
+        if error.is_timeout() {
+            ImageError::Timeout
+        } else if error.is_decode() || error.is_body() {
+            ImageError::UnexpectedResponse(error.to_string())
+        } else {
+            ImageError::Connection(error)
+        }
+    }
+}
+
+impl ImageError {
+    /// Whether the image checker may get it right when asked again. A 4xx means the request
+    /// itself is wrong, so those are not.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            ImageError::ServerError(_) | ImageError::Timeout | ImageError::Connection(_)
+        )
+    }
+}
+
+/// How the client copes with a slow or flapping image checker
+#[derive(Debug, Clone)]
+pub struct ImageCheckerPolicy {
+    pub request_timeout: Duration,
+    /// Extra attempts for idempotent calls after a transient failure
+    pub max_retries: u32,
+    /// Doubled on every retry, then jittered by up to half either way
+    pub retry_base_delay: Duration,
+    /// Consecutive transient failures that open the circuit breaker
+    pub breaker_threshold: u32,
+    /// How long an open breaker fails calls straight away before letting one through again
+    pub breaker_cooldown: Duration,
+}
+
+impl Default for ImageCheckerPolicy {
+    fn default() -> Self {
+        Self {
+            request_timeout: Duration::from_secs(10),
+            max_retries: 3,
+            retry_base_delay: Duration::from_millis(200),
+            breaker_threshold: 5,
+            breaker_cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+impl ImageCheckerPolicy {
+    /// Delay before retry number `retry`, counting from 1
+    fn retry_delay(&self, retry: u32) -> Duration {
+        let backoff = self.retry_base_delay * 2u32.saturating_pow(retry.saturating_sub(1));
+        backoff.mul_f64(rand::thread_rng().gen_range(0.5..1.5))
+    }
+}
+
+enum BreakerState {
+    Closed {
+        failures: u32,
+    },
+    Open {
+        until: Instant,
+    },
+    /// One trial call is out, `until` gives up on it in case its caller never reports back
+    HalfOpen {
+        until: Instant,
+    },
+}
+
+/// Stops calling an image checker that keeps failing, so proofs fail fast instead of each
+/// waiting out its own timeouts and retries. Once the cooldown is over a single trial call
+/// goes through while the others keep failing fast: its success closes the breaker, a
+/// transient failure opens it again.
+struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    state: Mutex<BreakerState>,
+}
+
+impl CircuitBreaker {
+    fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold,
+            cooldown,
+            state: Mutex::new(BreakerState::Closed { failures: 0 }),
+        }
+    }
+
+    fn allows_request(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+
+        let now = Instant::now();
+        match *state {
+            BreakerState::Closed { .. } => true,
+            BreakerState::Open { until } | BreakerState::HalfOpen { until } if now >= until => {
+                *state = BreakerState::HalfOpen {
+                    until: now + self.cooldown,
+                };
+                true
+            }
+            BreakerState::Open { .. } | BreakerState::HalfOpen { .. } => false,
+        }
+    }
+
+    fn record<T>(&self, outcome: &Result<T, ImageError>) {
+        let mut state = self.state.lock().unwrap();
+
+        let failed = matches!(outcome, Err(e) if e.is_transient());
+        *state = match (&*state, failed) {
+            (_, false) => BreakerState::Closed { failures: 0 },
+            (BreakerState::Closed { failures }, true) if failures + 1 < self.threshold => {
+                BreakerState::Closed {
+                    failures: failures + 1,
+                }
+            }
+            (_, true) => {
+                tracing::warn!(
+                    "Image checker keeps failing, pausing requests for {:?}",
+                    self.cooldown
+                );
+                BreakerState::Open {
+                    until: Instant::now() + self.cooldown,
+                }
+            }
+        };
+    }
+}
+
 pub struct ImageService {
     client: Client,
     base_url: String,
-    image_base_dir: String,
+    image_store: Arc<dyn ImageStore>,
+    policy: ImageCheckerPolicy,
+    breaker: CircuitBreaker,
 }
 
 impl ImageService {
-    pub fn new(base_url: String, image_base_dir: String) -> Self {
+    pub fn new(
+        base_url: String,
+        image_store: Arc<dyn ImageStore>,
+        policy: ImageCheckerPolicy,
+    ) -> Self {
         let client = Client::builder()
-            .timeout(Duration::from_secs(30))
+            .timeout(policy.request_timeout)
             .build()
             .expect("Failed to create HTTP client");
 
         Self {
             client,
             base_url,
-            image_base_dir,
+            image_store,
+            breaker: CircuitBreaker::new(policy.breaker_threshold, policy.breaker_cooldown),
+            policy,
         }
     }
 
-    /// Validate an image with the external image-checker service
-    pub async fn validate_image(
+    /// Hand an image to the external image-checker service, which works on it under
+    /// `processing_id` until `check_status` reports it completed
+    pub async fn submit(
         &self,
+        processing_id: &str,
         image_path: &str,
         expected_content: &str,
         location: Option<&GeoLocation>,
         max_distance: Option<f64>,
         datetime_constraint: Option<DateTimeConstraint>,
-    ) -> Result<ValidationResult, ImageError> {
-        let processing_id = Uuid::new_v4().to_string();
-
-        // Build full image path
+    ) -> Result<(), ImageError> {
+        // URL the checker fetches the image from
         let full_image_path = self.build_image_path(image_path)?;
 
         // Create location constraint if provided
@@ -106,9 +279,8 @@ impl ImageService {
             max_distance: max_distance.unwrap_or(50.0),
         });
 
-        // Submit validation request
         let request = ImageValidationRequest {
-            processing_id: processing_id.clone(),
+            processing_id: processing_id.to_string(),
             image_path: full_image_path,
             analysis_request: AnalysisRequest {
                 content: expected_content.to_string(),
@@ -117,133 +289,88 @@ impl ImageService {
             },
         };
 
-        self.submit_validation(&request).await?;
-
-        // Poll for completion
-        self.poll_for_completion(&processing_id).await?;
-
-        // Get results
-        self.get_results(&processing_id).await
+        self.submit_validation(&request).await
     }
 
-    /// Submit validation request to the image-checker service
+    /// Submit validation request to the image-checker service. Not retried here: a
+    /// submission the checker got but did not answer would be submitted twice, the proof job
+    /// tries again later instead.
     async fn submit_validation(&self, request: &ImageValidationRequest) -> Result<(), ImageError> {
         let url = format!("{}/validate", self.base_url);
 
-        let response = self.client.post(&url).json(request).send().await?;
-
-        if !response.status().is_success() {
-            return Err(ImageError::ServiceUnavailable);
-        }
+        self.send(|client| client.post(&url).json(request), false)
+            .await?;
 
         Ok(())
     }
 
-    /// Poll for processing completion with exponential backoff
-    async fn poll_for_completion(&self, processing_id: &str) -> Result<(), ImageError> {
-        let mut attempts = 0;
-        let max_attempts = 30;
-        let base_delay = 1000; // 1 second base delay
-
-        loop {
-            let status = self.check_status(processing_id).await?;
-
-            match status.status.as_str() {
-                "completed" => return Ok(()),
-                "failed" => return Err(ImageError::ValidationFailed),
-                "in_progress" | "accepted" => {
-                    attempts += 1;
-                    if attempts >= max_attempts {
-                        return Err(ImageError::Timeout);
-                    }
-
-                    // Exponential backoff with jitter
-                    let delay = base_delay + (attempts * 100);
-                    sleep(Duration::from_millis(delay)).await;
-                }
-                _ => {
-                    return Err(ImageError::UnexpectedResponse(format!(
-                        "Unknown status: {}",
-                        status.status
-                    )));
-                }
-            }
-        }
-    }
-
     /// Check processing status
-    async fn check_status(&self, processing_id: &str) -> Result<StatusResponse, ImageError> {
+    pub async fn check_status(&self, processing_id: &str) -> Result<StatusResponse, ImageError> {
         let url = format!("{}/status/{}", self.base_url, processing_id);
 
-        let response = self.client.get(&url).send().await?;
-
-        if !response.status().is_success() {
-            return Err(ImageError::ServiceUnavailable);
-        }
+        let response = self.send(|client| client.get(&url), true).await?;
 
         let status = response.json::<StatusResponse>().await?;
         Ok(status)
     }
 
     /// Get validation results
-    async fn get_results(&self, processing_id: &str) -> Result<ValidationResult, ImageError> {
+    pub async fn get_results(&self, processing_id: &str) -> Result<ValidationResult, ImageError> {
         let url = format!("{}/results/{}", self.base_url, processing_id);
 
-        let response = self.client.get(&url).send().await?;
-
-        if !response.status().is_success() {
-            return Err(ImageError::ServiceUnavailable);
-        }
+        let response = self.send(|client| client.get(&url), true).await?;
 
         let result = response.json::<ValidationResult>().await?;
         Ok(result)
     }
 
-    /// Build full image path from relative path
-    fn build_image_path(&self, relative_path: &str) -> Result<String, ImageError> {
-        // Validate path doesn't contain dangerous characters
-        if relative_path.contains("..") || relative_path.starts_with('/') {
-            return Err(ImageError::InvalidImagePath(
-                "Path contains invalid characters".to_string(),
-            ));
-        }
-
-        // Support both local and cloud storage paths
-        if self.image_base_dir.starts_with("http://") || self.image_base_dir.starts_with("https://")
-        {
-            // Cloud storage URL
-            Ok(format!(
-                "{}/{}",
-                self.image_base_dir.trim_end_matches('/'),
-                relative_path
-            ))
+    /// Send a request through the circuit breaker, retrying transient failures of idempotent
+    /// calls with jittered exponential backoff
+    async fn send(
+        &self,
+        request: impl Fn(&Client) -> RequestBuilder,
+        idempotent: bool,
+    ) -> Result<Response, ImageError> {
+        let max_retries = if idempotent {
+            self.policy.max_retries
         } else {
-            // Local file path
-            Ok(format!(
-                "file://{}/{}",
-                self.image_base_dir.trim_end_matches('/'),
-                relative_path
-            ))
-        }
-    }
-
-    /// Validate image format and size (basic checks)
-    pub fn validate_image_format(&self, filename: &str) -> Result<(), ImageError> {
-        let allowed_extensions = ["jpg", "jpeg", "png", "gif", "bmp"];
+            0
+        };
+        let mut retry = 0;
 
-        let extension = filename
-            .split('.')
-            .next_back()
-            .ok_or_else(|| ImageError::InvalidImagePath("No file extension".to_string()))?
-            .to_lowercase();
+        loop {
+            if !self.breaker.allows_request() {
+                return Err(ImageError::CircuitOpen);
+            }
 
-        if !allowed_extensions.contains(&extension.as_str()) {
-            return Err(ImageError::InvalidImagePath(format!(
-                "Unsupported file format: {extension}"
-            )));
+            let outcome = match request(&self.client).send().await {
+                Ok(response) => classify_status(response),
+                Err(e) => Err(e.into()),
+            };
+            self.breaker.record(&outcome);
+
+            match outcome {
+                Err(e) if e.is_transient() && retry < max_retries => {
+                    retry += 1;
+                    let delay = self.policy.retry_delay(retry);
+                    tracing::debug!(
+                        "Image checker request failed ({}), retry {} in {:?}",
+                        e,
+                        retry,
+                        delay
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                outcome => return outcome,
+            }
         }
+    }
 
-        Ok(())
+    /// Build the URL the image checker reads an image from
+    fn build_image_path(&self, relative_path: &str) -> Result<String, ImageError> {
+        Ok(self
+            .image_store
+            .presigned_url(relative_path, IMAGE_URL_EXPIRY)?)
     }
 
     /// Create datetime constraint for current time window
@@ -257,41 +384,65 @@ impl ImageService {
     }
 }
 
+/// Tell the image checker's own errors (5xx) apart from requests it refuses (4xx)
+fn classify_status(response: Response) -> Result<Response, ImageError> {
+    let status = response.status();
+
+    if status.is_success() {
+        Ok(response)
+    } else if status.is_client_error() {
+        Err(ImageError::ClientError(status.as_u16()))
+    } else if status.is_server_error() {
+        Err(ImageError::ServerError(status.as_u16()))
+    } else {
+        Err(ImageError::UnexpectedResponse(format!(
+            "Unexpected status {status}"
+        )))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::services::image_store::{LocalImageStore, S3ImageStore, S3Settings};
+    use std::collections::HashMap;
+
+    fn service(image_store: impl ImageStore + 'static) -> ImageService {
+        ImageService::new(
+            "http://localhost:8080".to_string(),
+            Arc::new(image_store),
+            ImageCheckerPolicy::default(),
+        )
+    }
 
     #[test]
     fn test_build_image_path_local() {
-        let service = ImageService::new(
-            "http://localhost:8080".to_string(),
-            "/var/images".to_string(),
-        );
+        let service = service(LocalImageStore::new("/var/images"));
 
         let path = service.build_image_path("test/image.jpg").unwrap();
         assert_eq!(path, "file:///var/images/test/image.jpg");
     }
 
     #[test]
-    fn test_build_image_path_cloud() {
-        let service = ImageService::new(
-            "http://localhost:8080".to_string(),
-            "https://storage.googleapis.com/my-bucket".to_string(),
-        );
+    fn test_build_image_path_s3() {
+        let service = service(S3ImageStore::new(S3Settings {
+            endpoint: "http://localhost:9000".to_string(),
+            bucket: "proofs".to_string(),
+            region: "us-east-1".to_string(),
+            access_key_id: "minio".to_string(),
+            secret_access_key: "minio-secret".to_string(),
+            path_style: true,
+        }));
 
         let path = service.build_image_path("test/image.jpg").unwrap();
-        assert_eq!(
-            path,
-            "https://storage.googleapis.com/my-bucket/test/image.jpg"
-        );
+        assert!(path.starts_with("http://localhost:9000/proofs/test/image.jpg?"));
+        assert!(path.contains("X-Amz-Expires=900&"));
+        assert!(path.contains("&X-Amz-Signature="));
     }
 
     #[test]
     fn test_invalid_image_path() {
-        let service = ImageService::new(
-            "http://localhost:8080".to_string(),
-            "/var/images".to_string(),
-        );
+        let service = service(LocalImageStore::new("/var/images"));
 
         // Test path traversal attack
         let result = service.build_image_path("../../../etc/passwd");
@@ -303,21 +454,40 @@ mod tests {
     }
 
     #[test]
-    fn test_validate_image_format() {
-        let service = ImageService::new(
-            "http://localhost:8080".to_string(),
-            "/var/images".to_string(),
-        );
-
-        // Valid formats
-        assert!(service.validate_image_format("test.jpg").is_ok());
-        assert!(service.validate_image_format("test.PNG").is_ok());
-        assert!(service.validate_image_format("test.jpeg").is_ok());
-
-        // Invalid formats
-        assert!(service.validate_image_format("test.txt").is_err());
-        assert!(service.validate_image_format("test.exe").is_err());
-        assert!(service.validate_image_format("test").is_err());
+    fn test_callback_signature() {
+        let secret = "callback-secret-that-is-long-enough";
+        let body =
+            br#"{"processing-id":"00000000-0000-0000-0000-000000000000","resolution":"accepted"}"#;
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(body);
+        let signature = format!("sha256={}", hex::encode(mac.finalize().into_bytes()));
+
+        assert!(ValidationCallback::verify_signature(
+            secret, body, &signature
+        ));
+        assert!(!ValidationCallback::verify_signature(
+            "another-secret",
+            body,
+            &signature
+        ));
+        assert!(!ValidationCallback::verify_signature(
+            secret, b"{}", &signature
+        ));
+        assert!(!ValidationCallback::verify_signature(
+            secret,
+            body,
+            &signature[7..]
+        ));
+        assert!(!ValidationCallback::verify_signature(
+            secret,
+            body,
+            "sha256=zz"
+        ));
+
+        let callback: ValidationCallback = serde_json::from_slice(body).unwrap();
+        assert_eq!(callback.processing_id, Uuid::nil());
+        assert_eq!(callback.result.resolution, "accepted");
+        assert!(callback.result.reasons.is_none());
     }
 
     #[test]
@@ -360,4 +530,171 @@ mod tests {
         assert!(json.contains("analysis-request"));
         assert!(json.contains("A red bicycle"));
     }
+
+    /// A local image checker whose endpoints misbehave on cue: `/status/{id}` answers 503
+    /// to the first `failures` requests for an id, then `completed`; ids starting with
+    /// `missing` get a 404, ids starting with `slow` an answer too late for the client
+    async fn mock_checker(failures: usize) -> (String, Arc<Mutex<HashMap<String, usize>>>) {
+        use axum::{extract::Path, http::StatusCode, routing::get, routing::post, Json, Router};
+
+        let hits = Arc::new(Mutex::new(HashMap::<String, usize>::new()));
+        let count = {
+            let hits = hits.clone();
+            move |key: String| {
+                let mut hits = hits.lock().unwrap();
+                let count = hits.entry(key).or_default();
+                *count += 1;
+                *count
+            }
+        };
+        let validate_count = count.clone();
+
+        let checker = Router::new()
+            .route(
+                "/validate",
+                post(move || async move {
+                    validate_count("validate".to_string());
+                    StatusCode::INTERNAL_SERVER_ERROR
+                }),
+            )
+            .route(
+                "/status/:id",
+                get(move |Path(id): Path<String>| async move {
+                    let hits = count(id.clone());
+                    if id.starts_with("missing") {
+                        return Err(StatusCode::NOT_FOUND);
+                    }
+                    if id.starts_with("slow") {
+                        tokio::time::sleep(Duration::from_millis(500)).await;
+                    }
+                    if hits <= failures {
+                        return Err(StatusCode::SERVICE_UNAVAILABLE);
+                    }
+                    Ok(Json(serde_json::json!({ "status": "completed" })))
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, checker).await });
+
+        (base_url, hits)
+    }
+
+    fn checker_client(base_url: String, policy: ImageCheckerPolicy) -> ImageService {
+        ImageService::new(
+            base_url,
+            Arc::new(LocalImageStore::new("/var/images")),
+            policy,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_failures_only() {
+        let (base_url, hits) = mock_checker(2).await;
+        let service = checker_client(
+            base_url,
+            ImageCheckerPolicy {
+                request_timeout: Duration::from_millis(100),
+                max_retries: 3,
+                retry_base_delay: Duration::from_millis(1),
+                breaker_threshold: 100,
+                ..Default::default()
+            },
+        );
+        let hits = |key: &str| hits.lock().unwrap().get(key).copied().unwrap_or_default();
+
+        // Two 503s are retried away
+        let status = service.check_status("flaky").await.unwrap();
+        assert_eq!(status.status, "completed");
+        assert_eq!(hits("flaky"), 3);
+
+        // A 404 is not worth retrying
+        let result = service.check_status("missing").await;
+        assert!(matches!(result, Err(ImageError::ClientError(404))));
+        assert_eq!(hits("missing"), 1);
+
+        // Timeouts are retried until the retries run out
+        let result = service.check_status("slow").await;
+        assert!(matches!(result, Err(ImageError::Timeout)));
+        assert_eq!(hits("slow"), 4);
+
+        // Submissions are not idempotent, so not retried
+        let result = service
+            .submit("p1", "a/b.jpg", "Bench", None, None, None)
+            .await;
+        assert!(matches!(result, Err(ImageError::ServerError(500))));
+        assert_eq!(hits("validate"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_opens_and_recovers() {
+        let (base_url, hits) = mock_checker(3).await;
+        let service = checker_client(
+            base_url,
+            ImageCheckerPolicy {
+                max_retries: 0,
+                breaker_threshold: 2,
+                breaker_cooldown: Duration::from_millis(100),
+                ..Default::default()
+            },
+        );
+        let hits = || hits.lock().unwrap()["down"];
+
+        // Two failures in a row open the breaker, after which calls fail without a request
+        for _ in 0..2 {
+            let result = service.check_status("down").await;
+            assert!(matches!(result, Err(ImageError::ServerError(503))));
+        }
+        let result = service.check_status("down").await;
+        assert!(matches!(result, Err(ImageError::CircuitOpen)));
+        assert_eq!(hits(), 2);
+
+        // After the cooldown one failed trial opens it again straight away
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        let result = service.check_status("down").await;
+        assert!(matches!(result, Err(ImageError::ServerError(503))));
+        let result = service.check_status("down").await;
+        assert!(matches!(result, Err(ImageError::CircuitOpen)));
+        assert_eq!(hits(), 3);
+
+        // And a successful one closes it
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert_eq!(
+            service.check_status("down").await.unwrap().status,
+            "completed"
+        );
+        assert_eq!(
+            service.check_status("down").await.unwrap().status,
+            "completed"
+        );
+        assert_eq!(hits(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_half_open_circuit_breaker_lets_one_trial_through() {
+        let (base_url, hits) = mock_checker(3).await;
+        let service = checker_client(
+            base_url,
+            ImageCheckerPolicy {
+                max_retries: 0,
+                breaker_threshold: 1,
+                breaker_cooldown: Duration::from_millis(100),
+                ..Default::default()
+            },
+        );
+
+        let result = service.check_status("down").await;
+        assert!(matches!(result, Err(ImageError::ServerError(503))));
+
+        // After the cooldown only one of two concurrent calls reaches the checker, the other
+        // fails fast while the trial is still out
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        let (trial, other) = tokio::join!(service.check_status("slow"), async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            service.check_status("slow").await
+        });
+        assert!(matches!(trial, Err(ImageError::ServerError(503))));
+        assert!(matches!(other, Err(ImageError::CircuitOpen)));
+        assert_eq!(hits.lock().unwrap()["slow"], 1);
+    }
 }
@@ -0,0 +1,327 @@
+use axum::body::Bytes;
+use axum::extract::multipart::MultipartError;
+use axum::extract::Multipart;
+use axum::http::StatusCode;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::services::exif::PhotoMetadata;
+use crate::services::image_store::{ImageStore, ImageStoreError};
+use crate::services::perceptual_hash::{PerceptualHash, MAX_IMAGE_DIMENSION};
+
+/// Slack on top of the image size limit for the rest of a multipart body
+pub const MULTIPART_OVERHEAD_BYTES: usize = 64 * 1024;
+/// How long moderators can open a photo from the review queue for
+const REVIEW_URL_EXPIRY: Duration = Duration::from_secs(60 * 60);
+
+/// Image formats accepted as proof, told apart by their magic bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Jpeg,
+    Png,
+    Gif,
+    Bmp,
+    Webp,
+}
+
+impl ImageFormat {
+    pub fn sniff(bytes: &[u8]) -> Option<ImageFormat> {
+        match bytes {
+            [0xFF, 0xD8, 0xFF, ..] => Some(ImageFormat::Jpeg),
+            [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, ..] => Some(ImageFormat::Png),
+            [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => Some(ImageFormat::Gif),
+            [b'B', b'M', ..] => Some(ImageFormat::Bmp),
+            [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => {
+                Some(ImageFormat::Webp)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg => "jpg",
+            ImageFormat::Png => "png",
+            ImageFormat::Gif => "gif",
+            ImageFormat::Bmp => "bmp",
+            ImageFormat::Webp => "webp",
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg => "image/jpeg",
+            ImageFormat::Png => "image/png",
+            ImageFormat::Gif => "image/gif",
+            ImageFormat::Bmp => "image/bmp",
+            ImageFormat::Webp => "image/webp",
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum UploadError {
+    #[error("No image provided")]
+    MissingImage,
+    #[error("Invalid multipart data: {0}")]
+    Multipart(String),
+    #[error("Image exceeds the {0} byte limit")]
+    TooLarge(usize),
+    #[error("Unsupported image format, expected JPEG, PNG, GIF, BMP or WebP")]
+    UnsupportedFormat,
+    #[error("Image is larger than {0}x{0} pixels or too large to decode")]
+    TooManyPixels(u32),
+    #[error("Failed to store image: {0}")]
+    Storage(#[from] ImageStoreError),
+}
+
+/// A proof image persisted in the image store
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredImage {
+    pub relative_path: String, // Its key in the image store
+    pub format: ImageFormat,
+    pub size_bytes: usize,
+    pub sha256: String,
+    pub metadata: PhotoMetadata, // Read from its EXIF data
+    pub perceptual_hash: Option<PerceptualHash>, // None when the image could not be decoded
+}
+
+/// Receives proof photos and stores them content-addressed under
+/// `{challenge}/{participant}/{sha256}.{ext}`, so resubmitting a photo reuses its key
+pub struct ImageUploader {
+    store: Arc<dyn ImageStore>,
+    max_bytes: usize,
+}
+
+impl ImageUploader {
+    pub fn new(store: Arc<dyn ImageStore>, max_bytes: usize) -> Self {
+        Self { store, max_bytes }
+    }
+
+    pub fn max_bytes(&self) -> usize {
+        self.max_bytes
+    }
+
+    /// Stream the `image` field of a multipart body, refusing it as soon as it passes the size
+    /// limit or its first bytes are not a supported image
+    pub async fn receive(
+        &self,
+        multipart: &mut Multipart,
+        challenge_id: i32,
+        participant_id: Uuid,
+    ) -> Result<StoredImage, UploadError> {
+        while let Some(mut field) = multipart
+            .next_field()
+            .await
+            .map_err(|e| self.multipart_error(e))?
+        {
+            if field.name() != Some("image") {
+                continue;
+            }
+
+            let mut data = Vec::new();
+            let mut hasher = Sha256::new();
+            let mut format = None;
+
+            while let Some(chunk) = field.chunk().await.map_err(|e| self.multipart_error(e))? {
+                if data.len() + chunk.len() > self.max_bytes {
+                    return Err(UploadError::TooLarge(self.max_bytes));
+                }
+                hasher.update(&chunk);
+                data.extend_from_slice(&chunk);
+
+                // Twelve bytes tell every supported format apart
+                if format.is_none() && data.len() >= 12 {
+                    format = Some(ImageFormat::sniff(&data).ok_or(UploadError::UnsupportedFormat)?);
+                }
+            }
+
+            let format = match format {
+                Some(format) => format,
+                None => ImageFormat::sniff(&data).ok_or(UploadError::UnsupportedFormat)?,
+            };
+            let sha256 = hex::encode(hasher.finalize());
+
+            let relative_path = format!(
+                "{}/{}/{}.{}",
+                challenge_id,
+                participant_id,
+                sha256,
+                format.extension()
+            );
+
+            // Decoding a full-size photo takes a while, keep it off the async workers
+            let data = Bytes::from(data);
+            let decoded = data.clone();
+            let perceptual_hash =
+                tokio::task::spawn_blocking(move || PerceptualHash::compute(&decoded))
+                    .await
+                    .unwrap_or(Ok(None))
+                    .map_err(|_| UploadError::TooManyPixels(MAX_IMAGE_DIMENSION))?;
+
+            self.store
+                .put(&relative_path, &data, format.content_type())
+                .await?;
+
+            let metadata = PhotoMetadata::read(&data, format);
+            let size_bytes = data.len();
+
+            return Ok(StoredImage {
+                relative_path,
+                format,
+                size_bytes,
+                sha256,
+                metadata,
+                perceptual_hash,
+            });
+        }
+
+        Err(UploadError::MissingImage)
+    }
+
+    /// A URL a moderator can look at a stored photo with, None when the store cannot hand
+    /// one out and the photo is served through the API
+    pub fn review_url(&self, relative_path: &str) -> Result<Option<String>, ImageStoreError> {
+        if !self.store.serves_browsers() {
+            return Ok(None);
+        }
+        self.store
+            .presigned_url(relative_path, REVIEW_URL_EXPIRY)
+            .map(Some)
+    }
+
+    /// A stored photo and its content type, for moderators to look at
+    pub async fn review_image(
+        &self,
+        relative_path: &str,
+    ) -> Result<(Vec<u8>, &'static str), ImageStoreError> {
+        let data = self.store.get(relative_path).await?;
+        let content_type = ImageFormat::sniff(&data)
+            .map(ImageFormat::content_type)
+            .unwrap_or("application/octet-stream");
+
+        Ok((data, content_type))
+    }
+
+    /// The request body limit can trip before the image limit does, both mean too large
+    fn multipart_error(&self, error: MultipartError) -> UploadError {
+        if error.status() == StatusCode::PAYLOAD_TOO_LARGE {
+            UploadError::TooLarge(self.max_bytes)
+        } else {
+            UploadError::Multipart(error.body_text())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::image_store::LocalImageStore;
+    use axum::body::Body;
+    use axum::extract::{FromRequest, Request};
+
+    const PNG: &[u8] = &[
+        0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, 0, 0, 13, b'I', b'H', b'D', b'R',
+    ];
+
+    async fn multipart(filename: &str, data: &[u8]) -> Multipart {
+        let mut body =
+            b"--boundary\r\nContent-Disposition: form-data; name=\"image\"; filename=\"".to_vec();
+        body.extend_from_slice(filename.as_bytes());
+        body.extend_from_slice(b"\"\r\nContent-Type: application/octet-stream\r\n\r\n");
+        body.extend_from_slice(data);
+        body.extend_from_slice(b"\r\n--boundary--\r\n");
+
+        let request = Request::builder()
+            .header("content-type", "multipart/form-data; boundary=boundary")
+            .body(Body::from(body))
+            .unwrap();
+        Multipart::from_request(request, &()).await.unwrap()
+    }
+
+    #[test]
+    fn test_sniff_image_format() {
+        assert_eq!(ImageFormat::sniff(PNG), Some(ImageFormat::Png));
+        assert_eq!(
+            ImageFormat::sniff(&[0xFF, 0xD8, 0xFF, 0xE0]),
+            Some(ImageFormat::Jpeg)
+        );
+        assert_eq!(ImageFormat::sniff(b"GIF89a..."), Some(ImageFormat::Gif));
+        assert_eq!(
+            ImageFormat::sniff(b"RIFF\x10\0\0\0WEBPVP8 "),
+            Some(ImageFormat::Webp)
+        );
+        assert_eq!(ImageFormat::sniff(b"RIFF\x10\0\0\0WAVEfmt "), None);
+        assert_eq!(ImageFormat::sniff(b"<html>"), None);
+        assert_eq!(ImageFormat::sniff(b""), None);
+    }
+
+    #[tokio::test]
+    async fn test_receive_stores_content_addressed() {
+        let base_dir = std::env::temp_dir().join(format!("image-upload-{}", Uuid::new_v4()));
+        let uploader = ImageUploader::new(Arc::new(LocalImageStore::new(&base_dir)), 1024);
+        let participant_id = Uuid::new_v4();
+
+        // The name and extension the client sends do not matter
+        let stored = uploader
+            .receive(&mut multipart("photo.txt", PNG).await, 7, participant_id)
+            .await
+            .unwrap();
+        assert_eq!(stored.format, ImageFormat::Png);
+        assert_eq!(stored.size_bytes, PNG.len());
+        assert_eq!(stored.perceptual_hash, None);
+        assert_eq!(
+            stored.relative_path,
+            format!("7/{participant_id}/{}.png", stored.sha256)
+        );
+        assert_eq!(
+            std::fs::read(base_dir.join(&stored.relative_path)).unwrap(),
+            PNG
+        );
+
+        let again = uploader
+            .receive(&mut multipart("again.png", PNG).await, 7, participant_id)
+            .await
+            .unwrap();
+        assert_eq!(again, stored);
+
+        assert!(matches!(
+            uploader
+                .receive(
+                    &mut multipart("page.jpg", b"<html></html>").await,
+                    7,
+                    participant_id
+                )
+                .await,
+            Err(UploadError::UnsupportedFormat)
+        ));
+        assert!(matches!(
+            uploader
+                .receive(
+                    &mut multipart("big.png", &[PNG, &[0; 1024]].concat()).await,
+                    7,
+                    participant_id
+                )
+                .await,
+            Err(UploadError::TooLarge(1024))
+        ));
+
+        let mut wide = Vec::new();
+        image::GrayImage::new(MAX_IMAGE_DIMENSION + 1, 1)
+            .write_to(
+                &mut std::io::Cursor::new(&mut wide),
+                image::ImageFormat::Png,
+            )
+            .unwrap();
+        assert!(matches!(
+            uploader
+                .receive(&mut multipart("wide.png", &wide).await, 7, participant_id)
+                .await,
+            Err(UploadError::TooManyPixels(MAX_IMAGE_DIMENSION))
+        ));
+
+        std::fs::remove_dir_all(base_dir).unwrap();
+    }
+}
@@ -3,7 +3,9 @@ use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::models::challenge::WaypointData;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GeoLocation {
     pub lat: f64,
     #[serde(rename = "long")]
@@ -45,54 +47,18 @@ impl LocationService {
     }
 
     /// Validate if a location is within the allowed radius of a waypoint
-    pub async fn validate_waypoint_location(
+    pub fn validate_waypoint_location(
         &self,
-        _waypoint_id: i32,
-        _user_location: &GeoLocation,
+        waypoint: &WaypointData,
+        user_location: &GeoLocation,
     ) -> Result<LocationValidationResult, LocationError> {
-        // TODO: Update for temporal challenge system - waypoints are now embedded in challenge JSON
-        // Temporarily return error until waypoint system is redesigned
-        Err(LocationError::WaypointNotFound)
-
-        /*
-        // OLD CODE - waypoints table no longer exists
-        let waypoint = sqlx::query!(
-            r#"
-            SELECT location_lat, location_lon, radius_meters
-            FROM waypoints
-            WHERE waypoint_id = $1
-            "#,
-            waypoint_id
-        )
-        .fetch_optional(&self.pool)
-        .await?
-        .ok_or(LocationError::WaypointNotFound)?;
-        */
-
-        /*
-        // OLD CODE continued - commented out since waypoints table no longer exists
-        let target_location = GeoLocation {
-            lat: waypoint.location_lat,
-            lon: waypoint.location_lon,
-        };
-
-        let distance = self.calculate_distance(&target_location, user_location)?;
-        let is_valid = distance <= waypoint.radius_meters;
-
-        if !is_valid {
-            return Ok(LocationValidationResult {
-                is_valid: false,
-                distance_meters: distance,
-                max_distance_meters: waypoint.radius_meters,
-            });
-        }
+        let distance = self.calculate_distance(&waypoint.location, user_location)?;
 
         Ok(LocationValidationResult {
-            is_valid: true,
+            is_valid: distance <= waypoint.radius_meters,
             distance_meters: distance,
             max_distance_meters: waypoint.radius_meters,
         })
-        */
     }
 
     /// Calculate distance between two GPS coordinates using Haversine formula
@@ -105,6 +71,11 @@ impl LocationService {
         self.validate_coordinates(target)?;
         self.validate_coordinates(current)?;
 
+        Ok(Self::haversine_distance(target, current))
+    }
+
+    /// Great-circle distance in meters between two valid coordinates
+    pub fn haversine_distance(target: &GeoLocation, current: &GeoLocation) -> f64 {
         const EARTH_RADIUS: f64 = 6371000.0; // Earth radius in meters
 
         let lat1_rad = target.lat.to_radians();
@@ -115,9 +86,7 @@ impl LocationService {
         let a = (delta_lat / 2.0).sin().powi(2)
             + lat1_rad.cos() * lat2_rad.cos() * (delta_lon / 2.0).sin().powi(2);
         let c = 2.0 * a.sqrt().atan2((1.0 - a).sqrt());
-        let distance = EARTH_RADIUS * c;
-
-        Ok(distance)
+        EARTH_RADIUS * c
     }
 
     /// Validate GPS coordinates are within valid ranges
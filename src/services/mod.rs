@@ -1,9 +1,28 @@
 pub mod auth_service;
+pub mod challenge_scheduler;
+pub mod email_service;
+pub mod event_hub;
+pub mod exif;
 pub mod image_service;
+pub mod image_store;
+pub mod image_upload;
 pub mod location_service;
+pub mod notification_service;
+pub mod perceptual_hash;
+pub mod proof_jobs;
+pub mod proof_verifier;
 
 pub use auth_service::{
     AuthResponse, AuthService, AuthServiceError, ParticipantAuthResponse, ParticipantTokenRequest,
 };
-pub use image_service::ImageService;
+pub use challenge_scheduler::ChallengeScheduler;
+pub use email_service::{Mailer, SmtpMailer};
+pub use event_hub::{ChallengeEvent, ChallengeEventHub, ChallengeEventType};
+pub use image_service::{ImageCheckerPolicy, ImageService};
+pub use image_store::{ImageStore, LocalImageStore, S3ImageStore, S3Settings};
+pub use image_upload::{ImageUploader, UploadError};
 pub use location_service::{LocationService, LocationValidationRequest};
+pub use notification_service::{HttpPushNotifier, NotificationService, Notifier, SpoolNotifier};
+pub use perceptual_hash::PerceptualHash;
+pub use proof_jobs::ProofJobService;
+pub use proof_verifier::{ProofError, ProofSubmission, ProofVerdict};
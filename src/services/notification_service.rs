@@ -0,0 +1,377 @@
+use axum::async_trait;
+use reqwest::Client;
+use serde::Serialize;
+use sqlx::PgPool;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+use crate::models::notification::{
+    DevicePlatform, DeviceToken, NotificationError, NotificationStatus, OutboxNotification,
+};
+
+/// Delivery attempts before an outbox notification is marked failed
+const DEFAULT_MAX_ATTEMPTS: i32 = 5;
+/// Notifications claimed per dispatcher pass
+const DISPATCH_BATCH_SIZE: i64 = 50;
+
+/// A single push message addressed to one device
+#[derive(Debug, Clone, Serialize)]
+pub struct PushNotification {
+    #[serde(rename = "to")]
+    pub device_token: String,
+    pub platform: DevicePlatform,
+    pub notification: PushContent,
+    pub data: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PushContent {
+    pub title: String,
+    pub body: String,
+}
+
+impl From<&OutboxNotification> for PushNotification {
+    fn from(outbox: &OutboxNotification) -> Self {
+        Self {
+            device_token: outbox.device_token.clone(),
+            platform: outbox.platform,
+            notification: PushContent {
+                title: outbox.title.clone(),
+                body: outbox.body.clone(),
+            },
+            data: outbox.data.clone(),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum NotifierError {
+    #[error("HTTP request failed: {0}")]
+    RequestFailed(#[from] reqwest::Error),
+    #[error("Push gateway rejected notification with status {status}: {body}")]
+    Rejected { status: u16, body: String },
+    #[error("Device token is no longer valid")]
+    InvalidDeviceToken,
+    #[error("Spool write failed: {0}")]
+    SpoolFailed(#[from] std::io::Error),
+}
+
+impl NotifierError {
+    /// Permanent failures are not retried
+    pub fn is_permanent(&self) -> bool {
+        match self {
+            NotifierError::InvalidDeviceToken => true,
+            NotifierError::Rejected { status, .. } => (400..500).contains(status) && *status != 429,
+            _ => false,
+        }
+    }
+}
+
+/// A push notification provider
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn send(&self, notification: &PushNotification) -> Result<(), NotifierError>;
+}
+
+/// Delivers notifications to an FCM/APNs-style HTTP push gateway
+pub struct HttpPushNotifier {
+    client: Client,
+    endpoint: String,
+    api_key: Option<String>,
+}
+
+impl HttpPushNotifier {
+    pub fn new(endpoint: String, api_key: Option<String>) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            client,
+            endpoint,
+            api_key,
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for HttpPushNotifier {
+    fn name(&self) -> &'static str {
+        "http"
+    }
+
+    async fn send(&self, notification: &PushNotification) -> Result<(), NotifierError> {
+        let mut request = self.client.post(&self.endpoint).json(notification);
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request.send().await?;
+        let status = response.status();
+
+        if status.is_success() {
+            return Ok(());
+        }
+
+        // Gateways answer 404/410 for tokens of uninstalled apps
+        if status == reqwest::StatusCode::NOT_FOUND || status == reqwest::StatusCode::GONE {
+            return Err(NotifierError::InvalidDeviceToken);
+        }
+
+        Err(NotifierError::Rejected {
+            status: status.as_u16(),
+            body: response.text().await.unwrap_or_default(),
+        })
+    }
+}
+
+/// Writes each notification as a JSON file into a spool directory, for local
+/// development and tests
+pub struct SpoolNotifier {
+    spool_dir: PathBuf,
+}
+
+impl SpoolNotifier {
+    pub fn new(spool_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            spool_dir: spool_dir.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for SpoolNotifier {
+    fn name(&self) -> &'static str {
+        "spool"
+    }
+
+    async fn send(&self, notification: &PushNotification) -> Result<(), NotifierError> {
+        tokio::fs::create_dir_all(&self.spool_dir).await?;
+
+        let file_name = format!(
+            "{}-{}.json",
+            chrono::Utc::now().format("%Y%m%dT%H%M%S%.6f"),
+            Uuid::new_v4()
+        );
+        let contents = serde_json::to_vec_pretty(notification).map_err(std::io::Error::other)?;
+
+        tokio::fs::write(self.spool_dir.join(file_name), contents).await?;
+
+        Ok(())
+    }
+}
+
+/// Queues notifications in the outbox and drains it through a `Notifier`
+pub struct NotificationService {
+    pool: PgPool,
+    notifier: Arc<dyn Notifier>,
+    max_attempts: i32,
+    wakeup: Notify,
+}
+
+impl NotificationService {
+    pub fn new(pool: PgPool, notifier: Arc<dyn Notifier>) -> Self {
+        Self {
+            pool,
+            notifier,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            wakeup: Notify::new(),
+        }
+    }
+
+    /// Queue a notification for all devices of the given users and wake the dispatcher
+    pub async fn notify_users(
+        &self,
+        user_ids: &[i32],
+        challenge_id: Option<i32>,
+        title: &str,
+        body: &str,
+        data: serde_json::Value,
+    ) -> Result<u64, NotificationError> {
+        let queued = OutboxNotification::enqueue_for_users(
+            &self.pool,
+            user_ids,
+            challenge_id,
+            title,
+            body,
+            &data,
+        )
+        .await?;
+
+        if queued > 0 {
+            self.wakeup.notify_one();
+        }
+
+        Ok(queued)
+    }
+
+    /// Attempt delivery of one batch of due notifications, returns how many were attempted
+    pub async fn dispatch_due(&self) -> Result<usize, NotificationError> {
+        let notifications = OutboxNotification::claim_due(&self.pool, DISPATCH_BATCH_SIZE).await?;
+        let attempted = notifications.len();
+
+        for outbox in notifications {
+            match self.notifier.send(&PushNotification::from(&outbox)).await {
+                Ok(()) => {
+                    OutboxNotification::mark_sent(&self.pool, outbox.notification_id).await?;
+                }
+                // The app was uninstalled, nothing sent to the token will arrive again
+                Err(NotifierError::InvalidDeviceToken) => {
+                    DeviceToken::remove_invalid(&self.pool, outbox.device_id).await?;
+                    tracing::info!(
+                        "Removed device {} after {} reported its token invalid",
+                        outbox.device_id,
+                        self.notifier.name()
+                    );
+                }
+                Err(e) => {
+                    let status = outbox
+                        .mark_failed(
+                            &self.pool,
+                            &e.to_string(),
+                            self.max_attempts,
+                            e.is_permanent(),
+                        )
+                        .await?;
+
+                    if status == NotificationStatus::Failed {
+                        tracing::warn!(
+                            "Notification {} via {} failed permanently after {} attempts: {}",
+                            outbox.notification_id,
+                            self.notifier.name(),
+                            outbox.attempts,
+                            e
+                        );
+                    } else {
+                        tracing::info!(
+                            "Notification {} via {} failed, will retry: {}",
+                            outbox.notification_id,
+                            self.notifier.name(),
+                            e
+                        );
+                    }
+                }
+            }
+        }
+
+        Ok(attempted)
+    }
+
+    /// Dispatcher loop: drains the outbox when woken by new notifications and
+    /// at every poll interval so retries become due
+    pub async fn run_dispatcher(self: Arc<Self>, poll_interval: Duration) {
+        tracing::info!(
+            "Notification dispatcher started with provider: {}",
+            self.notifier.name()
+        );
+
+        loop {
+            loop {
+                match self.dispatch_due().await {
+                    Ok(attempted) if attempted as i64 >= DISPATCH_BATCH_SIZE => continue,
+                    Ok(_) => break,
+                    Err(e) => {
+                        tracing::error!("Notification dispatch failed with error: {}", e);
+                        break;
+                    }
+                }
+            }
+
+            tokio::select! {
+                _ = self.wakeup.notified() => {}
+                _ = tokio::time::sleep(poll_interval) => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::post, Json, Router};
+    use std::sync::Mutex;
+
+    fn sample_notification() -> PushNotification {
+        PushNotification {
+            device_token: "device-abc".to_string(),
+            platform: DevicePlatform::Fcm,
+            notification: PushContent {
+                title: "Park Hunt has started".to_string(),
+                body: "Find the red box".to_string(),
+            },
+            data: serde_json::json!({"challenge-id": 1}),
+        }
+    }
+
+    #[test]
+    fn test_notifier_error_permanence() {
+        assert!(NotifierError::InvalidDeviceToken.is_permanent());
+        assert!(NotifierError::Rejected {
+            status: 400,
+            body: String::new()
+        }
+        .is_permanent());
+        assert!(!NotifierError::Rejected {
+            status: 429,
+            body: String::new()
+        }
+        .is_permanent());
+        assert!(!NotifierError::Rejected {
+            status: 503,
+            body: String::new()
+        }
+        .is_permanent());
+    }
+
+    #[tokio::test]
+    async fn test_spool_notifier_writes_notification_file() {
+        let spool_dir = std::env::temp_dir().join(format!("spool-{}", Uuid::new_v4()));
+        let notifier = SpoolNotifier::new(&spool_dir);
+
+        notifier.send(&sample_notification()).await.unwrap();
+
+        let mut entries = std::fs::read_dir(&spool_dir).unwrap();
+        let path = entries.next().unwrap().unwrap().path();
+        let contents: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(path).unwrap()).unwrap();
+        assert_eq!(contents["to"], "device-abc");
+        assert_eq!(contents["notification"]["body"], "Find the red box");
+
+        std::fs::remove_dir_all(spool_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_http_notifier_against_loopback_gateway() {
+        let received: Arc<Mutex<Vec<serde_json::Value>>> = Arc::default();
+        let captured = received.clone();
+        let gateway = Router::new()
+            .route(
+                "/push",
+                post(move |Json(body): Json<serde_json::Value>| {
+                    let captured = captured.clone();
+                    async move {
+                        captured.lock().unwrap().push(body);
+                    }
+                }),
+            )
+            .route("/gone", post(|| async { axum::http::StatusCode::GONE }));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, gateway).await.unwrap() });
+
+        let notifier = HttpPushNotifier::new(format!("http://{address}/push"), None);
+        notifier.send(&sample_notification()).await.unwrap();
+        assert_eq!(received.lock().unwrap()[0]["platform"], "FCM");
+
+        let notifier = HttpPushNotifier::new(format!("http://{address}/gone"), None);
+        let error = notifier.send(&sample_notification()).await.unwrap_err();
+        assert!(matches!(error, NotifierError::InvalidDeviceToken));
+    }
+}
@@ -0,0 +1,166 @@
+use image::imageops::FilterType;
+use image::{ImageError, ImageReader, Limits};
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+
+/// Hashes at most this many bits apart are taken to be the same picture
+pub const NEAR_DUPLICATE_MAX_DISTANCE: u32 = 6;
+/// Widest or tallest photo decoded, a 48 megapixel phone camera takes 8000x6000
+pub const MAX_IMAGE_DIMENSION: u32 = 12_000;
+/// Most memory decoding a photo may take
+const MAX_DECODE_BYTES: u64 = 256 * 1024 * 1024;
+
+/// A photo too large to decode, a small file can declare huge dimensions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("Image exceeds the decoding limits")]
+pub struct ImageTooLarge;
+
+/// A 64-bit difference hash of a photo: each bit tells whether a pixel of a 9x8 grayscale
+/// thumbnail is brighter than its right neighbour. Re-encoding, resizing or light edits
+/// change few bits, a different picture changes about half of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PerceptualHash(pub u64);
+
+impl PerceptualHash {
+    /// Hash an image, None when it cannot be decoded. Images past the decoding limits are
+    /// refused before their pixels are allocated.
+    pub fn compute(data: &[u8]) -> Result<Option<Self>, ImageTooLarge> {
+        let mut limits = Limits::default();
+        limits.max_image_width = Some(MAX_IMAGE_DIMENSION);
+        limits.max_image_height = Some(MAX_IMAGE_DIMENSION);
+        limits.max_alloc = Some(MAX_DECODE_BYTES);
+
+        let Ok(mut reader) = ImageReader::new(Cursor::new(data)).with_guessed_format() else {
+            return Ok(None);
+        };
+        reader.limits(limits);
+        let image = match reader.decode() {
+            Ok(image) => image,
+            Err(ImageError::Limits(_)) => return Err(ImageTooLarge),
+            Err(_) => return Ok(None),
+        };
+        let thumbnail = image.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+
+        let mut hash = 0u64;
+        for y in 0..8 {
+            for x in 0..8 {
+                let brighter = thumbnail.get_pixel(x, y)[0] > thumbnail.get_pixel(x + 1, y)[0];
+                hash = (hash << 1) | u64::from(brighter);
+            }
+        }
+
+        Ok(Some(PerceptualHash(hash)))
+    }
+
+    /// Number of bits the hashes differ in
+    pub fn distance(self, other: PerceptualHash) -> u32 {
+        (self.0 ^ other.0).count_ones()
+    }
+
+    /// The same bits as a signed BIGINT, the way Postgres stores them
+    pub fn to_i64(self) -> i64 {
+        self.0 as i64
+    }
+
+    pub fn from_i64(value: i64) -> Self {
+        PerceptualHash(value as u64)
+    }
+}
+
+impl std::fmt::Display for PerceptualHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageBuffer, Rgb};
+
+    fn encode(
+        width: u32,
+        height: u32,
+        format: image::ImageFormat,
+        pixel: impl Fn(u32, u32) -> Rgb<u8>,
+    ) -> Vec<u8> {
+        let mut data = Vec::new();
+        ImageBuffer::from_fn(width, height, pixel)
+            .write_to(&mut Cursor::new(&mut data), format)
+            .unwrap();
+        data
+    }
+
+    /// A scene with some structure: a diagonal gradient with a bright disc off centre
+    fn scene(width: u32, height: u32) -> impl Fn(u32, u32) -> Rgb<u8> {
+        move |x, y| {
+            let (fx, fy) = (x as f64 / width as f64, y as f64 / height as f64);
+            let disc = (fx - 0.3).powi(2) + (fy - 0.6).powi(2) < 0.04;
+            let level = if disc {
+                250
+            } else {
+                (fx * 120.0 + fy * 80.0) as u8
+            };
+            Rgb([level, level, level / 2])
+        }
+    }
+
+    #[test]
+    fn test_same_picture_hashes_near() {
+        let original =
+            PerceptualHash::compute(&encode(320, 240, image::ImageFormat::Png, scene(320, 240)))
+                .unwrap()
+                .unwrap();
+
+        // Resized, re-encoded as JPEG and slightly brightened
+        let resized =
+            PerceptualHash::compute(&encode(160, 120, image::ImageFormat::Jpeg, |x, y| {
+                let Rgb([r, g, b]) = scene(160, 120)(x, y);
+                Rgb([
+                    r.saturating_add(10),
+                    g.saturating_add(10),
+                    b.saturating_add(10),
+                ])
+            }))
+            .unwrap()
+            .unwrap();
+        assert!(original.distance(resized) <= NEAR_DUPLICATE_MAX_DISTANCE);
+
+        // Mirrored, the structure runs the other way
+        let mirrored =
+            PerceptualHash::compute(&encode(320, 240, image::ImageFormat::Png, |x, y| {
+                scene(320, 240)(319 - x, y)
+            }))
+            .unwrap()
+            .unwrap();
+        assert!(original.distance(mirrored) > NEAR_DUPLICATE_MAX_DISTANCE);
+    }
+
+    #[test]
+    fn test_undecodable_image_has_no_hash() {
+        assert_eq!(
+            PerceptualHash::compute(b"\xFF\xD8\xFF\xE0 truncated"),
+            Ok(None)
+        );
+        assert_eq!(PerceptualHash::compute(b""), Ok(None));
+    }
+
+    #[test]
+    fn test_oversized_image_is_refused() {
+        let wide = encode(
+            MAX_IMAGE_DIMENSION + 1,
+            1,
+            image::ImageFormat::Png,
+            |_, _| Rgb([0, 0, 0]),
+        );
+        assert_eq!(PerceptualHash::compute(&wide), Err(ImageTooLarge));
+    }
+
+    #[test]
+    fn test_hash_round_trips_through_i64() {
+        let hash = PerceptualHash(0xF0F0_0000_FFFF_0001);
+        assert_eq!(PerceptualHash::from_i64(hash.to_i64()), hash);
+        assert_eq!(hash.to_string(), "f0f00000ffff0001");
+        assert_eq!(hash.distance(PerceptualHash(0xF0F0_0000_FFFF_0000)), 1);
+    }
+}
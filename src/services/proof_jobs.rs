@@ -0,0 +1,527 @@
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use std::sync::Arc;
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+use crate::models::audit_log::{AuditLog, WaypointVerificationParams};
+use crate::models::challenge::{
+    ChallengeData, ChallengeError, ChallengeParticipant, ParticipantStatus, TemporalChallenge,
+    WaypointState,
+};
+use crate::models::proof_job::{ProofJob, ProofJobError, ProofJobStatus, ProofReview};
+use crate::services::event_hub::{ChallengeEvent, ChallengeEventHub};
+use crate::services::exif::PhotoMetadata;
+use crate::services::image_service::{ImageError, ImageService, ValidationResult};
+use crate::services::proof_verifier::ProofVerdict;
+
+/// How long a photo may wait for a verdict before its job fails
+const PROOF_JOB_TIMEOUT_MINUTES: i64 = 10;
+/// Polling the image checker backs off from this delay up to the cap
+const POLL_BASE_MILLIS: i64 = 500;
+const POLL_MAX_SECONDS: i64 = 30;
+/// The image checker's resolution when it cannot tell either way
+const UNCERTAIN_RESOLUTION: &str = "uncertain";
+
+/// Delay before the next look at a job after `attempts` claims
+pub fn poll_delay(attempts: i32) -> Duration {
+    let exponent = attempts.clamp(1, 16) - 1;
+    let millis = POLL_BASE_MILLIS.saturating_mul(1 << exponent);
+    Duration::milliseconds(millis.min(POLL_MAX_SECONDS * 1000))
+}
+
+impl From<ValidationResult> for ProofVerdict {
+    fn from(result: ValidationResult) -> Self {
+        if result.resolution == "accepted" {
+            ProofVerdict::Accepted
+        } else {
+            ProofVerdict::Rejected {
+                reasons: result.reasons.unwrap_or_default(),
+            }
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+enum ProofStepError {
+    #[error(transparent)]
+    Job(#[from] ProofJobError),
+    #[error(transparent)]
+    Challenge(#[from] ChallengeError),
+    #[error(transparent)]
+    ImageCheck(#[from] ImageError),
+}
+
+/// Verifies photo proofs in the background: workers send queued photos to the image checker,
+/// poll it for a verdict and apply the verdict to the participant
+pub struct ProofJobService {
+    pool: PgPool,
+    image_service: Arc<ImageService>,
+    event_hub: Arc<ChallengeEventHub>,
+    wakeup: Notify,
+}
+
+impl ProofJobService {
+    pub fn new(
+        pool: PgPool,
+        image_service: Arc<ImageService>,
+        event_hub: Arc<ChallengeEventHub>,
+    ) -> Self {
+        Self {
+            pool,
+            image_service,
+            event_hub,
+            wakeup: Notify::new(),
+        }
+    }
+
+    /// Queue a stored photo for verification and wake a worker
+    pub async fn enqueue(
+        &self,
+        participant_id: Uuid,
+        challenge_id: i32,
+        waypoint_sequence: i32,
+        image_path: &str,
+        photo_metadata: &PhotoMetadata,
+    ) -> Result<ProofJob, ProofJobError> {
+        let job = ProofJob::create(
+            &self.pool,
+            participant_id,
+            challenge_id,
+            waypoint_sequence,
+            image_path,
+            photo_metadata,
+        )
+        .await?;
+        self.wakeup.notify_one();
+
+        Ok(job)
+    }
+
+    /// Hold a stored photo for a moderator without asking the image checker
+    pub async fn enqueue_for_review(
+        &self,
+        participant_id: Uuid,
+        challenge_id: i32,
+        waypoint_sequence: i32,
+        image_path: &str,
+        photo_metadata: &PhotoMetadata,
+        reason: &str,
+    ) -> Result<ProofJob, ProofJobError> {
+        let job = ProofJob::create_for_review(
+            &self.pool,
+            participant_id,
+            challenge_id,
+            waypoint_sequence,
+            image_path,
+            photo_metadata,
+            reason,
+        )
+        .await?;
+        self.log_outcome(&job, "manual_review").await;
+
+        Ok(job)
+    }
+
+    /// Take one due job a step further, returns whether there was one
+    pub async fn process_due(&self) -> Result<bool, ProofJobError> {
+        let Some(mut job) = ProofJob::claim_due(&self.pool, 1).await?.pop() else {
+            return Ok(false);
+        };
+
+        if Utc::now() - job.created_at > Duration::minutes(PROOF_JOB_TIMEOUT_MINUTES) {
+            self.fail(&mut job, "Timed out waiting for the image checker")
+                .await?;
+            return Ok(true);
+        }
+
+        let step = match job.status {
+            ProofJobStatus::Queued => self.submit(&mut job).await,
+            _ => self.poll(&mut job).await,
+        };
+        match step {
+            Ok(()) => {}
+            // With the image checker down a moderator looks at the photo instead
+            Err(ProofStepError::ImageCheck(ImageError::CircuitOpen)) => {
+                let reason =
+                    "The image checker is unavailable, the photo was queued for manual review";
+                self.hold_for_review(&mut job, &[reason.to_string()])
+                    .await?;
+            }
+            // Asking again will not change the image checker's mind about a bad request
+            Err(ProofStepError::ImageCheck(e @ ImageError::ClientError(_))) => {
+                tracing::error!("Image checker refused proof {}: {}", job.processing_id, e);
+                self.fail(&mut job, "The image checker could not process the photo")
+                    .await?;
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Proof job {} failed a step, will retry: {}",
+                    job.processing_id,
+                    e
+                );
+                job.reschedule(
+                    &self.pool,
+                    job.status,
+                    Utc::now() + poll_delay(job.attempts),
+                    Some(&e.to_string()),
+                )
+                .await?;
+            }
+        }
+
+        Ok(true)
+    }
+
+    async fn submit(&self, job: &mut ProofJob) -> Result<(), ProofStepError> {
+        let challenge_data = TemporalChallenge::get_current_by_id(&self.pool, job.challenge_id)
+            .await?
+            .get_challenge_data()?;
+        let Some(waypoint) = challenge_data
+            .waypoints
+            .iter()
+            .find(|w| w.waypoint_sequence == job.waypoint_sequence)
+        else {
+            self.fail(job, "The waypoint no longer exists").await?;
+            return Ok(());
+        };
+
+        self.image_service
+            .submit(
+                &job.processing_id.to_string(),
+                &job.image_path,
+                &waypoint.image_subject,
+                Some(&waypoint.location),
+                Some(waypoint.radius_meters),
+                None,
+            )
+            .await?;
+
+        job.status = ProofJobStatus::Submitted;
+        job.reschedule(
+            &self.pool,
+            ProofJobStatus::Submitted,
+            Utc::now() + poll_delay(job.attempts),
+            None,
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn poll(&self, job: &mut ProofJob) -> Result<(), ProofStepError> {
+        let processing_id = job.processing_id.to_string();
+        let status = self.image_service.check_status(&processing_id).await?;
+
+        match status.status.as_str() {
+            "completed" => {
+                let result = self.image_service.get_results(&processing_id).await?;
+                self.apply_result(job, result).await?;
+            }
+            "failed" => {
+                self.fail(job, "The image checker could not process the photo")
+                    .await?;
+            }
+            "in_progress" | "accepted" => {
+                job.reschedule(
+                    &self.pool,
+                    ProofJobStatus::Submitted,
+                    Utc::now() + poll_delay(job.attempts),
+                    None,
+                )
+                .await?;
+            }
+            other => {
+                return Err(
+                    ImageError::UnexpectedResponse(format!("Unknown status: {other}")).into(),
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Apply what the image checker made of a photo, holding it for a moderator when the
+    /// checker was unsure. Returns false when the job already had an outcome.
+    pub async fn apply_result(
+        &self,
+        job: &mut ProofJob,
+        result: ValidationResult,
+    ) -> Result<bool, ProofJobError> {
+        if result.resolution != UNCERTAIN_RESOLUTION {
+            return self.apply_verdict(job, result.into()).await;
+        }
+
+        let reasons = result.reasons.unwrap_or_else(|| {
+            vec![
+                "The image checker could not tell whether the photo shows the waypoint".to_string(),
+            ]
+        });
+        self.hold_for_review(job, &reasons).await
+    }
+
+    /// Record the image checker's verdict and, when accepted, verify the waypoint. Returns
+    /// false when the job already had an outcome, which is then left alone.
+    pub async fn apply_verdict(
+        &self,
+        job: &mut ProofJob,
+        verdict: ProofVerdict,
+    ) -> Result<bool, ProofJobError> {
+        let (status, reasons) = match &verdict {
+            ProofVerdict::Accepted => (ProofJobStatus::Accepted, Vec::new()),
+            ProofVerdict::Rejected { reasons } => (ProofJobStatus::Rejected, reasons.clone()),
+        };
+        if !job.finish(&self.pool, status, &reasons).await? {
+            return Ok(false);
+        }
+        self.log_outcome(job, verdict.resolution()).await;
+        self.complete(job, &verdict).await;
+
+        Ok(true)
+    }
+
+    /// Record a moderator's verdict on a photo held for review, then verify the waypoint
+    /// when approved just as for the image checker's verdicts
+    pub async fn decide_review(
+        &self,
+        review: &mut ProofReview,
+        reviewer_id: i32,
+        verdict: ProofVerdict,
+        comment: Option<&str>,
+    ) -> Result<(), ProofJobError> {
+        let (status, reasons) = match &verdict {
+            ProofVerdict::Accepted => (ProofJobStatus::Accepted, Vec::new()),
+            ProofVerdict::Rejected { reasons } => (ProofJobStatus::Rejected, reasons.clone()),
+        };
+        review
+            .decide(&self.pool, reviewer_id, status, &reasons, comment)
+            .await?;
+
+        let job = review.to_job();
+        self.log_outcome_with(
+            &job,
+            verdict.resolution(),
+            serde_json::json!({
+                "processing_id": job.processing_id,
+                "reviewer_id": reviewer_id,
+                "comment": comment,
+            }),
+        )
+        .await;
+        self.complete(&job, &verdict).await;
+
+        Ok(())
+    }
+
+    /// Verify the waypoint of an accepted photo and tell the participant the outcome
+    async fn complete(&self, job: &ProofJob, verdict: &ProofVerdict) {
+        if *verdict == ProofVerdict::Accepted {
+            if let Err(e) = self.verify_waypoint(job).await {
+                tracing::error!(
+                    "Failed to verify waypoint {} for participant {} after accepted proof {}: {}",
+                    job.waypoint_sequence,
+                    job.participant_id,
+                    job.processing_id,
+                    e
+                );
+            }
+        }
+        self.event_hub.publish(ChallengeEvent::proof_verified(job));
+    }
+
+    /// Give up on a job, the participant stays checked in and may send another photo
+    async fn fail(&self, job: &mut ProofJob, reason: &str) -> Result<(), ProofJobError> {
+        if job
+            .finish(&self.pool, ProofJobStatus::Failed, &[reason.to_string()])
+            .await?
+        {
+            tracing::warn!("Proof job {} failed: {}", job.processing_id, reason);
+            self.log_outcome(job, "failed").await;
+            self.event_hub.publish(ChallengeEvent::proof_verified(job));
+        }
+        Ok(())
+    }
+
+    /// Leave a pending job to a moderator, returns false when it already had an outcome
+    async fn hold_for_review(
+        &self,
+        job: &mut ProofJob,
+        reasons: &[String],
+    ) -> Result<bool, ProofJobError> {
+        if !job.hold_for_review(&self.pool, reasons).await? {
+            return Ok(false);
+        }
+
+        tracing::warn!(
+            "Proof {} queued for manual review: {}",
+            job.processing_id,
+            reasons.join("; ")
+        );
+        self.log_outcome(job, "manual_review").await;
+        self.event_hub.publish(ChallengeEvent::proof_verified(job));
+        Ok(true)
+    }
+
+    async fn log_outcome(&self, job: &ProofJob, verification_result: &str) {
+        self.log_outcome_with(
+            job,
+            verification_result,
+            serde_json::json!({ "processing_id": job.processing_id }),
+        )
+        .await;
+    }
+
+    async fn log_outcome_with(
+        &self,
+        job: &ProofJob,
+        verification_result: &str,
+        outcome_payload: serde_json::Value,
+    ) {
+        let processing_time = Utc::now() - job.created_at;
+
+        if let Err(e) = AuditLog::log_waypoint_verified(
+            &self.pool,
+            WaypointVerificationParams {
+                participant_id: job.participant_id,
+                challenge_id: job.challenge_id,
+                waypoint_id: job.waypoint_sequence,
+                waypoint_sequence: job.waypoint_sequence,
+                verification_result,
+                verification_reasons: (!job.reasons.is_empty()).then_some(job.reasons.as_slice()),
+                processing_time_seconds: processing_time.num_milliseconds() as f64 / 1000.0,
+                outcome_payload: Some(outcome_payload),
+            },
+        )
+        .await
+        {
+            tracing::warn!("Failed to log proof job outcome: {}", e);
+        }
+    }
+
+    /// Verify the waypoint unless the participant moved on while the photo was checked, for
+    /// instance through a moderator override
+    async fn verify_waypoint(&self, job: &ProofJob) -> Result<(), ChallengeError> {
+        let mut participant =
+            ChallengeParticipant::get_by_id(&self.pool, job.participant_id).await?;
+        let challenge_data = TemporalChallenge::get_current_by_id(&self.pool, job.challenge_id)
+            .await?
+            .get_challenge_data()?;
+
+        if participant.participant_status != ParticipantStatus::Active
+            || !participant.is_checked_in_at(&challenge_data, job.waypoint_sequence)
+        {
+            tracing::info!(
+                "Participant {} no longer waits on waypoint {}, accepted proof {} left unapplied",
+                job.participant_id,
+                job.waypoint_sequence,
+                job.processing_id
+            );
+            return Ok(());
+        }
+
+        complete_proven_waypoint(
+            &self.pool,
+            &self.event_hub,
+            &mut participant,
+            &challenge_data,
+            job.waypoint_sequence,
+        )
+        .await
+    }
+
+    /// Worker pool: each worker takes due jobs one at a time, woken by new jobs and at
+    /// every poll interval so polls and retries become due
+    pub async fn run_workers(self: Arc<Self>, workers: usize, poll_interval: std::time::Duration) {
+        tracing::info!("Starting {} proof verification workers", workers);
+
+        for _ in 0..workers {
+            let service = self.clone();
+            tokio::spawn(async move {
+                loop {
+                    loop {
+                        match service.process_due().await {
+                            Ok(true) => continue,
+                            Ok(false) => break,
+                            Err(e) => {
+                                tracing::error!("Proof job processing failed with error: {}", e);
+                                break;
+                            }
+                        }
+                    }
+
+                    tokio::select! {
+                        _ = service.wakeup.notified() => {}
+                        _ = tokio::time::sleep(poll_interval) => {}
+                    }
+                }
+            });
+        }
+    }
+}
+
+/// Verify a proven waypoint and tell the participant's subscribers where they are now
+pub async fn complete_proven_waypoint(
+    pool: &PgPool,
+    event_hub: &ChallengeEventHub,
+    participant: &mut ChallengeParticipant,
+    challenge_data: &ChallengeData,
+    waypoint_sequence: i32,
+) -> Result<(), ChallengeError> {
+    let presented = participant
+        .complete_waypoint(pool, challenge_data, waypoint_sequence)
+        .await?;
+
+    event_hub.publish(ChallengeEvent::waypoint_state_changed(
+        participant.challenge_id,
+        participant.participant_id,
+        waypoint_sequence,
+        WaypointState::Verified,
+    ));
+    if let Some(presented) = presented {
+        event_hub.publish(ChallengeEvent::waypoint_state_changed(
+            participant.challenge_id,
+            participant.participant_id,
+            presented,
+            WaypointState::Presented,
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_poll_delay_backs_off_and_caps() {
+        assert_eq!(poll_delay(0), Duration::milliseconds(500));
+        assert_eq!(poll_delay(1), Duration::milliseconds(500));
+        assert_eq!(poll_delay(2), Duration::seconds(1));
+        assert_eq!(poll_delay(4), Duration::seconds(4));
+        assert_eq!(poll_delay(10), Duration::seconds(30));
+        assert_eq!(poll_delay(1000), Duration::seconds(30));
+    }
+
+    #[test]
+    fn test_validation_result_into_verdict() {
+        let result = |resolution: &str, reasons: Option<Vec<String>>| ValidationResult {
+            resolution: resolution.to_string(),
+            reasons,
+        };
+
+        assert_eq!(
+            ProofVerdict::from(result("accepted", None)),
+            ProofVerdict::Accepted
+        );
+        assert_eq!(
+            ProofVerdict::from(result("rejected", Some(vec!["No bench".to_string()]))),
+            ProofVerdict::Rejected {
+                reasons: vec!["No bench".to_string()]
+            }
+        );
+        assert_eq!(
+            ProofVerdict::from(result("rejected", None)),
+            ProofVerdict::Rejected { reasons: vec![] }
+        );
+    }
+}
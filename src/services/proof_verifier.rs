@@ -0,0 +1,303 @@
+use axum::async_trait;
+use serde::Deserialize;
+
+use crate::models::challenge::{ProofKind, WaypointData};
+
+/// What a participant sent as proof for the kinds verified on the spot, as JSON with the
+/// field their kind needs. Image proofs arrive as multipart uploads instead.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ProofSubmission {
+    pub code: Option<String>,
+    pub answer: Option<String>,
+    pub choice: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProofVerdict {
+    Accepted,
+    Rejected { reasons: Vec<String> },
+}
+
+impl ProofVerdict {
+    fn rejected(reason: &str) -> Self {
+        ProofVerdict::Rejected {
+            reasons: vec![reason.to_string()],
+        }
+    }
+
+    /// The verification result recorded in the audit log
+    pub fn resolution(&self) -> &'static str {
+        match self {
+            ProofVerdict::Accepted => "accepted",
+            ProofVerdict::Rejected { .. } => "rejected",
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ProofError {
+    #[error("This waypoint expects {0} as proof")]
+    MissingSubmission(&'static str),
+}
+
+/// Decides whether a submission proves a waypoint, one implementation per `ProofKind`
+#[async_trait]
+pub trait ProofVerifier: Send + Sync {
+    async fn verify(
+        &self,
+        waypoint: &WaypointData,
+        submission: &ProofSubmission,
+    ) -> Result<ProofVerdict, ProofError>;
+}
+
+/// The verifier for a waypoint's proof kind. Image proofs have none, the photo is verified
+/// in the background by the proof job workers.
+pub fn verifier_for<'a>(proof: &'a ProofKind) -> Option<Box<dyn ProofVerifier + 'a>> {
+    let verifier: Box<dyn ProofVerifier + 'a> = match proof {
+        ProofKind::Image => return None,
+        ProofKind::QrCode { secret } => Box::new(QrCodeVerifier { secret }),
+        ProofKind::TextAnswer {
+            accepted_answers,
+            max_typos,
+        } => Box::new(TextAnswerVerifier {
+            accepted_answers,
+            max_typos: *max_typos,
+        }),
+        ProofKind::MultipleChoice { correct, .. } => Box::new(MultipleChoiceVerifier { correct }),
+        ProofKind::LocationOnly => Box::new(LocationOnlyVerifier),
+    };
+
+    Some(verifier)
+}
+
+pub struct QrCodeVerifier<'a> {
+    secret: &'a str,
+}
+
+#[async_trait]
+impl ProofVerifier for QrCodeVerifier<'_> {
+    async fn verify(
+        &self,
+        _waypoint: &WaypointData,
+        submission: &ProofSubmission,
+    ) -> Result<ProofVerdict, ProofError> {
+        let code = submission
+            .code
+            .as_deref()
+            .ok_or(ProofError::MissingSubmission("a QR code"))?;
+
+        if code.trim() == self.secret.trim() {
+            Ok(ProofVerdict::Accepted)
+        } else {
+            Ok(ProofVerdict::rejected(
+                "The QR code does not belong to this waypoint",
+            ))
+        }
+    }
+}
+
+pub struct TextAnswerVerifier<'a> {
+    accepted_answers: &'a [String],
+    max_typos: Option<usize>,
+}
+
+#[async_trait]
+impl ProofVerifier for TextAnswerVerifier<'_> {
+    async fn verify(
+        &self,
+        _waypoint: &WaypointData,
+        submission: &ProofSubmission,
+    ) -> Result<ProofVerdict, ProofError> {
+        let answer = submission
+            .answer
+            .as_deref()
+            .ok_or(ProofError::MissingSubmission("an answer"))?;
+
+        if matches_answer(answer, self.accepted_answers, self.max_typos) {
+            Ok(ProofVerdict::Accepted)
+        } else {
+            Ok(ProofVerdict::rejected("That is not the right answer"))
+        }
+    }
+}
+
+pub struct MultipleChoiceVerifier<'a> {
+    correct: &'a [usize],
+}
+
+#[async_trait]
+impl ProofVerifier for MultipleChoiceVerifier<'_> {
+    async fn verify(
+        &self,
+        _waypoint: &WaypointData,
+        submission: &ProofSubmission,
+    ) -> Result<ProofVerdict, ProofError> {
+        let choice = submission
+            .choice
+            .ok_or(ProofError::MissingSubmission("a choice"))?;
+
+        if self.correct.contains(&choice) {
+            Ok(ProofVerdict::Accepted)
+        } else {
+            Ok(ProofVerdict::rejected("That is not the right option"))
+        }
+    }
+}
+
+/// The location was validated at check-in, so there is nothing left to check
+pub struct LocationOnlyVerifier;
+
+#[async_trait]
+impl ProofVerifier for LocationOnlyVerifier {
+    async fn verify(
+        &self,
+        _waypoint: &WaypointData,
+        _submission: &ProofSubmission,
+    ) -> Result<ProofVerdict, ProofError> {
+        Ok(ProofVerdict::Accepted)
+    }
+}
+
+/// Whether an answer matches one of the accepted answers once both are normalized, allowing
+/// `max_typos` edits or one per five characters of the accepted answer
+fn matches_answer(answer: &str, accepted_answers: &[String], max_typos: Option<usize>) -> bool {
+    let answer = normalize_answer(answer);
+    if answer.is_empty() {
+        return false;
+    }
+
+    accepted_answers.iter().any(|accepted| {
+        let accepted = normalize_answer(accepted);
+        let allowed = max_typos.unwrap_or(accepted.chars().count() / 5);
+        !accepted.is_empty() && edit_distance(&answer, &accepted) <= allowed
+    })
+}
+
+/// Lowercase, drop punctuation and collapse whitespace
+fn normalize_answer(answer: &str) -> String {
+    answer
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Levenshtein distance in characters
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answer_matching() {
+        let accepted = vec!["The Clock Tower".to_string(), "Big Ben".to_string()];
+
+        assert!(matches_answer("the clock-tower!", &accepted, None));
+        assert!(matches_answer("  BIG   ben ", &accepted, None));
+        // Fifteen characters allow three typos by default
+        assert!(matches_answer("the clok towr", &accepted, None));
+        assert!(!matches_answer("the clok towr", &accepted, Some(1)));
+        // Seven characters allow a single typo
+        assert!(matches_answer("big bet", &accepted, None));
+        assert!(!matches_answer("bug bet", &accepted, None));
+        assert!(!matches_answer("...", &accepted, None));
+
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[tokio::test]
+    async fn test_verifiers_dispatch_on_proof_kind() {
+        use crate::services::location_service::GeoLocation;
+
+        let waypoint = WaypointData {
+            waypoint_id: None,
+            waypoint_sequence: 1,
+            location: GeoLocation { lat: 1.0, lon: 1.0 },
+            radius_meters: 50.0,
+            waypoint_clue: "Where the stickers are".to_string(),
+            hints: vec![],
+            waypoint_time_minutes: None,
+            image_subject: String::new(),
+            proof: ProofKind::LocationOnly,
+            bonus: None,
+            created_at: None,
+        };
+        let code = |code: &str| ProofSubmission {
+            code: Some(code.to_string()),
+            ..Default::default()
+        };
+
+        let qr_code = ProofKind::QrCode {
+            secret: "sticker-42".to_string(),
+        };
+        let verifier = verifier_for(&qr_code).unwrap();
+        assert_eq!(
+            verifier
+                .verify(&waypoint, &code("sticker-42\n"))
+                .await
+                .unwrap(),
+            ProofVerdict::Accepted
+        );
+        assert_eq!(
+            verifier
+                .verify(&waypoint, &code("sticker-41"))
+                .await
+                .unwrap()
+                .resolution(),
+            "rejected"
+        );
+        assert!(matches!(
+            verifier
+                .verify(&waypoint, &ProofSubmission::default())
+                .await,
+            Err(ProofError::MissingSubmission(_))
+        ));
+
+        let multiple_choice = ProofKind::MultipleChoice {
+            options: vec!["Red".to_string(), "Green".to_string(), "Blue".to_string()],
+            correct: vec![2],
+        };
+        let verifier = verifier_for(&multiple_choice).unwrap();
+        let choice = |choice| ProofSubmission {
+            choice: Some(choice),
+            ..Default::default()
+        };
+        assert_eq!(
+            verifier.verify(&waypoint, &choice(2)).await.unwrap(),
+            ProofVerdict::Accepted
+        );
+        assert_ne!(
+            verifier.verify(&waypoint, &choice(5)).await.unwrap(),
+            ProofVerdict::Accepted
+        );
+
+        let verifier = verifier_for(&waypoint.proof).unwrap();
+        assert_eq!(
+            verifier
+                .verify(&waypoint, &ProofSubmission::default())
+                .await
+                .unwrap(),
+            ProofVerdict::Accepted
+        );
+        assert!(verifier_for(&ProofKind::Image).is_none());
+    }
+}
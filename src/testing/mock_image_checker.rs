@@ -0,0 +1,332 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+/// Header on `/validate` requests that picks the outcome, taking precedence over rules
+pub const OUTCOME_HEADER: &str = "x-mock-outcome";
+
+/// What the mock does with a submitted image. Written as `accept`, `reject` or
+/// `reject:reason|other reason`, `uncertain[:reason|reason]`, `slow` or `slow:<polls>`, `fail`
+/// and `error:<status>`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MockOutcome {
+    Accept,
+    Reject(Vec<String>),
+    /// Completed without telling either way, which leaves the photo to a moderator
+    Uncertain(Vec<String>),
+    /// `in_progress` for this many status polls, then accepted
+    Slow(u32),
+    /// Processing fails, the status turns `failed` and there are no results
+    Fail,
+    /// `/validate` itself answers with this HTTP status
+    Error(u16),
+}
+
+impl FromStr for MockOutcome {
+    type Err = String;
+
+    fn from_str(outcome: &str) -> Result<Self, Self::Err> {
+        let (kind, argument) = match outcome.trim().split_once(':') {
+            Some((kind, argument)) => (kind, Some(argument)),
+            None => (outcome.trim(), None),
+        };
+
+        match (kind.to_ascii_lowercase().as_str(), argument) {
+            ("accept", None) => Ok(MockOutcome::Accept),
+            ("reject", None) => Ok(MockOutcome::Reject(vec![
+                "The image does not show the expected subject".to_string(),
+            ])),
+            ("reject", Some(reasons)) => Ok(MockOutcome::Reject(
+                reasons.split('|').map(|r| r.trim().to_string()).collect(),
+            )),
+            ("uncertain", None) => Ok(MockOutcome::Uncertain(vec![
+                "The subject is partly hidden".to_string()
+            ])),
+            ("uncertain", Some(reasons)) => Ok(MockOutcome::Uncertain(
+                reasons.split('|').map(|r| r.trim().to_string()).collect(),
+            )),
+            ("slow", None) => Ok(MockOutcome::Slow(3)),
+            ("slow", Some(polls)) => polls
+                .parse()
+                .map(MockOutcome::Slow)
+                .map_err(|_| format!("Invalid number of polls: {polls}")),
+            ("fail", None) => Ok(MockOutcome::Fail),
+            ("error", Some(status)) => status
+                .parse::<u16>()
+                .ok()
+                .filter(|status| StatusCode::from_u16(*status).is_ok())
+                .map(MockOutcome::Error)
+                .ok_or_else(|| format!("Invalid HTTP status: {status}")),
+            _ => Err(format!("Unknown outcome: {outcome}")),
+        }
+    }
+}
+
+struct MockJob {
+    outcome: MockOutcome,
+    polls: u32,
+}
+
+struct MockState {
+    rules: Vec<(String, MockOutcome)>,
+    default: MockOutcome,
+    jobs: Mutex<HashMap<String, MockJob>>,
+}
+
+/// A stand-in for the image checker speaking the protocol `ImageService` uses, for local
+/// runs and tests. The outcome for a submission comes from the `x-mock-outcome` header, else
+/// the first rule whose pattern occurs in the image file name or the expected content, else
+/// the default.
+pub struct MockImageChecker {
+    state: Arc<MockState>,
+}
+
+impl MockImageChecker {
+    pub fn new(default: MockOutcome) -> Self {
+        Self {
+            state: Arc::new(MockState {
+                rules: Vec::new(),
+                default,
+                jobs: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+
+    /// Use `outcome` for images whose file name or expected content contains `pattern`.
+    /// Stored proof images are named after the SHA-256 of their content.
+    pub fn with_rule(mut self, pattern: impl Into<String>, outcome: MockOutcome) -> Self {
+        Arc::get_mut(&mut self.state)
+            .expect("rules are added before the mock is served")
+            .rules
+            .push((pattern.into(), outcome));
+        self
+    }
+
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/validate", post(validate))
+            .route("/status/:processing_id", get(status))
+            .route("/results/:processing_id", get(results))
+            .with_state(self.state.clone())
+    }
+
+    /// Serve the mock on an ephemeral local port and return its base URL
+    pub async fn spawn(self) -> std::io::Result<String> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let base_url = format!("http://{}", listener.local_addr()?);
+        let router = self.router();
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        Ok(base_url)
+    }
+}
+
+impl MockState {
+    fn outcome_for(&self, headers: &HeaderMap, request: &Value) -> Result<MockOutcome, String> {
+        if let Some(outcome) = headers.get(OUTCOME_HEADER) {
+            return outcome
+                .to_str()
+                .map_err(|_| "Invalid outcome header".to_string())?
+                .parse();
+        }
+
+        let file_name = request["image-path"]
+            .as_str()
+            .and_then(|path| path.split('?').next())
+            .and_then(|path| path.rsplit('/').next())
+            .unwrap_or_default();
+        let content = request["analysis-request"]["content"]
+            .as_str()
+            .unwrap_or_default();
+
+        Ok(self
+            .rules
+            .iter()
+            .find(|(pattern, _)| file_name.contains(pattern) || content.contains(pattern))
+            .map_or(&self.default, |(_, outcome)| outcome)
+            .clone())
+    }
+}
+
+fn error(status: StatusCode, message: &str) -> Response {
+    (status, Json(json!({ "error": message }))).into_response()
+}
+
+/// POST /validate
+async fn validate(
+    State(state): State<Arc<MockState>>,
+    headers: HeaderMap,
+    Json(request): Json<Value>,
+) -> Response {
+    let Some(processing_id) = request["processing-id"].as_str() else {
+        return error(StatusCode::BAD_REQUEST, "processing-id is required");
+    };
+    let outcome = match state.outcome_for(&headers, &request) {
+        Ok(outcome) => outcome,
+        Err(e) => return error(StatusCode::BAD_REQUEST, &e),
+    };
+    tracing::info!("Mock image checker got {} as {:?}", processing_id, outcome);
+
+    if let MockOutcome::Error(status) = outcome {
+        let status = StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        return error(status, "Scripted error");
+    }
+
+    state
+        .jobs
+        .lock()
+        .unwrap()
+        .insert(processing_id.to_string(), MockJob { outcome, polls: 0 });
+
+    (StatusCode::ACCEPTED, Json(json!({ "status": "accepted" }))).into_response()
+}
+
+/// GET /status/:processing_id
+async fn status(
+    State(state): State<Arc<MockState>>,
+    Path(processing_id): Path<String>,
+) -> Response {
+    let mut jobs = state.jobs.lock().unwrap();
+    let Some(job) = jobs.get_mut(&processing_id) else {
+        return error(StatusCode::NOT_FOUND, "Unknown processing id");
+    };
+    job.polls += 1;
+
+    let status = match job.outcome {
+        MockOutcome::Slow(polls) if job.polls <= polls => "in_progress",
+        MockOutcome::Fail => "failed",
+        _ => "completed",
+    };
+
+    Json(json!({ "status": status })).into_response()
+}
+
+/// GET /results/:processing_id
+async fn results(
+    State(state): State<Arc<MockState>>,
+    Path(processing_id): Path<String>,
+) -> Response {
+    let jobs = state.jobs.lock().unwrap();
+    let Some(job) = jobs.get(&processing_id) else {
+        return error(StatusCode::NOT_FOUND, "Unknown processing id");
+    };
+
+    match &job.outcome {
+        MockOutcome::Slow(polls) if job.polls <= *polls => {
+            error(StatusCode::CONFLICT, "Still in progress")
+        }
+        MockOutcome::Accept | MockOutcome::Slow(_) => {
+            Json(json!({ "resolution": "accepted", "reasons": null })).into_response()
+        }
+        MockOutcome::Reject(reasons) => {
+            Json(json!({ "resolution": "rejected", "reasons": reasons })).into_response()
+        }
+        MockOutcome::Uncertain(reasons) => {
+            Json(json!({ "resolution": "uncertain", "reasons": reasons })).into_response()
+        }
+        MockOutcome::Fail | MockOutcome::Error(_) => {
+            error(StatusCode::CONFLICT, "Processing failed")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::{ImageCheckerPolicy, ImageService, LocalImageStore};
+
+    #[test]
+    fn test_parse_outcomes() {
+        assert_eq!("accept".parse(), Ok(MockOutcome::Accept));
+        assert_eq!(
+            "reject:Too dark| No bench ".parse(),
+            Ok(MockOutcome::Reject(vec![
+                "Too dark".to_string(),
+                "No bench".to_string()
+            ]))
+        );
+        assert_eq!(
+            "uncertain:Too far away".parse(),
+            Ok(MockOutcome::Uncertain(vec!["Too far away".to_string()]))
+        );
+        assert_eq!("SLOW:5".parse(), Ok(MockOutcome::Slow(5)));
+        assert_eq!("fail".parse(), Ok(MockOutcome::Fail));
+        assert_eq!("error:503".parse(), Ok(MockOutcome::Error(503)));
+        assert!("error:42".parse::<MockOutcome>().is_err());
+        assert!("maybe".parse::<MockOutcome>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_scripted_outcomes() {
+        let base_url = MockImageChecker::new(MockOutcome::Accept)
+            .with_rule("deadbeef", MockOutcome::Reject(vec!["Blurry".to_string()]))
+            .with_rule("Clock tower", MockOutcome::Slow(1))
+            .with_rule("Fountain", MockOutcome::Fail)
+            .spawn()
+            .await
+            .unwrap();
+        let service = ImageService::new(
+            base_url.clone(),
+            Arc::new(LocalImageStore::new("/var/images")),
+            ImageCheckerPolicy {
+                max_retries: 0,
+                ..Default::default()
+            },
+        );
+
+        // Rules match the stored file name or the expected content
+        service
+            .submit("p1", "1/a/deadbeef.jpg", "Bench", None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            service.check_status("p1").await.unwrap().status,
+            "completed"
+        );
+        let result = service.get_results("p1").await.unwrap();
+        assert_eq!(result.resolution, "rejected");
+        assert_eq!(result.reasons.unwrap(), vec!["Blurry"]);
+
+        service
+            .submit("p2", "1/a/cafe.jpg", "Clock tower", None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            service.check_status("p2").await.unwrap().status,
+            "in_progress"
+        );
+        assert_eq!(
+            service.check_status("p2").await.unwrap().status,
+            "completed"
+        );
+        assert_eq!(
+            service.get_results("p2").await.unwrap().resolution,
+            "accepted"
+        );
+
+        service
+            .submit("p3", "1/a/cafe.jpg", "Fountain", None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(service.check_status("p3").await.unwrap().status, "failed");
+
+        // The header wins over rules and the default
+        let response = reqwest::Client::new()
+            .post(format!("{base_url}/validate"))
+            .header(OUTCOME_HEADER, "error:503")
+            .json(&json!({ "processing-id": "p4", "image-path": "deadbeef.jpg" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 503);
+        assert!(service.check_status("p4").await.is_err());
+    }
+}
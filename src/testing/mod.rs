@@ -0,0 +1,3 @@
+pub mod mock_image_checker;
+
+pub use mock_image_checker::{MockImageChecker, MockOutcome};